@@ -0,0 +1,36 @@
+-- Esquema inicial compatible con SQLite y PostgreSQL
+-- (los booleanos se guardan como BIGINT 0/1: el driver Any de sqlx no soporta BOOLEAN en SQLite)
+
+CREATE TABLE empleados (
+    id TEXT PRIMARY KEY,
+    nombre TEXT NOT NULL,
+    departamento TEXT NOT NULL,
+    email TEXT NOT NULL,
+    es_admin BIGINT NOT NULL DEFAULT 0,
+    fecha_ingreso TEXT NOT NULL,
+    password_hash TEXT
+);
+
+CREATE INDEX idx_empleados_email ON empleados (email);
+
+CREATE TABLE solicitudes (
+    id TEXT PRIMARY KEY,
+    empleado_id TEXT NOT NULL,
+    empleado_nombre TEXT NOT NULL,
+    fecha_inicio TEXT NOT NULL,
+    fecha_fin TEXT NOT NULL,
+    estado TEXT NOT NULL,
+    dias_solicitados BIGINT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_solicitudes_empleado ON solicitudes (empleado_id);
+
+CREATE TABLE sesiones (
+    session_id TEXT PRIMARY KEY,
+    data TEXT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_sesiones_expires_at ON sesiones (expires_at);
@@ -0,0 +1,7 @@
+-- Emails únicos sin distinguir mayúsculas: se guardan normalizados (trim + minúsculas)
+
+UPDATE empleados SET email = LOWER(TRIM(email));
+
+DROP INDEX idx_empleados_email;
+
+CREATE UNIQUE INDEX ux_empleados_email ON empleados (email);
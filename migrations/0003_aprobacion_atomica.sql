@@ -0,0 +1,10 @@
+-- Control de concurrencia en los cambios de estado y saldo de días tomados por empleado y año
+
+ALTER TABLE solicitudes ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE saldos (
+    empleado_id TEXT NOT NULL,
+    ano BIGINT NOT NULL,
+    dias_tomados BIGINT NOT NULL,
+    PRIMARY KEY (empleado_id, ano)
+);
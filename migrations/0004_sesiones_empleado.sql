@@ -0,0 +1,8 @@
+-- Sesiones vinculadas al empleado, con datos del dispositivo para "Mis sesiones"
+
+ALTER TABLE sesiones ADD COLUMN empleado_id TEXT;
+ALTER TABLE sesiones ADD COLUMN ultima_actividad TEXT;
+ALTER TABLE sesiones ADD COLUMN ip TEXT;
+ALTER TABLE sesiones ADD COLUMN user_agent TEXT;
+
+CREATE INDEX idx_sesiones_empleado ON sesiones (empleado_id);
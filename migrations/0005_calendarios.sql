@@ -0,0 +1,12 @@
+-- Calendarios de días inhábiles de la empresa y ubicación (oficina) de cada empleado.
+-- departamentos, ubicaciones y dias se guardan como JSON.
+
+ALTER TABLE empleados ADD COLUMN ubicacion TEXT;
+
+CREATE TABLE calendarios (
+    id TEXT PRIMARY KEY,
+    nombre TEXT NOT NULL,
+    departamentos TEXT NOT NULL,
+    ubicaciones TEXT NOT NULL,
+    dias TEXT NOT NULL
+);
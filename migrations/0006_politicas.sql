@@ -0,0 +1,12 @@
+-- Políticas de días de vacaciones por antigüedad (de la empresa o reformas futuras);
+-- las de la LFT vigentes viven en el código. tabla se guarda como JSON.
+
+CREATE TABLE politicas (
+    id TEXT PRIMARY KEY,
+    nombre TEXT NOT NULL,
+    ambito TEXT NOT NULL,
+    vigente_desde TEXT NOT NULL,
+    tabla TEXT NOT NULL,
+    incremento INTEGER NOT NULL,
+    cada_anos INTEGER NOT NULL
+);
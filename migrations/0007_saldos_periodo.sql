@@ -0,0 +1,12 @@
+-- Los saldos de días tomados pasan de año calendario a periodo de servicio
+-- (de aniversario a aniversario). Los saldos son un derivado de las solicitudes
+-- aprobadas, así que no se convierten: se reconstruyen al leerse.
+
+DROP TABLE saldos;
+
+CREATE TABLE saldos_periodo (
+    empleado_id TEXT NOT NULL,
+    periodo BIGINT NOT NULL,
+    dias_tomados BIGINT NOT NULL,
+    PRIMARY KEY (empleado_id, periodo)
+);
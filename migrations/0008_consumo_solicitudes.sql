@@ -0,0 +1,4 @@
+-- Días que cada solicitud aprobada descontó de cada periodo de servicio, como JSON
+-- [{"periodo": n, "dias": d}]. Las solicitudes anteriores se imputan a su fecha de inicio.
+
+ALTER TABLE solicitudes ADD COLUMN consumo TEXT NOT NULL DEFAULT '[]';
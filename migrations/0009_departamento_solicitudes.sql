@@ -0,0 +1,9 @@
+-- Nombre y departamento del empleado copiados a cada solicitud desde su registro (antes el
+-- nombre lo enviaba el cliente). Las solicitudes existentes se corrigen con el registro actual.
+
+ALTER TABLE solicitudes ADD COLUMN departamento TEXT NOT NULL DEFAULT '';
+
+UPDATE solicitudes
+SET empleado_nombre = (SELECT nombre FROM empleados WHERE empleados.id = solicitudes.empleado_id),
+    departamento = (SELECT departamento FROM empleados WHERE empleados.id = solicitudes.empleado_id)
+WHERE EXISTS (SELECT 1 FROM empleados WHERE empleados.id = solicitudes.empleado_id);
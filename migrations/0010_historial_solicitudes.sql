@@ -0,0 +1,5 @@
+-- Historial de cambios de estado de cada solicitud, como JSON
+-- [{"anterior": "pendiente", "estado": "aprobada", "actor": "1", "fecha": "...", "motivo": "..."}].
+-- Las solicitudes anteriores empiezan con el historial vacío.
+
+ALTER TABLE solicitudes ADD COLUMN historial TEXT NOT NULL DEFAULT '[]';
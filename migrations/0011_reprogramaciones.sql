@@ -0,0 +1,3 @@
+-- ID de la solicitud que cada solicitud reprograma (NULL si no es una reprogramación)
+
+ALTER TABLE solicitudes ADD COLUMN reprograma TEXT;
@@ -0,0 +1,6 @@
+-- Quién aprobó o rechazó cada solicitud, cuándo y con qué comentario
+-- (NULL en las pendientes y en las resueltas antes de registrarlo)
+
+ALTER TABLE solicitudes ADD COLUMN resuelto_por TEXT;
+ALTER TABLE solicitudes ADD COLUMN resuelto_at TEXT;
+ALTER TABLE solicitudes ADD COLUMN comentario TEXT;
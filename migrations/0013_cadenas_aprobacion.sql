@@ -0,0 +1,15 @@
+-- Jefe directo de cada empleado, cadenas de aprobación por departamento y, en cada
+-- solicitud, los pasos de su cadena y la decisión de cada paso (JSON). Las solicitudes
+-- existentes quedan sin pasos: las aprueba cualquier administrador, como antes.
+
+ALTER TABLE empleados ADD COLUMN jefe_id TEXT;
+
+CREATE TABLE cadenas (
+    id TEXT PRIMARY KEY,
+    nombre TEXT NOT NULL,
+    departamento TEXT,
+    pasos TEXT NOT NULL
+);
+
+ALTER TABLE solicitudes ADD COLUMN pasos TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE solicitudes ADD COLUMN aprobaciones TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,13 @@
+-- Delegaciones de la facultad de aprobación entre empleados por rango de fechas
+-- (desde y hasta incluidos, formato YYYY-MM-DD)
+
+CREATE TABLE delegaciones (
+    id TEXT PRIMARY KEY,
+    delegante_id TEXT NOT NULL,
+    delegado_id TEXT NOT NULL,
+    desde TEXT NOT NULL,
+    hasta TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_delegaciones_delegado ON delegaciones (delegado_id);
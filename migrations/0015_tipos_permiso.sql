@@ -0,0 +1,5 @@
+-- Tipo de permiso de cada solicitud y referencia de su documento. Las solicitudes
+-- existentes son de vacaciones.
+
+ALTER TABLE solicitudes ADD COLUMN tipo_permiso TEXT NOT NULL DEFAULT 'vacaciones';
+ALTER TABLE solicitudes ADD COLUMN documento TEXT;
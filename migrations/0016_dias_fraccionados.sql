@@ -0,0 +1,10 @@
+-- Medios días y solicitudes por horas. Los días pasan a guardarse en milésimos de día
+-- (punto fijo) para sumar fracciones sin redondeos: 1 día = 1000, medio día = 500 y una
+-- hora de una jornada de 8 = 125. El consumo en JSON sigue expresado en días.
+
+UPDATE solicitudes SET dias_solicitados = dias_solicitados * 1000;
+UPDATE saldos_periodo SET dias_tomados = dias_tomados * 1000;
+
+ALTER TABLE solicitudes ADD COLUMN medio_dia_inicio TEXT;
+ALTER TABLE solicitudes ADD COLUMN medio_dia_fin TEXT;
+ALTER TABLE solicitudes ADD COLUMN horas BIGINT;
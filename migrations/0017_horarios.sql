@@ -0,0 +1,10 @@
+-- Horarios de trabajo (semanales o rotativos, patrón en JSON) y el horario asignado a
+-- cada empleado. Los empleados existentes quedan sin horario: lunes a viernes, como antes.
+
+CREATE TABLE horarios (
+    id TEXT PRIMARY KEY,
+    nombre TEXT NOT NULL,
+    patron TEXT NOT NULL
+);
+
+ALTER TABLE empleados ADD COLUMN horario_id TEXT;
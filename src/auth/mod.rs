@@ -1,16 +1,17 @@
 use axum::{
+    RequestPartsExt,
     extract::{FromRequestParts, State},
-    http::{request::Parts, StatusCode},
+    http::{StatusCode, request::Parts},
     response::{IntoResponse, Redirect, Response},
-    RequestPartsExt,
 };
-use std::future::Future;
+use chrono::Utc;
 use tower_sessions::Session;
 
 use crate::{
-    db::DynamoDBClient,
+    db::Database,
     models::empleado::Empleado,
-    services::empleado::EmpleadoService,
+    services::{delegacion::DelegacionService, empleado::EmpleadoService},
+    session,
 };
 
 /// Usuario autenticado extraído de la sesión
@@ -25,6 +26,14 @@ pub struct AdminUser {
     pub empleado: Empleado,
 }
 
+/// Usuario que decide pasos de aprobación (autenticado + las delegaciones que tiene
+/// vigentes hoy: IDs de los empleados en cuyo nombre puede aprobar o rechazar)
+#[derive(Debug, Clone)]
+pub struct AprobadorUser {
+    pub empleado: Empleado,
+    pub delegantes: Vec<String>,
+}
+
 /// Errores de autenticación
 #[derive(Debug)]
 pub enum AuthError {
@@ -57,41 +66,61 @@ impl IntoResponse for AuthError {
 impl<S> FromRequestParts<S> for AuthUser
 where
     S: Send + Sync,
-    DynamoDBClient: axum::extract::FromRef<S>,
+    Database: axum::extract::FromRef<S>,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Extraer sesión
+        let session = parts
+            .extract::<Session>()
+            .await
+            .map_err(|_| AuthError::SessionError("Failed to get session".to_string()))?;
+
+        // Obtener empleado_id de la sesión
+        let empleado_id: String = session
+            .get(session::CLAVE_EMPLEADO_ID)
+            .await
+            .map_err(|e| AuthError::SessionError(format!("Failed to read session: {}", e)))?
+            .ok_or(AuthError::Unauthenticated)?;
+
+        // Extraer el backend de almacenamiento del estado
+        let State(db): State<Database> = State::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AuthError::DatabaseError("Failed to get database client".to_string()))?;
+
+        // Cargar empleado desde DB
+        let service = EmpleadoService::new(db);
+        let empleado = service
+            .obtener_empleado(&empleado_id)
+            .await
+            .map_err(|_| AuthError::Unauthenticated)?;
+
+        // Última actividad para "Mis sesiones"
+        session::registrar_actividad(&session)
+            .await
+            .map_err(|e| AuthError::SessionError(format!("Failed to update session: {}", e)))?;
+
+        Ok(AuthUser { empleado })
+    }
+}
+
+/// En las páginas públicas, `Option<AuthUser>` es `None` para quien no inició sesión
+impl<S> axum::extract::OptionalFromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+    Database: axum::extract::FromRef<S>,
 {
     type Rejection = AuthError;
 
-    fn from_request_parts(
+    async fn from_request_parts(
         parts: &mut Parts,
         state: &S,
-    ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
-        async move {
-            // Extraer sesión
-            let session = parts
-                .extract::<Session>()
-                .await
-                .map_err(|_| AuthError::SessionError("Failed to get session".to_string()))?;
-
-            // Obtener empleado_id de la sesión
-            let empleado_id: String = session
-                .get("empleado_id")
-                .await
-                .map_err(|e| AuthError::SessionError(format!("Failed to read session: {}", e)))?
-                .ok_or(AuthError::Unauthenticated)?;
-
-            // Extraer DynamoDB client del estado
-            let State(db_client): State<DynamoDBClient> = State::from_request_parts(parts, state)
-                .await
-                .map_err(|_| AuthError::DatabaseError("Failed to get database client".to_string()))?;
-
-            // Cargar empleado desde DB
-            let service = EmpleadoService::new(db_client);
-            let empleado = service
-                .obtener_empleado(&empleado_id)
-                .await
-                .map_err(|_| AuthError::Unauthenticated)?;
-
-            Ok(AuthUser { empleado })
+    ) -> Result<Option<Self>, Self::Rejection> {
+        match <AuthUser as FromRequestParts<S>>::from_request_parts(parts, state).await {
+            Ok(auth_user) => Ok(Some(auth_user)),
+            Err(AuthError::Unauthenticated) => Ok(None),
+            Err(e) => Err(e),
         }
     }
 }
@@ -99,26 +128,49 @@ where
 impl<S> FromRequestParts<S> for AdminUser
 where
     S: Send + Sync,
-    DynamoDBClient: axum::extract::FromRef<S>,
+    Database: axum::extract::FromRef<S>,
 {
     type Rejection = AuthError;
 
-    fn from_request_parts(
-        parts: &mut Parts,
-        state: &S,
-    ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
-        async move {
-            // Primero verificar que esté autenticado
-            let auth_user = AuthUser::from_request_parts(parts, state).await?;
-
-            // Verificar que sea admin
-            if !auth_user.empleado.es_admin {
-                return Err(AuthError::Forbidden);
-            }
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Primero verificar que esté autenticado
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
 
-            Ok(AdminUser {
-                empleado: auth_user.empleado,
-            })
+        // Verificar que sea admin
+        if !auth_user.empleado.es_admin {
+            return Err(AuthError::Forbidden);
         }
+
+        Ok(AdminUser {
+            empleado: auth_user.empleado,
+        })
+    }
+}
+
+impl<S> FromRequestParts<S> for AprobadorUser
+where
+    S: Send + Sync,
+    Database: axum::extract::FromRef<S>,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // Primero verificar que esté autenticado
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
+
+        let State(db): State<Database> = State::from_request_parts(parts, state)
+            .await
+            .map_err(|_| AuthError::DatabaseError("Failed to get database client".to_string()))?;
+
+        // Delegaciones que recibió y están vigentes hoy
+        let delegantes = DelegacionService::new(db)
+            .delegantes_vigentes(&auth_user.empleado.id, Utc::now().date_naive())
+            .await
+            .map_err(|e| AuthError::DatabaseError(format!("Failed to load delegations: {}", e)))?;
+
+        Ok(AprobadorUser {
+            empleado: auth_user.empleado,
+            delegantes,
+        })
     }
 }
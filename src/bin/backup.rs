@@ -0,0 +1,93 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use vacaciones_app::{config::Config, db, services::RespaldoService};
+
+const USO: &str = "Uso:
+  cargo run --bin backup -- exportar <archivo.jsonl> [--sesiones]
+  cargo run --bin backup -- restaurar <archivo.jsonl> [--dry-run]";
+
+/// Exporta todos los datos a un archivo JSON Lines versionado o los restaura
+/// en una base vacía. La restauración valida el archivo completo y reporta
+/// conflictos con el destino antes de escribir; si hay alguno no escribe nada.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (comando, archivo) = match (args.first(), args.get(1)) {
+        (Some(comando), Some(archivo)) => (comando.as_str(), archivo.as_str()),
+        _ => {
+            eprintln!("{}", USO);
+            std::process::exit(2);
+        }
+    };
+    let opcion = |nombre: &str| args.iter().skip(2).any(|a| a == nombre);
+
+    let config = Config::from_env().expect("Failed to load configuration");
+    let db = db::connect(&config).await?;
+    let servicio = RespaldoService::new(db);
+
+    match comando {
+        "exportar" | "export" => {
+            println!("=== Exportar respaldo ===\n");
+            let salida = BufWriter::new(File::create(archivo)?);
+            let resumen = servicio.exportar(salida, opcion("--sesiones")).await?;
+
+            println!("Empleados:   {}", resumen.empleados);
+            println!("Solicitudes: {}", resumen.solicitudes);
+            println!("Calendarios: {}", resumen.calendarios);
+            println!("Políticas:   {}", resumen.politicas);
+            println!("Cadenas:     {}", resumen.cadenas);
+            println!("Delegaciones: {}", resumen.delegaciones);
+            println!("Horarios:    {}", resumen.horarios);
+            println!("Sesiones:    {}", resumen.sesiones);
+            println!("\n✓ Respaldo escrito en {}", archivo);
+        }
+        "restaurar" | "restore" => {
+            let dry_run = opcion("--dry-run");
+            println!(
+                "=== Restaurar respaldo{} ===\n",
+                if dry_run { " (dry-run)" } else { "" }
+            );
+            let entrada = BufReader::new(File::open(archivo)?);
+            let informe = servicio.restaurar(entrada, dry_run).await?;
+
+            println!("Empleados:   {}", informe.resumen.empleados);
+            println!("Solicitudes: {}", informe.resumen.solicitudes);
+            println!("Calendarios: {}", informe.resumen.calendarios);
+            println!("Políticas:   {}", informe.resumen.politicas);
+            println!("Cadenas:     {}", informe.resumen.cadenas);
+            println!("Delegaciones: {}", informe.resumen.delegaciones);
+            println!("Horarios:    {}", informe.resumen.horarios);
+            println!("Sesiones:    {}", informe.resumen.sesiones);
+
+            if !informe.errores.is_empty() {
+                println!("\n✗ Errores en el archivo:");
+                for error in &informe.errores {
+                    println!("  - {}", error);
+                }
+            }
+            if !informe.conflictos.is_empty() {
+                println!("\n✗ Conflictos con los datos existentes:");
+                for conflicto in &informe.conflictos {
+                    println!("  - {}", conflicto);
+                }
+            }
+
+            if informe.aplicado {
+                println!("\n✓ Respaldo restaurado");
+            } else if informe.es_valido() {
+                println!(
+                    "\n✓ El respaldo es válido (sin cambios: ejecuta sin --dry-run para aplicarlo)"
+                );
+            } else {
+                println!("\nNo se escribió nada.");
+                std::process::exit(1);
+            }
+        }
+        _ => {
+            eprintln!("{}", USO);
+            std::process::exit(2);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use vacaciones_app::{
+    config::{Config, DatabaseBackend},
+    db::{
+        self, DynamoDBClient,
+        esquema::{MIGRACIONES, ResumenRelleno, VERSION_ESQUEMA},
+    },
+};
+
+/// Crea o actualiza la tabla de DynamoDB (o aplica las migraciones SQL).
+///
+/// Uso: `cargo run --bin migrate [-- --estado | --dry-run | --rellenar-emails]`
+/// Con `--rellenar-emails` además crea los items `EMAIL#` que falten (p. ej. de empleados
+/// escritos en la tabla por fuera de la aplicación), aunque el esquema ya esté al día.
+/// Con DynamoDB Local basta con definir `AWS_ENDPOINT_URL=http://localhost:8000`.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("=== Migración del esquema ===\n");
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let solo_estado = args.iter().any(|a| a == "--estado" || a == "--status");
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let rellenar_emails = args.iter().any(|a| a == "--rellenar-emails");
+
+    let config = Config::from_env().expect("Failed to load configuration");
+
+    match config.database_backend {
+        DatabaseBackend::DynamoDB => {}
+        DatabaseBackend::Sqlite | DatabaseBackend::Postgres => {
+            // Las migraciones SQL están embebidas y se aplican al conectar
+            db::connect(&config).await?;
+            println!(
+                "✓ Migraciones SQL aplicadas ({:?})",
+                config.database_backend
+            );
+            return Ok(());
+        }
+        DatabaseBackend::Memory => {
+            println!("El backend en memoria no requiere migraciones.");
+            return Ok(());
+        }
+    }
+
+    let client = DynamoDBClient::new(&config).await;
+    println!("Tabla: {}", client.table_name);
+
+    let version = client.version_esquema().await?;
+    match version {
+        None => println!("Versión actual: la tabla no existe"),
+        Some(v) => println!("Versión actual: {}", v),
+    }
+    println!("Versión esperada: {}\n", VERSION_ESQUEMA);
+
+    let pendientes: Vec<_> = MIGRACIONES
+        .iter()
+        .filter(|(v, _)| *v > version.unwrap_or(0))
+        .collect();
+
+    if pendientes.is_empty() {
+        println!("✓ El esquema está al día");
+        if rellenar_emails && !(solo_estado || dry_run) {
+            println!("\nRelleno de items EMAIL#:");
+            imprimir_emails(&client.rellenar_emails().await?);
+        }
+        return Ok(());
+    }
+
+    println!("Migraciones pendientes:");
+    for (v, descripcion) in &pendientes {
+        println!("  {}. {}", v, descripcion);
+    }
+
+    if solo_estado || dry_run {
+        println!("\n(sin cambios: ejecuta sin --estado/--dry-run para aplicarlas)");
+        return Ok(());
+    }
+
+    println!("\nAplicando...");
+    let (aplicadas, relleno) = client.migrar().await?;
+    for descripcion in aplicadas {
+        println!("✓ {}", descripcion);
+    }
+
+    // El paso 4 ya rellena los EMAIL#; si no se aplicó ahora, se rellenan aparte
+    match relleno {
+        Some(relleno) => {
+            println!("\nRelleno de atributos:");
+            println!(
+                "  Solicitudes con version agregada: {}",
+                relleno.solicitudes_versionadas
+            );
+            imprimir_emails(&relleno);
+        }
+        None if rellenar_emails => {
+            println!("\nRelleno de items EMAIL#:");
+            imprimir_emails(&client.rellenar_emails().await?);
+        }
+        None => {}
+    }
+
+    println!("\n✓ Esquema en la versión {}", VERSION_ESQUEMA);
+    Ok(())
+}
+
+fn imprimir_emails(relleno: &ResumenRelleno) {
+    println!(
+        "  Items EMAIL# creados o verificados: {}",
+        relleno.emails_indexados
+    );
+    if !relleno.emails_duplicados.is_empty() {
+        println!("  ✗ Emails repetidos entre empleados (corrígelos y vuelve a ejecutar):");
+        for email in &relleno.emails_duplicados {
+            println!("    - {}", email);
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use aws_sdk_dynamodb::types::AttributeValue;
 use std::io::{self, Write};
-use vacaciones_app::{config::Config, db::DynamoDBClient, services::auth::AuthService};
+use vacaciones_app::{
+    config::Config,
+    db,
+    services::{SesionService, auth::AuthService},
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -8,58 +11,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Cargar configuración
     let config = Config::from_env().expect("Failed to load configuration");
-    let db_client = DynamoDBClient::new(&config).await;
-    let auth_service = AuthService::new(db_client.clone());
+    let db = db::connect(&config).await?;
+    let auth_service = AuthService::new(db.clone());
+    let sesion_service = SesionService::new(db.clone());
 
     // Listar todos los empleados
     println!("Obteniendo lista de empleados...\n");
-    let result = db_client
-        .client
-        .scan()
-        .table_name(&db_client.table_name)
-        .filter_expression("tipo = :tipo")
-        .expression_attribute_values(":tipo", AttributeValue::S("empleado".to_string()))
-        .send()
-        .await?;
-
-    let items = result.items.unwrap_or_default();
-
-    if items.is_empty() {
+    let empleados = db.listar_empleados().await?;
+
+    if empleados.is_empty() {
         println!("No se encontraron empleados en la base de datos.");
         return Ok(());
     }
 
-    println!("Empleados encontrados: {}\n", items.len());
+    println!("Empleados encontrados: {}\n", empleados.len());
     println!("----------------------------------------");
 
-    for item in items {
-        // Extraer datos del empleado
-        let id = item
-            .get("id")
-            .and_then(|v| v.as_s().ok())
-            .map(|s| s.as_str())
-            .unwrap_or("unknown");
-        let nombre = item
-            .get("nombre")
-            .and_then(|v| v.as_s().ok())
-            .map(|s| s.as_str())
-            .unwrap_or("Sin nombre");
-        let email = item
-            .get("email")
-            .and_then(|v| v.as_s().ok())
-            .map(|s| s.as_str())
-            .unwrap_or("Sin email");
-        let es_admin = item
-            .get("es_admin")
-            .and_then(|v| v.as_bool().ok())
-            .copied()
-            .unwrap_or(false);
+    for empleado in empleados {
+        let id = empleado.id.as_str();
+        let nombre = empleado.nombre.as_str();
+        let email = empleado.email.as_str();
+        let es_admin = empleado.es_admin;
 
         // Verificar si ya tiene password
-        let tiene_password = item
-            .get("password_hash")
-            .and_then(|v| v.as_s().ok())
-            .is_some();
+        let tiene_password = empleado.password_hash.is_some();
 
         println!("\nEmpleado: {} ({})", nombre, email);
         println!("ID: {}", id);
@@ -126,6 +101,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             println!("✓ Contraseña configurada exitosamente");
                             println!("  Email: {}", email);
                             println!("  Password: {}", password);
+
+                            // Con la contraseña anterior pudo haber sesiones abiertas
+                            if tiene_password {
+                                match sesion_service.revocar_todas(id, None).await {
+                                    Ok(n) => println!("  Sesiones cerradas: {}", n),
+                                    Err(e) => {
+                                        println!("✗ No se pudieron cerrar sus sesiones: {}", e)
+                                    }
+                                }
+                            }
                             break;
                         }
                         Err(e) => {
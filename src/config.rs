@@ -1,24 +1,108 @@
 use std::env;
+use std::net::IpAddr;
+
+/// Backend de almacenamiento seleccionado con `DATABASE_BACKEND`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DatabaseBackend {
+    DynamoDB,
+    Sqlite,
+    Postgres,
+    Memory,
+}
+
+impl DatabaseBackend {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "dynamodb" => Ok(DatabaseBackend::DynamoDB),
+            "sqlite" => Ok(DatabaseBackend::Sqlite),
+            "postgres" | "postgresql" => Ok(DatabaseBackend::Postgres),
+            "memory" | "memoria" => Ok(DatabaseBackend::Memory),
+            otro => Err(format!(
+                "DATABASE_BACKEND inválido: '{}' (valores: dynamodb, sqlite, postgres, memory)",
+                otro
+            )),
+        }
+    }
+
+    /// URL por defecto cuando no se define `DATABASE_URL`
+    fn default_url(&self) -> Option<&'static str> {
+        match self {
+            DatabaseBackend::Sqlite => Some("sqlite://vacaciones.db?mode=rwc"),
+            _ => None,
+        }
+    }
+}
+
+/// Proxies de confianza (`TRUSTED_PROXIES`): solo de ellos se acepta `X-Forwarded-For`
+#[derive(Clone, Debug, Default)]
+pub struct ProxiesConfiables(pub Vec<IpAddr>);
+
+impl ProxiesConfiables {
+    fn parse(value: &str) -> Result<Self, String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty())
+            .map(|ip| {
+                ip.parse()
+                    .map_err(|_| format!("TRUSTED_PROXIES inválido: '{}' no es una IP", ip))
+            })
+            .collect::<Result<_, _>>()
+            .map(ProxiesConfiables)
+    }
+
+    /// IP del cliente: la de la conexión, salvo que esta venga de un proxy de confianza; en
+    /// ese caso, el último salto de `forwarded_for` que no sea otro proxy de confianza
+    pub fn ip_cliente(&self, conexion: IpAddr, forwarded_for: Option<&str>) -> IpAddr {
+        if !self.0.contains(&conexion) {
+            return conexion;
+        }
+        forwarded_for
+            .into_iter()
+            .flat_map(|v| v.rsplit(','))
+            .map_while(|ip| ip.trim().parse::<IpAddr>().ok())
+            .find(|ip| !self.0.contains(ip))
+            .unwrap_or(conexion)
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Config {
     pub server_host: String,
     pub server_port: u16,
+    pub database_backend: DatabaseBackend,
+    pub database_url: Option<String>,
     pub dynamodb_table_name: String,
     pub session_secret: String,
     pub session_ttl_days: i64,
+    pub session_cleanup_minutes: u64,
+    pub request_expiry_minutes: u64,
+    pub trusted_proxies: ProxiesConfiables,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, String> {
         dotenv::dotenv().ok();
 
+        let database_backend = DatabaseBackend::parse(
+            &env::var("DATABASE_BACKEND").unwrap_or_else(|_| "dynamodb".into()),
+        )?;
+        let database_url = env::var("DATABASE_URL")
+            .ok()
+            .or_else(|| database_backend.default_url().map(String::from));
+
+        if database_backend == DatabaseBackend::Postgres && database_url.is_none() {
+            return Err("DATABASE_URL es requerido con DATABASE_BACKEND=postgres".to_string());
+        }
+
         Ok(Config {
             server_host: env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".into()),
             server_port: env::var("SERVER_PORT")
                 .unwrap_or_else(|_| "3000".into())
                 .parse()
                 .unwrap_or(3000),
+            database_backend,
+            database_url,
             dynamodb_table_name: env::var("DYNAMODB_TABLE_NAME")
                 .unwrap_or_else(|_| "vacaciones".into()),
             session_secret: env::var("SESSION_SECRET")
@@ -27,6 +111,19 @@ impl Config {
                 .unwrap_or_else(|_| "7".into())
                 .parse()
                 .unwrap_or(7),
+            session_cleanup_minutes: env::var("SESSION_CLEANUP_MINUTES")
+                .unwrap_or_else(|_| "60".into())
+                .parse()
+                .unwrap_or(60)
+                .max(1),
+            request_expiry_minutes: env::var("REQUEST_EXPIRY_MINUTES")
+                .unwrap_or_else(|_| "60".into())
+                .parse()
+                .unwrap_or(60)
+                .max(1),
+            trusted_proxies: ProxiesConfiables::parse(
+                &env::var("TRUSTED_PROXIES").unwrap_or_default(),
+            )?,
         })
     }
 
@@ -1,6 +1,25 @@
+use async_trait::async_trait;
 use aws_config::BehaviorVersion;
 use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::transact_write_items::TransactWriteItemsError;
+use aws_sdk_dynamodb::types::{AttributeValue, Delete, KeysAndAttributes, Put, TransactWriteItem};
+use std::collections::HashMap;
+use std::future::Future;
+
 use crate::config::Config;
+use crate::db::esquema::{INDICE_DELEGADO, INDICE_SESIONES_EMPLEADO, INDICE_TIPO};
+use crate::db::repository::{
+    CadenaRepository, CalendarioRepository, DelegacionRepository, EmpleadoRepository,
+    HorarioRepository, PoliticaRepository, SessionRepository, SolicitudRepository,
+};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CadenaAprobacion, CalendarioEmpresa, CambioSaldo, Delegacion, Dias, Empleado, EstadoSolicitud,
+    FiltroSolicitudes, HorarioTrabajo, Pagina, Paginacion, PoliticaVacaciones, Sesion,
+    SolicitudVacaciones,
+    sesion::{ATRIBUTO_EMPLEADO, ATRIBUTO_TTL},
+};
 
 #[derive(Clone, Debug)]
 pub struct DynamoDBClient {
@@ -17,4 +36,1220 @@ impl DynamoDBClient {
             table_name: config.dynamodb_table_name.clone(),
         }
     }
-}
\ No newline at end of file
+}
+
+fn db_error<E: std::fmt::Display>(e: E) -> AppError {
+    AppError::DatabaseError(e.to_string())
+}
+
+/// Posiciones (dentro de la transacción) de las operaciones cuya condición falló.
+/// `None` si el error no es una cancelación de transacción.
+fn condiciones_fallidas<R>(err: &SdkError<TransactWriteItemsError, R>) -> Option<Vec<usize>> {
+    match err.as_service_error()? {
+        TransactWriteItemsError::TransactionCanceledException(e) => Some(
+            e.cancellation_reasons()
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.code() == Some("ConditionalCheckFailed"))
+                .map(|(i, _)| i)
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+type Item = HashMap<String, AttributeValue>;
+
+/// Lee páginas de DynamoDB desde la posición del cursor hasta juntar `limite` items.
+/// `leer` recibe el `ExclusiveStartKey` y el máximo de items a evaluar, y devuelve
+/// los items de esa página junto con su `LastEvaluatedKey`.
+async fn leer_pagina<F, Fut>(
+    paginacion: &Paginacion,
+    mut leer: F,
+) -> AppResult<(Vec<Item>, Option<HashMap<String, String>>)>
+where
+    F: FnMut(Option<Item>, i32) -> Fut,
+    Fut: Future<Output = AppResult<(Vec<Item>, Option<Item>)>>,
+{
+    let limite = paginacion.limite();
+    // Las claves de la tabla (PK, SK) son strings: el cursor guarda solo su valor
+    let mut inicio: Option<Item> = paginacion
+        .posicion::<HashMap<String, String>>()?
+        .map(|clave| {
+            clave
+                .into_iter()
+                .map(|(k, v)| (k, AttributeValue::S(v)))
+                .collect()
+        });
+    let mut items = Vec::new();
+
+    loop {
+        let (pagina, ultima_clave) = leer(inicio, (limite - items.len()) as i32).await?;
+        items.extend(pagina);
+
+        match ultima_clave {
+            Some(clave) if items.len() < limite => inicio = Some(clave),
+            ultima_clave => {
+                let cursor = ultima_clave.map(|clave| {
+                    clave
+                        .into_iter()
+                        .filter_map(|(k, v)| v.as_s().ok().map(|s| (k, s.clone())))
+                        .collect()
+                });
+                return Ok((items, cursor));
+            }
+        }
+    }
+}
+
+fn saldo_key(empleado_id: &str, periodo: i32) -> (AttributeValue, AttributeValue) {
+    (
+        AttributeValue::S(format!("EMPLEADO#{}", empleado_id)),
+        AttributeValue::S(format!("PERIODO#{}", periodo)),
+    )
+}
+
+fn email_key(email: &str) -> (AttributeValue, AttributeValue) {
+    (
+        AttributeValue::S(format!("EMAIL#{}", email)),
+        AttributeValue::S("EMAIL".to_string()),
+    )
+}
+
+impl DynamoDBClient {
+    /// Put del item índice `EMAIL#` que solo procede si el email está libre o ya es de este empleado
+    fn put_email(&self, empleado: &Empleado) -> AppResult<TransactWriteItem> {
+        let put = Put::builder()
+            .table_name(&self.table_name)
+            .set_item(Some(empleado.to_email_item()))
+            .condition_expression("attribute_not_exists(PK) OR empleado_id = :id")
+            .expression_attribute_values(":id", AttributeValue::S(empleado.id.clone()))
+            .build()
+            .map_err(db_error)?;
+
+        Ok(TransactWriteItem::builder().put(put).build())
+    }
+}
+
+#[async_trait]
+impl EmpleadoRepository for DynamoDBClient {
+    async fn obtener_empleado(&self, empleado_id: &str) -> AppResult<Option<Empleado>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("EMPLEADO#{}", empleado_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        match result.item() {
+            Some(item) => Empleado::from_item(item)
+                .map(Some)
+                .ok_or_else(|| AppError::InternalError("Error al parsear empleado".to_string())),
+            None => Ok(None),
+        }
+    }
+
+    async fn listar_empleados(&self) -> AppResult<Vec<Empleado>> {
+        let items = self
+            .client
+            .scan()
+            .table_name(&self.table_name)
+            .filter_expression("tipo = :tipo")
+            .expression_attribute_values(":tipo", AttributeValue::S("empleado".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        Ok(items.iter().filter_map(Empleado::from_item).collect())
+    }
+
+    async fn listar_empleados_pagina(
+        &self,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<Empleado>> {
+        let (items, siguiente) = leer_pagina(paginacion, |inicio, limite| async move {
+            let result = self
+                .client
+                .scan()
+                .table_name(&self.table_name)
+                .filter_expression("tipo = :tipo")
+                .expression_attribute_values(":tipo", AttributeValue::S("empleado".to_string()))
+                .set_exclusive_start_key(inicio)
+                .limit(limite)
+                .send()
+                .await
+                .map_err(db_error)?;
+
+            Ok((
+                result.items().to_vec(),
+                result.last_evaluated_key().cloned(),
+            ))
+        })
+        .await?;
+
+        Ok(Pagina::new(
+            items.iter().filter_map(Empleado::from_item).collect(),
+            siguiente,
+        ))
+    }
+
+    async fn buscar_por_email(&self, email: &str) -> AppResult<Option<Empleado>> {
+        let (pk, sk) = email_key(email);
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", pk)
+            .key("SK", sk)
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        // Solo el item EMAIL#: los que falten los crea `migrate` (paso 4 o `--rellenar-emails`)
+        match result
+            .item()
+            .and_then(|item| item.get("empleado_id"))
+            .and_then(|v| v.as_s().ok())
+        {
+            Some(empleado_id) => self.obtener_empleado(empleado_id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn crear_empleado(&self, empleado: &Empleado) -> AppResult<()> {
+        let put_empleado = Put::builder()
+            .table_name(&self.table_name)
+            .set_item(Some(empleado.to_item()))
+            .condition_expression("attribute_not_exists(PK)")
+            .build()
+            .map_err(db_error)?;
+
+        let result = self
+            .client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().put(put_empleado).build())
+            .transact_items(self.put_email(empleado)?)
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) => match condiciones_fallidas(&e).as_deref() {
+                Some([0, ..]) => Err(AppError::Conflict(format!(
+                    "Ya existe un empleado con ID {}",
+                    empleado.id
+                ))),
+                Some(fallidas) if !fallidas.is_empty() => Err(AppError::Conflict(format!(
+                    "El email {} ya está registrado",
+                    empleado.email
+                ))),
+                _ => Err(db_error(e)),
+            },
+        }
+    }
+
+    async fn actualizar_empleado(
+        &self,
+        empleado: &Empleado,
+        email_anterior: &str,
+    ) -> AppResult<()> {
+        let put_empleado = Put::builder()
+            .table_name(&self.table_name)
+            .set_item(Some(empleado.to_item()))
+            .condition_expression("attribute_exists(PK)")
+            .build()
+            .map_err(db_error)?;
+
+        let mut request = self
+            .client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().put(put_empleado).build())
+            .transact_items(self.put_email(empleado)?);
+
+        let email_anterior = Empleado::normalizar_email(email_anterior);
+        if email_anterior != Empleado::normalizar_email(&empleado.email) {
+            let (pk, sk) = email_key(&email_anterior);
+            let delete = Delete::builder()
+                .table_name(&self.table_name)
+                .key("PK", pk)
+                .key("SK", sk)
+                .build()
+                .map_err(db_error)?;
+            request = request.transact_items(TransactWriteItem::builder().delete(delete).build());
+        }
+
+        match request.send().await {
+            Ok(_) => Ok(()),
+            Err(e) => match condiciones_fallidas(&e).as_deref() {
+                Some([0, ..]) => Err(AppError::NotFound(format!(
+                    "Empleado {} no encontrado",
+                    empleado.id
+                ))),
+                Some([1]) => Err(AppError::Conflict(format!(
+                    "El email {} ya está registrado",
+                    empleado.email
+                ))),
+                _ => Err(db_error(e)),
+            },
+        }
+    }
+
+    async fn actualizar_password(&self, empleado_id: &str, password_hash: &str) -> AppResult<()> {
+        self.client
+            .update_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("EMPLEADO#{}", empleado_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .update_expression("SET password_hash = :hash")
+            .expression_attribute_values(":hash", AttributeValue::S(password_hash.to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SolicitudRepository for DynamoDBClient {
+    async fn obtener_solicitud(
+        &self,
+        empleado_id: &str,
+        solicitud_id: &str,
+    ) -> AppResult<Option<SolicitudVacaciones>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("EMPLEADO#{}", empleado_id)))
+            .key(
+                "SK",
+                AttributeValue::S(format!("SOLICITUD#{}", solicitud_id)),
+            )
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        match result.item() {
+            Some(item) => SolicitudVacaciones::from_item(item)
+                .map(Some)
+                .ok_or_else(|| AppError::InternalError("Error al parsear solicitud".to_string())),
+            None => Ok(None),
+        }
+    }
+
+    async fn listar_solicitudes(&self) -> AppResult<Vec<SolicitudVacaciones>> {
+        let items = self
+            .client
+            .scan()
+            .table_name(&self.table_name)
+            .filter_expression("begins_with(SK, :sk)")
+            .expression_attribute_values(":sk", AttributeValue::S("SOLICITUD#".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        Ok(items
+            .iter()
+            .filter_map(SolicitudVacaciones::from_item)
+            .collect())
+    }
+
+    async fn listar_solicitudes_por_empleado(
+        &self,
+        empleado_id: &str,
+    ) -> AppResult<Vec<SolicitudVacaciones>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .key_condition_expression("PK = :pk AND begins_with(SK, :sk)")
+            .expression_attribute_values(
+                ":pk",
+                AttributeValue::S(format!("EMPLEADO#{}", empleado_id)),
+            )
+            .expression_attribute_values(":sk", AttributeValue::S("SOLICITUD#".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        Ok(items
+            .iter()
+            .filter_map(SolicitudVacaciones::from_item)
+            .collect())
+    }
+
+    async fn listar_solicitudes_pagina(
+        &self,
+        filtro: &FiltroSolicitudes,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<SolicitudVacaciones>> {
+        let estado = filtro.estado.map(|e| AttributeValue::S(e.to_string()));
+        let sk = AttributeValue::S("SOLICITUD#".to_string());
+
+        let (items, siguiente) = match &filtro.empleado_id {
+            // Solicitudes de un empleado: query sobre su partición
+            Some(empleado_id) => {
+                let pk = AttributeValue::S(format!("EMPLEADO#{}", empleado_id));
+                leer_pagina(paginacion, |inicio, limite| {
+                    let mut query = self
+                        .client
+                        .query()
+                        .table_name(&self.table_name)
+                        .key_condition_expression("PK = :pk AND begins_with(SK, :sk)")
+                        .expression_attribute_values(":pk", pk.clone())
+                        .expression_attribute_values(":sk", sk.clone())
+                        .set_exclusive_start_key(inicio)
+                        .limit(limite);
+                    if let Some(estado) = &estado {
+                        query = query
+                            .filter_expression("estado = :estado")
+                            .expression_attribute_values(":estado", estado.clone());
+                    }
+
+                    async move {
+                        let result = query.send().await.map_err(db_error)?;
+                        Ok((
+                            result.items().to_vec(),
+                            result.last_evaluated_key().cloned(),
+                        ))
+                    }
+                })
+                .await?
+            }
+            // Todas las solicitudes: scan de la tabla
+            None => {
+                leer_pagina(paginacion, |inicio, limite| {
+                    let mut scan = self
+                        .client
+                        .scan()
+                        .table_name(&self.table_name)
+                        .expression_attribute_values(":sk", sk.clone())
+                        .set_exclusive_start_key(inicio)
+                        .limit(limite);
+                    scan = match &estado {
+                        Some(estado) => scan
+                            .filter_expression("begins_with(SK, :sk) AND estado = :estado")
+                            .expression_attribute_values(":estado", estado.clone()),
+                        None => scan.filter_expression("begins_with(SK, :sk)"),
+                    };
+
+                    async move {
+                        let result = scan.send().await.map_err(db_error)?;
+                        Ok((
+                            result.items().to_vec(),
+                            result.last_evaluated_key().cloned(),
+                        ))
+                    }
+                })
+                .await?
+            }
+        };
+
+        Ok(Pagina::new(
+            items
+                .iter()
+                .filter_map(SolicitudVacaciones::from_item)
+                .collect(),
+            siguiente,
+        ))
+    }
+
+    async fn guardar_solicitud(&self, solicitud: &SolicitudVacaciones) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(solicitud.to_item()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn obtener_saldo(&self, empleado_id: &str, periodo: i32) -> AppResult<Option<Dias>> {
+        let (pk, sk) = saldo_key(empleado_id, periodo);
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", pk)
+            .key("SK", sk)
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(result
+            .item()
+            .and_then(|item| item.get("dias_tomados"))
+            .and_then(|v| v.as_n().ok())
+            .and_then(|n| n.parse().ok()))
+    }
+
+    async fn obtener_saldos(
+        &self,
+        claves: &[(String, i32)],
+    ) -> AppResult<HashMap<(String, i32), Dias>> {
+        let mut saldos = HashMap::new();
+
+        // BatchGetItem admite hasta 100 claves por petición
+        for lote in claves.chunks(100) {
+            let claves = lote
+                .iter()
+                .map(|(id, periodo)| {
+                    let (pk, sk) = saldo_key(id, *periodo);
+                    HashMap::from([("PK".to_string(), pk), ("SK".to_string(), sk)])
+                })
+                .collect();
+            let mut pendientes = Some(HashMap::from([(
+                self.table_name.clone(),
+                KeysAndAttributes::builder()
+                    .set_keys(Some(claves))
+                    .build()
+                    .map_err(db_error)?,
+            )]));
+
+            // Reintentar las claves que DynamoDB no alcanzó a procesar
+            let mut intento = 0;
+            while let Some(request_items) = pendientes.take().filter(|p| !p.is_empty()) {
+                if intento > 0 {
+                    tokio::time::sleep(std::time::Duration::from_millis(50 << intento.min(5)))
+                        .await;
+                }
+                intento += 1;
+
+                let result = self
+                    .client
+                    .batch_get_item()
+                    .set_request_items(Some(request_items))
+                    .send()
+                    .await
+                    .map_err(db_error)?;
+
+                for item in result
+                    .responses()
+                    .and_then(|r| r.get(&self.table_name))
+                    .into_iter()
+                    .flatten()
+                {
+                    let empleado_id = item.get("empleado_id").and_then(|v| v.as_s().ok());
+                    let numero = |nombre: &str| item.get(nombre)?.as_n().ok();
+                    if let (Some(empleado_id), Some(Ok(periodo)), Some(Ok(dias))) = (
+                        empleado_id,
+                        numero("periodo").map(|n| n.parse::<i32>()),
+                        numero("dias_tomados").map(|n| n.parse::<Dias>()),
+                    ) {
+                        saldos.insert((empleado_id.clone(), periodo), dias);
+                    }
+                }
+                pendientes = result.unprocessed_keys().cloned();
+            }
+        }
+
+        Ok(saldos)
+    }
+
+    async fn inicializar_saldo(
+        &self,
+        empleado_id: &str,
+        periodo: i32,
+        dias_tomados: Dias,
+    ) -> AppResult<()> {
+        let (pk, sk) = saldo_key(empleado_id, periodo);
+        let result = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .item("PK", pk)
+            .item("SK", sk)
+            .item("empleado_id", AttributeValue::S(empleado_id.to_string()))
+            .item("periodo", AttributeValue::N(periodo.to_string()))
+            .item("dias_tomados", AttributeValue::N(dias_tomados.to_string()))
+            .item("tipo", AttributeValue::S("saldo".to_string()))
+            .condition_expression("attribute_not_exists(PK)")
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            // Otra operación ya registró el saldo: ese valor es el vigente
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+
+    async fn cambiar_estado_solicitud(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+    ) -> AppResult<()> {
+        self.cambiar_estado(solicitud, estado_anterior, saldos, None)
+            .await
+    }
+
+    async fn reprogramar_solicitud(
+        &self,
+        original: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+        nueva: &SolicitudVacaciones,
+    ) -> AppResult<()> {
+        self.cambiar_estado(original, estado_anterior, saldos, Some(nueva))
+            .await
+    }
+}
+
+impl DynamoDBClient {
+    /// Cambio de estado de `cambiar_estado_solicitud` que además guarda `nueva` en la misma transacción
+    async fn cambiar_estado(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+        nueva: Option<&SolicitudVacaciones>,
+    ) -> AppResult<()> {
+        let version_anterior = solicitud.version - 1;
+        // Las solicitudes creadas antes del control de versiones no tienen el atributo
+        let condicion_version = if version_anterior == 0 {
+            "(attribute_not_exists(version) OR version = :version)"
+        } else {
+            "version = :version"
+        };
+
+        let put_solicitud = Put::builder()
+            .table_name(&self.table_name)
+            .set_item(Some(solicitud.to_item()))
+            .condition_expression(format!(
+                "attribute_exists(PK) AND estado = :estado AND {}",
+                condicion_version
+            ))
+            .expression_attribute_values(":estado", AttributeValue::S(estado_anterior.to_string()))
+            .expression_attribute_values(
+                ":version",
+                AttributeValue::N(version_anterior.to_string()),
+            )
+            .build()
+            .map_err(db_error)?;
+
+        let mut request = self
+            .client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().put(put_solicitud).build());
+
+        for saldo in saldos {
+            let (pk, sk) = saldo_key(&solicitud.empleado_id, saldo.periodo);
+            let mut put_saldo = Put::builder()
+                .table_name(&self.table_name)
+                .item("PK", pk)
+                .item("SK", sk)
+                .item(
+                    "empleado_id",
+                    AttributeValue::S(solicitud.empleado_id.clone()),
+                )
+                .item("periodo", AttributeValue::N(saldo.periodo.to_string()))
+                .item("dias_tomados", AttributeValue::N(saldo.nuevo.to_string()))
+                .item("tipo", AttributeValue::S("saldo".to_string()));
+            put_saldo = match saldo.anterior {
+                Some(anterior) => put_saldo
+                    .condition_expression("dias_tomados = :anterior")
+                    .expression_attribute_values(
+                        ":anterior",
+                        AttributeValue::N(anterior.to_string()),
+                    ),
+                None => put_saldo.condition_expression("attribute_not_exists(PK)"),
+            };
+            request = request.transact_items(
+                TransactWriteItem::builder()
+                    .put(put_saldo.build().map_err(db_error)?)
+                    .build(),
+            );
+        }
+
+        // La nueva va al final, sin condición: su id es nuevo
+        if let Some(nueva) = nueva {
+            let put_nueva = Put::builder()
+                .table_name(&self.table_name)
+                .set_item(Some(nueva.to_item()))
+                .build()
+                .map_err(db_error)?;
+            request = request.transact_items(TransactWriteItem::builder().put(put_nueva).build());
+        }
+
+        match request.send().await {
+            Ok(_) => Ok(()),
+            Err(e) => match condiciones_fallidas(&e).as_deref() {
+                Some([0, ..]) => Err(AppError::Conflict(
+                    "La solicitud fue modificada por otra operación; recarga e intenta de nuevo"
+                        .to_string(),
+                )),
+                // Los saldos ocupan las posiciones 1..n: cualquiera que falle es un conflicto
+                Some([_, ..]) => Err(AppError::Conflict(
+                    "El saldo de días del empleado cambió; recarga e intenta de nuevo".to_string(),
+                )),
+                _ => Err(db_error(e)),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl SessionRepository for DynamoDBClient {
+    async fn guardar_sesion(&self, sesion: &Sesion) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(sesion.to_item()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn obtener_sesion(&self, session_id: &str) -> AppResult<Option<Sesion>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("SESSION#{}", session_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.item().and_then(Sesion::from_item))
+    }
+
+    async fn eliminar_sesion(&self, session_id: &str) -> AppResult<()> {
+        self.client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("SESSION#{}", session_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn listar_sesiones(&self) -> AppResult<Vec<Sesion>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo")
+            .expression_attribute_values(":tipo", AttributeValue::S("session".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        Ok(items.iter().filter_map(Sesion::from_item).collect())
+    }
+
+    async fn listar_sesiones_empleado(&self, empleado_id: &str) -> AppResult<Vec<Sesion>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_SESIONES_EMPLEADO)
+            .key_condition_expression("#empleado = :empleado_id")
+            .expression_attribute_names("#empleado", ATRIBUTO_EMPLEADO)
+            .expression_attribute_values(":empleado_id", AttributeValue::S(empleado_id.to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        Ok(items.iter().filter_map(Sesion::from_item).collect())
+    }
+
+    async fn eliminar_sesiones_expiradas(&self, ahora: i64) -> AppResult<usize> {
+        let mut expiradas = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo")
+            .filter_expression("#exp < :ahora")
+            .expression_attribute_names("#exp", ATRIBUTO_TTL)
+            .expression_attribute_values(":tipo", AttributeValue::S("session".to_string()))
+            .expression_attribute_values(":ahora", AttributeValue::N(ahora.to_string()))
+            .into_paginator()
+            .items()
+            .send();
+
+        let mut eliminadas = 0;
+        while let Some(item) = expiradas.try_next().await.map_err(db_error)? {
+            // La condición evita borrar una sesión renovada después de leer el índice
+            let result = self
+                .client
+                .delete_item()
+                .table_name(&self.table_name)
+                .key("PK", item["PK"].clone())
+                .key("SK", item["SK"].clone())
+                .condition_expression("#exp < :ahora")
+                .expression_attribute_names("#exp", ATRIBUTO_TTL)
+                .expression_attribute_values(":ahora", AttributeValue::N(ahora.to_string()))
+                .send()
+                .await;
+            match result {
+                Ok(_) => eliminadas += 1,
+                Err(e)
+                    if e.as_service_error()
+                        .is_some_and(|e| e.is_conditional_check_failed_exception()) => {}
+                Err(e) => return Err(db_error(e)),
+            }
+        }
+
+        Ok(eliminadas)
+    }
+}
+
+#[async_trait]
+impl CalendarioRepository for DynamoDBClient {
+    async fn listar_calendarios(&self) -> AppResult<Vec<CalendarioEmpresa>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo")
+            .expression_attribute_values(":tipo", AttributeValue::S("calendario".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        let mut calendarios: Vec<CalendarioEmpresa> = items
+            .iter()
+            .filter_map(CalendarioEmpresa::from_item)
+            .collect();
+        calendarios.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        Ok(calendarios)
+    }
+
+    async fn obtener_calendario(
+        &self,
+        calendario_id: &str,
+    ) -> AppResult<Option<CalendarioEmpresa>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key(
+                "PK",
+                AttributeValue::S(format!("CALENDARIO#{}", calendario_id)),
+            )
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.item().and_then(CalendarioEmpresa::from_item))
+    }
+
+    async fn guardar_calendario(&self, calendario: &CalendarioEmpresa) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(calendario.to_item()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_calendario(&self, calendario_id: &str) -> AppResult<()> {
+        let result = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key(
+                "PK",
+                AttributeValue::S(format!("CALENDARIO#{}", calendario_id)),
+            )
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .condition_expression("attribute_exists(PK)")
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Err(AppError::NotFound(format!(
+                    "Calendario {} no encontrado",
+                    calendario_id
+                )))
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl PoliticaRepository for DynamoDBClient {
+    async fn listar_politicas(&self) -> AppResult<Vec<PoliticaVacaciones>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo")
+            .expression_attribute_values(":tipo", AttributeValue::S("politica".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        let mut politicas: Vec<PoliticaVacaciones> = items
+            .iter()
+            .filter_map(PoliticaVacaciones::from_item)
+            .collect();
+        politicas.sort_by_key(|p| p.vigente_desde);
+        Ok(politicas)
+    }
+
+    async fn obtener_politica(&self, politica_id: &str) -> AppResult<Option<PoliticaVacaciones>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("POLITICA#{}", politica_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.item().and_then(PoliticaVacaciones::from_item))
+    }
+
+    async fn guardar_politica(&self, politica: &PoliticaVacaciones) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(politica.to_item()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_politica(&self, politica_id: &str) -> AppResult<()> {
+        let result = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("POLITICA#{}", politica_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .condition_expression("attribute_exists(PK)")
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Err(AppError::NotFound(format!(
+                    "Política {} no encontrada",
+                    politica_id
+                )))
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl CadenaRepository for DynamoDBClient {
+    async fn listar_cadenas(&self) -> AppResult<Vec<CadenaAprobacion>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo")
+            .expression_attribute_values(":tipo", AttributeValue::S("cadena".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        let mut cadenas: Vec<CadenaAprobacion> = items
+            .iter()
+            .filter_map(CadenaAprobacion::from_item)
+            .collect();
+        cadenas.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        Ok(cadenas)
+    }
+
+    async fn obtener_cadena(&self, cadena_id: &str) -> AppResult<Option<CadenaAprobacion>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("CADENA#{}", cadena_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.item().and_then(CadenaAprobacion::from_item))
+    }
+
+    async fn guardar_cadena(&self, cadena: &CadenaAprobacion) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(cadena.to_item()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_cadena(&self, cadena_id: &str) -> AppResult<()> {
+        let result = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("CADENA#{}", cadena_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .condition_expression("attribute_exists(PK)")
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Err(AppError::NotFound(format!(
+                    "Cadena {} no encontrada",
+                    cadena_id
+                )))
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl HorarioRepository for DynamoDBClient {
+    async fn listar_horarios(&self) -> AppResult<Vec<HorarioTrabajo>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo")
+            .expression_attribute_values(":tipo", AttributeValue::S("horario".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        let mut horarios: Vec<HorarioTrabajo> =
+            items.iter().filter_map(HorarioTrabajo::from_item).collect();
+        horarios.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        Ok(horarios)
+    }
+
+    async fn obtener_horario(&self, horario_id: &str) -> AppResult<Option<HorarioTrabajo>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("HORARIO#{}", horario_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.item().and_then(HorarioTrabajo::from_item))
+    }
+
+    async fn guardar_horario(&self, horario: &HorarioTrabajo) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(horario.to_item()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_horario(&self, horario_id: &str) -> AppResult<()> {
+        let result = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S(format!("HORARIO#{}", horario_id)))
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .condition_expression("attribute_exists(PK)")
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Err(AppError::NotFound(format!(
+                    "Horario {} no encontrado",
+                    horario_id
+                )))
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+}
+
+#[async_trait]
+impl DelegacionRepository for DynamoDBClient {
+    async fn listar_delegaciones(&self) -> AppResult<Vec<Delegacion>> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo")
+            .expression_attribute_values(":tipo", AttributeValue::S("delegacion".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        let mut delegaciones: Vec<Delegacion> =
+            items.iter().filter_map(Delegacion::from_item).collect();
+        delegaciones.sort_by_key(|d| d.desde);
+        Ok(delegaciones)
+    }
+
+    async fn listar_delegaciones_de_delegado(
+        &self,
+        delegado_id: &str,
+    ) -> AppResult<Vec<Delegacion>> {
+        // El índice ya las devuelve ordenadas por `desde`
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_DELEGADO)
+            .key_condition_expression("delegado_id = :delegado_id")
+            .expression_attribute_values(":delegado_id", AttributeValue::S(delegado_id.to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        Ok(items.iter().filter_map(Delegacion::from_item).collect())
+    }
+
+    async fn obtener_delegacion(&self, delegacion_id: &str) -> AppResult<Option<Delegacion>> {
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key(
+                "PK",
+                AttributeValue::S(format!("DELEGACION#{}", delegacion_id)),
+            )
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.item().and_then(Delegacion::from_item))
+    }
+
+    async fn guardar_delegacion(&self, delegacion: &Delegacion) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(delegacion.to_item()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_delegacion(&self, delegacion_id: &str) -> AppResult<()> {
+        let result = self
+            .client
+            .delete_item()
+            .table_name(&self.table_name)
+            .key(
+                "PK",
+                AttributeValue::S(format!("DELEGACION#{}", delegacion_id)),
+            )
+            .key("SK", AttributeValue::S("METADATA".to_string()))
+            .condition_expression("attribute_exists(PK)")
+            .send()
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Err(AppError::NotFound(format!(
+                    "Delegación {} no encontrada",
+                    delegacion_id
+                )))
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use aws_sdk_dynamodb::types::{
+    AttributeDefinition, AttributeValue, BillingMode, CreateGlobalSecondaryIndexAction,
+    GlobalSecondaryIndexUpdate, IndexStatus, KeySchemaElement, KeyType, Projection, ProjectionType,
+    ScalarAttributeType, TableDescription, TableStatus, TimeToLiveSpecification, TimeToLiveStatus,
+};
+use std::time::Duration;
+
+use crate::db::{DynamoDBClient, EmpleadoRepository};
+use crate::error::{AppError, AppResult};
+use crate::models::Empleado;
+use crate::models::sesion::{ATRIBUTO_EMPLEADO, ATRIBUTO_TTL};
+
+/// Versión de esquema que espera esta versión de la aplicación
+pub const VERSION_ESQUEMA: i64 = 7;
+
+/// Índice secundario para listar items por tipo de entidad sin recorrer toda la tabla
+pub const INDICE_TIPO: &str = "tipo-index";
+
+/// Índice disperso con las sesiones de cada empleado (solo las que tienen login)
+pub const INDICE_SESIONES_EMPLEADO: &str = "sesiones-empleado-index";
+
+/// Índice con las delegaciones que recibió cada empleado (solo las delegaciones tienen `delegado_id`)
+pub const INDICE_DELEGADO: &str = "delegaciones-delegado-index";
+
+/// Pasos de migración en orden: (versión que alcanzan, descripción)
+pub const MIGRACIONES: &[(i64, &str)] = &[
+    (1, "Crear la tabla con clave primaria PK/SK"),
+    (2, "Activar TTL sobre expires_at (expiración de sesiones)"),
+    (3, "Crear el índice tipo-index (tipo, SK)"),
+    (
+        4,
+        "Completar version en solicitudes e items EMAIL# de empleados",
+    ),
+    (
+        5,
+        "Crear el índice sesiones-empleado-index (sesion_empleado_id, created_at)",
+    ),
+    (
+        6,
+        "Eliminar los saldos por año calendario (SALDO#); se reconstruyen por periodo",
+    ),
+    (
+        7,
+        "Crear el índice delegaciones-delegado-index (delegado_id, desde)",
+    ),
+];
+
+/// Cuántos items modificó el relleno de atributos (paso 4, o solo los `EMAIL#` con
+/// `rellenar_emails`)
+#[derive(Debug, Default)]
+pub struct ResumenRelleno {
+    pub solicitudes_versionadas: usize,
+    pub emails_indexados: usize,
+    pub emails_duplicados: Vec<String>,
+}
+
+fn db_error<E: std::fmt::Display>(e: E) -> AppError {
+    AppError::DatabaseError(e.to_string())
+}
+
+fn key(nombre: &str, tipo: KeyType) -> AppResult<KeySchemaElement> {
+    KeySchemaElement::builder()
+        .attribute_name(nombre)
+        .key_type(tipo)
+        .build()
+        .map_err(db_error)
+}
+
+fn atributo_string(nombre: &str) -> AppResult<AttributeDefinition> {
+    AttributeDefinition::builder()
+        .attribute_name(nombre)
+        .attribute_type(ScalarAttributeType::S)
+        .build()
+        .map_err(db_error)
+}
+
+impl DynamoDBClient {
+    /// Descripción de la tabla, o `None` si todavía no existe
+    async fn describir_tabla(&self) -> AppResult<Option<TableDescription>> {
+        match self
+            .client
+            .describe_table()
+            .table_name(&self.table_name)
+            .send()
+            .await
+        {
+            Ok(output) => Ok(output.table().cloned()),
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_resource_not_found_exception()) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(db_error(e)),
+        }
+    }
+
+    /// Versión de esquema registrada en la tabla: `None` si la tabla no existe,
+    /// `Some(0)` si existe pero nunca se migró (creada a mano)
+    pub async fn version_esquema(&self) -> AppResult<Option<i64>> {
+        if self.describir_tabla().await?.is_none() {
+            return Ok(None);
+        }
+
+        let result = self
+            .client
+            .get_item()
+            .table_name(&self.table_name)
+            .key("PK", AttributeValue::S("SCHEMA".to_string()))
+            .key("SK", AttributeValue::S("VERSION".to_string()))
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(Some(
+            result
+                .item()
+                .and_then(|item| item.get("version"))
+                .and_then(|v| v.as_n().ok())
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0),
+        ))
+    }
+
+    async fn registrar_version(&self, version: i64) -> AppResult<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table_name)
+            .item("PK", AttributeValue::S("SCHEMA".to_string()))
+            .item("SK", AttributeValue::S("VERSION".to_string()))
+            .item("version", AttributeValue::N(version.to_string()))
+            .item(
+                "applied_at",
+                AttributeValue::S(chrono::Utc::now().to_rfc3339()),
+            )
+            .item("tipo", AttributeValue::S("schema".to_string()))
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    /// Espera a que la tabla y todos sus índices estén activos
+    async fn esperar_tabla_activa(&self) -> AppResult<()> {
+        for _ in 0..120 {
+            if let Some(tabla) = self.describir_tabla().await? {
+                let tabla_activa = tabla.table_status() == Some(&TableStatus::Active);
+                let indices_activos = tabla
+                    .global_secondary_indexes()
+                    .iter()
+                    .all(|i| i.index_status() == Some(&IndexStatus::Active));
+                if tabla_activa && indices_activos {
+                    return Ok(());
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        Err(AppError::DatabaseError(format!(
+            "La tabla {} no quedó activa a tiempo",
+            self.table_name
+        )))
+    }
+
+    /// Aplica las migraciones pendientes y registra la versión tras cada paso.
+    /// Cada paso es idempotente: volver a ejecutarlo sobre una tabla ya migrada no cambia nada.
+    /// Devuelve los pasos aplicados y, si se ejecutó el paso 4, el resumen del relleno.
+    pub async fn migrar(&self) -> AppResult<(Vec<&'static str>, Option<ResumenRelleno>)> {
+        let version = self.version_esquema().await?.unwrap_or(0);
+        let mut aplicadas = Vec::new();
+        let mut relleno = None;
+
+        for &(destino, descripcion) in MIGRACIONES.iter().filter(|(v, _)| *v > version) {
+            match destino {
+                1 => self.crear_tabla().await?,
+                2 => self.activar_ttl().await?,
+                3 => self.crear_indice(INDICE_TIPO, "tipo", "SK").await?,
+                4 => relleno = Some(self.rellenar_atributos().await?),
+                5 => {
+                    self.crear_indice(INDICE_SESIONES_EMPLEADO, ATRIBUTO_EMPLEADO, "created_at")
+                        .await?
+                }
+                6 => self.eliminar_saldos_anuales().await?,
+                7 => {
+                    self.crear_indice(INDICE_DELEGADO, "delegado_id", "desde")
+                        .await?
+                }
+                _ => unreachable!("migración sin implementar: {}", destino),
+            }
+            self.registrar_version(destino).await?;
+            aplicadas.push(descripcion);
+        }
+
+        Ok((aplicadas, relleno))
+    }
+
+    async fn crear_tabla(&self) -> AppResult<()> {
+        if self.describir_tabla().await?.is_none() {
+            self.client
+                .create_table()
+                .table_name(&self.table_name)
+                .attribute_definitions(atributo_string("PK")?)
+                .attribute_definitions(atributo_string("SK")?)
+                .key_schema(key("PK", KeyType::Hash)?)
+                .key_schema(key("SK", KeyType::Range)?)
+                .billing_mode(BillingMode::PayPerRequest)
+                .send()
+                .await
+                .map_err(db_error)?;
+        }
+
+        self.esperar_tabla_activa().await
+    }
+
+    async fn activar_ttl(&self) -> AppResult<()> {
+        let result = self
+            .client
+            .describe_time_to_live()
+            .table_name(&self.table_name)
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        let estado = result
+            .time_to_live_description()
+            .and_then(|d| d.time_to_live_status());
+        if matches!(
+            estado,
+            Some(TimeToLiveStatus::Enabled | TimeToLiveStatus::Enabling)
+        ) {
+            return Ok(());
+        }
+
+        self.client
+            .update_time_to_live()
+            .table_name(&self.table_name)
+            .time_to_live_specification(
+                TimeToLiveSpecification::builder()
+                    .enabled(true)
+                    .attribute_name(ATRIBUTO_TTL)
+                    .build()
+                    .map_err(db_error)?,
+            )
+            .send()
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    /// Crea un índice secundario global (proyección completa) si todavía no existe
+    async fn crear_indice(&self, nombre: &str, hash: &str, range: &str) -> AppResult<()> {
+        let existe = self.describir_tabla().await?.is_some_and(|tabla| {
+            tabla
+                .global_secondary_indexes()
+                .iter()
+                .any(|i| i.index_name() == Some(nombre))
+        });
+
+        if !existe {
+            let indice = CreateGlobalSecondaryIndexAction::builder()
+                .index_name(nombre)
+                .key_schema(key(hash, KeyType::Hash)?)
+                .key_schema(key(range, KeyType::Range)?)
+                .projection(
+                    Projection::builder()
+                        .projection_type(ProjectionType::All)
+                        .build(),
+                )
+                .build()
+                .map_err(db_error)?;
+
+            self.client
+                .update_table()
+                .table_name(&self.table_name)
+                .attribute_definitions(atributo_string(hash)?)
+                .attribute_definitions(atributo_string(range)?)
+                .global_secondary_index_updates(
+                    GlobalSecondaryIndexUpdate::builder().create(indice).build(),
+                )
+                .send()
+                .await
+                .map_err(db_error)?;
+        }
+
+        self.esperar_tabla_activa().await
+    }
+
+    /// Completa atributos agregados después de que se crearon los items:
+    /// `version` en solicitudes y el item `EMAIL#` de cada empleado
+    async fn rellenar_atributos(&self) -> AppResult<ResumenRelleno> {
+        let mut resumen = ResumenRelleno::default();
+
+        let items = self
+            .client
+            .scan()
+            .table_name(&self.table_name)
+            .filter_expression("tipo IN (:empleado, :solicitud)")
+            .expression_attribute_values(":empleado", AttributeValue::S("empleado".to_string()))
+            .expression_attribute_values(":solicitud", AttributeValue::S("solicitud".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        for item in &items {
+            let tipo = item.get("tipo").and_then(|v| v.as_s().ok());
+
+            if tipo.map(String::as_str) == Some("solicitud") && !item.contains_key("version") {
+                let result = self
+                    .client
+                    .update_item()
+                    .table_name(&self.table_name)
+                    .key("PK", item["PK"].clone())
+                    .key("SK", item["SK"].clone())
+                    .update_expression("SET version = :cero")
+                    .condition_expression("attribute_exists(PK) AND attribute_not_exists(version)")
+                    .expression_attribute_values(":cero", AttributeValue::N("0".to_string()))
+                    .send()
+                    .await;
+                match result {
+                    Ok(_) => resumen.solicitudes_versionadas += 1,
+                    Err(e)
+                        if e.as_service_error()
+                            .is_some_and(|e| e.is_conditional_check_failed_exception()) => {}
+                    Err(e) => return Err(db_error(e)),
+                }
+            }
+
+            if tipo.map(String::as_str) == Some("empleado")
+                && let Some(empleado) = Empleado::from_item(item)
+            {
+                self.indexar_email(&empleado, &mut resumen).await?;
+            }
+        }
+
+        Ok(resumen)
+    }
+
+    /// Crea el item `EMAIL#` de cada empleado que no lo tenga, p. ej. si se escribió en la
+    /// tabla por fuera de la aplicación después del paso 4. Se puede repetir cuantas veces
+    /// se quiera (`migrate -- --rellenar-emails`): los items que ya existen no cambian.
+    pub async fn rellenar_emails(&self) -> AppResult<ResumenRelleno> {
+        let mut resumen = ResumenRelleno::default();
+        for empleado in self.listar_empleados().await? {
+            self.indexar_email(&empleado, &mut resumen).await?;
+        }
+        Ok(resumen)
+    }
+
+    /// Crea o verifica el item `EMAIL#` del empleado; si el email ya es de otro empleado lo
+    /// anota en `emails_duplicados` para resolverlo a mano
+    async fn indexar_email(
+        &self,
+        empleado: &Empleado,
+        resumen: &mut ResumenRelleno,
+    ) -> AppResult<()> {
+        let result = self
+            .client
+            .put_item()
+            .table_name(&self.table_name)
+            .set_item(Some(empleado.to_email_item()))
+            .condition_expression("attribute_not_exists(PK) OR empleado_id = :id")
+            .expression_attribute_values(":id", AttributeValue::S(empleado.id.clone()))
+            .send()
+            .await;
+        match result {
+            Ok(_) => resumen.emails_indexados += 1,
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                resumen
+                    .emails_duplicados
+                    .push(format!("{} ({})", empleado.email, empleado.id));
+            }
+            Err(e) => return Err(db_error(e)),
+        }
+        Ok(())
+    }
+
+    /// Borra los saldos `SALDO#<año>`: desde la versión 6 los días tomados se llevan por
+    /// periodo de servicio (`PERIODO#<n>`) y se recalculan desde las solicitudes aprobadas
+    async fn eliminar_saldos_anuales(&self) -> AppResult<()> {
+        let items = self
+            .client
+            .query()
+            .table_name(&self.table_name)
+            .index_name(INDICE_TIPO)
+            .key_condition_expression("tipo = :tipo AND begins_with(SK, :prefijo)")
+            .expression_attribute_values(":tipo", AttributeValue::S("saldo".to_string()))
+            .expression_attribute_values(":prefijo", AttributeValue::S("SALDO#".to_string()))
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .map_err(db_error)?;
+
+        for item in &items {
+            self.client
+                .delete_item()
+                .table_name(&self.table_name)
+                .key("PK", item["PK"].clone())
+                .key("SK", item["SK"].clone())
+                .send()
+                .await
+                .map_err(db_error)?;
+        }
+
+        Ok(())
+    }
+}
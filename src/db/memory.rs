@@ -0,0 +1,713 @@
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::db::repository::{
+    CadenaRepository, CalendarioRepository, DelegacionRepository, EmpleadoRepository,
+    HorarioRepository, PoliticaRepository, SessionRepository, SolicitudRepository,
+};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CadenaAprobacion, CalendarioEmpresa, CambioSaldo, Delegacion, Dias, Empleado, EstadoSolicitud,
+    FiltroSolicitudes, HorarioTrabajo, Pagina, Paginacion, PoliticaVacaciones, Sesion,
+    SolicitudVacaciones,
+};
+
+/// Backend en memoria para desarrollo local y tests (los datos se pierden al reiniciar)
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStore {
+    data: Arc<RwLock<MemoryData>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryData {
+    empleados: BTreeMap<String, Empleado>,
+    // (empleado_id, solicitud_id) → solicitud
+    solicitudes: BTreeMap<(String, String), SolicitudVacaciones>,
+    // (empleado_id, periodo de servicio) → días tomados
+    saldos: HashMap<(String, i32), Dias>,
+    sesiones: HashMap<String, Sesion>,
+    calendarios: BTreeMap<String, CalendarioEmpresa>,
+    politicas: BTreeMap<String, PoliticaVacaciones>,
+    cadenas: BTreeMap<String, CadenaAprobacion>,
+    delegaciones: BTreeMap<String, Delegacion>,
+    horarios: BTreeMap<String, HorarioTrabajo>,
+}
+
+impl MemoryData {
+    /// El email debe ser único entre empleados (sin distinguir mayúsculas)
+    fn verificar_email_libre(&self, empleado: &Empleado) -> AppResult<()> {
+        let email = Empleado::normalizar_email(&empleado.email);
+        let ocupado = self
+            .empleados
+            .values()
+            .any(|e| e.id != empleado.id && Empleado::normalizar_email(&e.email) == email);
+
+        if ocupado {
+            return Err(AppError::Conflict(format!(
+                "El email {} ya está registrado",
+                empleado.email
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Carga empleados de ejemplo para poder iniciar sesión sin otra herramienta.
+    /// Devuelve los emails creados; todos usan `password` como contraseña.
+    pub fn seed_demo(&self, password: &str) -> AppResult<Vec<String>> {
+        let password_hash = bcrypt::hash(password, bcrypt::DEFAULT_COST)
+            .map_err(|e| AppError::InternalError(format!("Password hashing failed: {}", e)))?;
+
+        let demo = [
+            (
+                "1",
+                "Ana Administradora",
+                "Recursos Humanos",
+                "admin@ejemplo.com",
+                true,
+                "2018-03-01",
+                "CDMX",
+                None,
+            ),
+            (
+                "2",
+                "Juan Pérez",
+                "Tecnología",
+                "juan@ejemplo.com",
+                false,
+                "2022-06-15",
+                "Monterrey",
+                Some("1"),
+            ),
+        ];
+
+        let mut data = self.write()?;
+        let mut emails = Vec::new();
+        for (id, nombre, departamento, email, es_admin, fecha_ingreso, ubicacion, jefe_id) in demo {
+            data.empleados.insert(
+                id.to_string(),
+                Empleado {
+                    id: id.to_string(),
+                    nombre: nombre.to_string(),
+                    departamento: departamento.to_string(),
+                    email: email.to_string(),
+                    es_admin,
+                    fecha_ingreso: fecha_ingreso.to_string(),
+                    ubicacion: Some(ubicacion.to_string()),
+                    jefe_id: jefe_id.map(str::to_string),
+                    horario_id: None,
+                    password_hash: Some(password_hash.clone()),
+                    dias_disponibles: None,
+                    dias_tomados: None,
+                    antiguedad_anos: None,
+                    periodo: None,
+                },
+            );
+            emails.push(email.to_string());
+        }
+
+        Ok(emails)
+    }
+
+    fn read(&self) -> AppResult<RwLockReadGuard<'_, MemoryData>> {
+        self.data
+            .read()
+            .map_err(|_| AppError::DatabaseError("Memory store lock poisoned".to_string()))
+    }
+
+    fn write(&self) -> AppResult<RwLockWriteGuard<'_, MemoryData>> {
+        self.data
+            .write()
+            .map_err(|_| AppError::DatabaseError("Memory store lock poisoned".to_string()))
+    }
+}
+
+#[async_trait]
+impl EmpleadoRepository for MemoryStore {
+    async fn obtener_empleado(&self, empleado_id: &str) -> AppResult<Option<Empleado>> {
+        Ok(self.read()?.empleados.get(empleado_id).cloned())
+    }
+
+    async fn listar_empleados(&self) -> AppResult<Vec<Empleado>> {
+        Ok(self.read()?.empleados.values().cloned().collect())
+    }
+
+    async fn listar_empleados_pagina(
+        &self,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<Empleado>> {
+        let limite = paginacion.limite();
+        let inicio = match paginacion.posicion::<String>()? {
+            Some(id) => Bound::Excluded(id),
+            None => Bound::Unbounded,
+        };
+
+        let empleados = self
+            .read()?
+            .empleados
+            .range((inicio, Bound::Unbounded))
+            .take(limite + 1)
+            .map(|(_, e)| e.clone())
+            .collect();
+
+        Ok(Pagina::recortar(empleados, limite, |e| e.id.clone()))
+    }
+
+    async fn buscar_por_email(&self, email: &str) -> AppResult<Option<Empleado>> {
+        Ok(self
+            .read()?
+            .empleados
+            .values()
+            .find(|e| Empleado::normalizar_email(&e.email) == email)
+            .cloned())
+    }
+
+    async fn crear_empleado(&self, empleado: &Empleado) -> AppResult<()> {
+        let mut data = self.write()?;
+        if data.empleados.contains_key(&empleado.id) {
+            return Err(AppError::Conflict(format!(
+                "Ya existe un empleado con ID {}",
+                empleado.id
+            )));
+        }
+        data.verificar_email_libre(empleado)?;
+        data.empleados.insert(empleado.id.clone(), empleado.clone());
+        Ok(())
+    }
+
+    async fn actualizar_empleado(
+        &self,
+        empleado: &Empleado,
+        _email_anterior: &str,
+    ) -> AppResult<()> {
+        let mut data = self.write()?;
+        if !data.empleados.contains_key(&empleado.id) {
+            return Err(AppError::NotFound(format!(
+                "Empleado {} no encontrado",
+                empleado.id
+            )));
+        }
+        data.verificar_email_libre(empleado)?;
+        data.empleados.insert(empleado.id.clone(), empleado.clone());
+        Ok(())
+    }
+
+    async fn actualizar_password(&self, empleado_id: &str, password_hash: &str) -> AppResult<()> {
+        let mut data = self.write()?;
+        let empleado = data
+            .empleados
+            .get_mut(empleado_id)
+            .ok_or_else(|| AppError::NotFound(format!("Empleado {} no encontrado", empleado_id)))?;
+        empleado.password_hash = Some(password_hash.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SolicitudRepository for MemoryStore {
+    async fn obtener_solicitud(
+        &self,
+        empleado_id: &str,
+        solicitud_id: &str,
+    ) -> AppResult<Option<SolicitudVacaciones>> {
+        Ok(self
+            .read()?
+            .solicitudes
+            .get(&(empleado_id.to_string(), solicitud_id.to_string()))
+            .cloned())
+    }
+
+    async fn listar_solicitudes(&self) -> AppResult<Vec<SolicitudVacaciones>> {
+        Ok(self.read()?.solicitudes.values().cloned().collect())
+    }
+
+    async fn listar_solicitudes_por_empleado(
+        &self,
+        empleado_id: &str,
+    ) -> AppResult<Vec<SolicitudVacaciones>> {
+        Ok(self
+            .read()?
+            .solicitudes
+            .values()
+            .filter(|s| s.empleado_id == empleado_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn listar_solicitudes_pagina(
+        &self,
+        filtro: &FiltroSolicitudes,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<SolicitudVacaciones>> {
+        let limite = paginacion.limite();
+        let inicio = match paginacion.posicion::<(String, String)>()? {
+            Some(clave) => Bound::Excluded(clave),
+            None => Bound::Unbounded,
+        };
+
+        let solicitudes = self
+            .read()?
+            .solicitudes
+            .range((inicio, Bound::Unbounded))
+            .map(|(_, s)| s)
+            .filter(|s| filtro.acepta(s))
+            .take(limite + 1)
+            .cloned()
+            .collect();
+
+        Ok(Pagina::recortar(solicitudes, limite, |s| {
+            (s.empleado_id.clone(), s.id.clone())
+        }))
+    }
+
+    async fn guardar_solicitud(&self, solicitud: &SolicitudVacaciones) -> AppResult<()> {
+        self.write()?.solicitudes.insert(
+            (solicitud.empleado_id.clone(), solicitud.id.clone()),
+            solicitud.clone(),
+        );
+        Ok(())
+    }
+
+    async fn obtener_saldo(&self, empleado_id: &str, periodo: i32) -> AppResult<Option<Dias>> {
+        Ok(self
+            .read()?
+            .saldos
+            .get(&(empleado_id.to_string(), periodo))
+            .copied())
+    }
+
+    async fn obtener_saldos(
+        &self,
+        claves: &[(String, i32)],
+    ) -> AppResult<HashMap<(String, i32), Dias>> {
+        let data = self.read()?;
+        Ok(claves
+            .iter()
+            .filter_map(|clave| data.saldos.get(clave).map(|dias| (clave.clone(), *dias)))
+            .collect())
+    }
+
+    async fn inicializar_saldo(
+        &self,
+        empleado_id: &str,
+        periodo: i32,
+        dias_tomados: Dias,
+    ) -> AppResult<()> {
+        self.write()?
+            .saldos
+            .entry((empleado_id.to_string(), periodo))
+            .or_insert(dias_tomados);
+        Ok(())
+    }
+
+    async fn cambiar_estado_solicitud(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+    ) -> AppResult<()> {
+        self.cambiar_estado(solicitud, estado_anterior, saldos, None)
+    }
+
+    async fn reprogramar_solicitud(
+        &self,
+        original: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+        nueva: &SolicitudVacaciones,
+    ) -> AppResult<()> {
+        self.cambiar_estado(original, estado_anterior, saldos, Some(nueva))
+    }
+}
+
+impl MemoryStore {
+    /// Cambio de estado de `cambiar_estado_solicitud` que además guarda `nueva` bajo el mismo bloqueo
+    fn cambiar_estado(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+        nueva: Option<&SolicitudVacaciones>,
+    ) -> AppResult<()> {
+        let mut data = self.write()?;
+        let clave = (solicitud.empleado_id.clone(), solicitud.id.clone());
+
+        let vigente = data
+            .solicitudes
+            .get(&clave)
+            .is_some_and(|s| s.estado == estado_anterior && s.version == solicitud.version - 1);
+        if !vigente {
+            return Err(AppError::Conflict(
+                "La solicitud fue modificada por otra operación; recarga e intenta de nuevo"
+                    .to_string(),
+            ));
+        }
+
+        let cambio_vigente = |saldo: &CambioSaldo| {
+            data.saldos
+                .get(&(solicitud.empleado_id.clone(), saldo.periodo))
+                .copied()
+                == saldo.anterior
+        };
+        if !saldos.iter().all(cambio_vigente) {
+            return Err(AppError::Conflict(
+                "El saldo de días del empleado cambió; recarga e intenta de nuevo".to_string(),
+            ));
+        }
+        for saldo in saldos {
+            data.saldos
+                .insert((solicitud.empleado_id.clone(), saldo.periodo), saldo.nuevo);
+        }
+
+        data.solicitudes.insert(clave, solicitud.clone());
+        if let Some(nueva) = nueva {
+            data.solicitudes
+                .insert((nueva.empleado_id.clone(), nueva.id.clone()), nueva.clone());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SessionRepository for MemoryStore {
+    async fn guardar_sesion(&self, sesion: &Sesion) -> AppResult<()> {
+        self.write()?
+            .sesiones
+            .insert(sesion.session_id.clone(), sesion.clone());
+        Ok(())
+    }
+
+    async fn obtener_sesion(&self, session_id: &str) -> AppResult<Option<Sesion>> {
+        Ok(self.read()?.sesiones.get(session_id).cloned())
+    }
+
+    async fn eliminar_sesion(&self, session_id: &str) -> AppResult<()> {
+        self.write()?.sesiones.remove(session_id);
+        Ok(())
+    }
+
+    async fn listar_sesiones(&self) -> AppResult<Vec<Sesion>> {
+        Ok(self.read()?.sesiones.values().cloned().collect())
+    }
+
+    async fn listar_sesiones_empleado(&self, empleado_id: &str) -> AppResult<Vec<Sesion>> {
+        let mut sesiones: Vec<Sesion> = self
+            .read()?
+            .sesiones
+            .values()
+            .filter(|s| s.empleado_id.as_deref() == Some(empleado_id))
+            .cloned()
+            .collect();
+        sesiones.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        Ok(sesiones)
+    }
+
+    async fn eliminar_sesiones_expiradas(&self, ahora: i64) -> AppResult<usize> {
+        let mut data = self.write()?;
+        let antes = data.sesiones.len();
+        data.sesiones.retain(|_, s| s.expires_at >= ahora);
+        Ok(antes - data.sesiones.len())
+    }
+}
+
+#[async_trait]
+impl CalendarioRepository for MemoryStore {
+    async fn listar_calendarios(&self) -> AppResult<Vec<CalendarioEmpresa>> {
+        Ok(self.read()?.calendarios.values().cloned().collect())
+    }
+
+    async fn obtener_calendario(
+        &self,
+        calendario_id: &str,
+    ) -> AppResult<Option<CalendarioEmpresa>> {
+        Ok(self.read()?.calendarios.get(calendario_id).cloned())
+    }
+
+    async fn guardar_calendario(&self, calendario: &CalendarioEmpresa) -> AppResult<()> {
+        self.write()?
+            .calendarios
+            .insert(calendario.id.clone(), calendario.clone());
+        Ok(())
+    }
+
+    async fn eliminar_calendario(&self, calendario_id: &str) -> AppResult<()> {
+        self.write()?
+            .calendarios
+            .remove(calendario_id)
+            .map(|_| ())
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Calendario {} no encontrado", calendario_id))
+            })
+    }
+}
+
+#[async_trait]
+impl PoliticaRepository for MemoryStore {
+    async fn listar_politicas(&self) -> AppResult<Vec<PoliticaVacaciones>> {
+        Ok(self.read()?.politicas.values().cloned().collect())
+    }
+
+    async fn obtener_politica(&self, politica_id: &str) -> AppResult<Option<PoliticaVacaciones>> {
+        Ok(self.read()?.politicas.get(politica_id).cloned())
+    }
+
+    async fn guardar_politica(&self, politica: &PoliticaVacaciones) -> AppResult<()> {
+        self.write()?
+            .politicas
+            .insert(politica.id.clone(), politica.clone());
+        Ok(())
+    }
+
+    async fn eliminar_politica(&self, politica_id: &str) -> AppResult<()> {
+        self.write()?
+            .politicas
+            .remove(politica_id)
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound(format!("Política {} no encontrada", politica_id)))
+    }
+}
+
+#[async_trait]
+impl CadenaRepository for MemoryStore {
+    async fn listar_cadenas(&self) -> AppResult<Vec<CadenaAprobacion>> {
+        Ok(self.read()?.cadenas.values().cloned().collect())
+    }
+
+    async fn obtener_cadena(&self, cadena_id: &str) -> AppResult<Option<CadenaAprobacion>> {
+        Ok(self.read()?.cadenas.get(cadena_id).cloned())
+    }
+
+    async fn guardar_cadena(&self, cadena: &CadenaAprobacion) -> AppResult<()> {
+        self.write()?
+            .cadenas
+            .insert(cadena.id.clone(), cadena.clone());
+        Ok(())
+    }
+
+    async fn eliminar_cadena(&self, cadena_id: &str) -> AppResult<()> {
+        self.write()?
+            .cadenas
+            .remove(cadena_id)
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound(format!("Cadena {} no encontrada", cadena_id)))
+    }
+}
+
+#[async_trait]
+impl DelegacionRepository for MemoryStore {
+    async fn listar_delegaciones(&self) -> AppResult<Vec<Delegacion>> {
+        Ok(self.read()?.delegaciones.values().cloned().collect())
+    }
+
+    async fn listar_delegaciones_de_delegado(
+        &self,
+        delegado_id: &str,
+    ) -> AppResult<Vec<Delegacion>> {
+        let mut delegaciones: Vec<Delegacion> = self
+            .read()?
+            .delegaciones
+            .values()
+            .filter(|d| d.delegado_id == delegado_id)
+            .cloned()
+            .collect();
+        delegaciones.sort_by_key(|d| d.desde);
+        Ok(delegaciones)
+    }
+
+    async fn obtener_delegacion(&self, delegacion_id: &str) -> AppResult<Option<Delegacion>> {
+        Ok(self.read()?.delegaciones.get(delegacion_id).cloned())
+    }
+
+    async fn guardar_delegacion(&self, delegacion: &Delegacion) -> AppResult<()> {
+        self.write()?
+            .delegaciones
+            .insert(delegacion.id.clone(), delegacion.clone());
+        Ok(())
+    }
+
+    async fn eliminar_delegacion(&self, delegacion_id: &str) -> AppResult<()> {
+        self.write()?
+            .delegaciones
+            .remove(delegacion_id)
+            .map(|_| ())
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Delegación {} no encontrada", delegacion_id))
+            })
+    }
+}
+
+#[async_trait]
+impl HorarioRepository for MemoryStore {
+    async fn listar_horarios(&self) -> AppResult<Vec<HorarioTrabajo>> {
+        Ok(self.read()?.horarios.values().cloned().collect())
+    }
+
+    async fn obtener_horario(&self, horario_id: &str) -> AppResult<Option<HorarioTrabajo>> {
+        Ok(self.read()?.horarios.get(horario_id).cloned())
+    }
+
+    async fn guardar_horario(&self, horario: &HorarioTrabajo) -> AppResult<()> {
+        self.write()?
+            .horarios
+            .insert(horario.id.clone(), horario.clone());
+        Ok(())
+    }
+
+    async fn eliminar_horario(&self, horario_id: &str) -> AppResult<()> {
+        self.write()?
+            .horarios
+            .remove(horario_id)
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound(format!("Horario {} no encontrado", horario_id)))
+    }
+}
+
+#[cfg(test)]
+impl MemoryStore {
+    /// Base en memoria con los empleados de `seed_demo`, para los tests de los servicios
+    pub(crate) fn demo() -> crate::db::Database {
+        let store = Self::new();
+        store.seed_demo("Demo1234").unwrap();
+        Arc::new(store)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::solicitud::tests::{nueva, nueva_desde};
+    use crate::services::{EmpleadoService, SolicitudService};
+    use chrono::{Duration, Utc};
+
+    #[tokio::test]
+    async fn test_flujo_solicitud_en_memoria() {
+        let db = MemoryStore::demo();
+
+        let solicitudes = SolicitudService::new(db.clone());
+        let empleados_service = EmpleadoService::new(db.clone());
+        let antes = empleados_service
+            .obtener_empleado_con_dias("2")
+            .await
+            .unwrap();
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+
+        let creada = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(5), "2")
+            .await
+            .unwrap();
+        assert_eq!(creada.estado, EstadoSolicitud::Pendiente);
+        assert_eq!(creada.empleado_nombre, juan.nombre);
+        assert_eq!(creada.departamento, juan.departamento);
+
+        let aprobada = solicitudes
+            .actualizar_estado("2", &creada.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+        assert_eq!(aprobada.estado, EstadoSolicitud::Aprobada);
+        assert_eq!(
+            aprobada.consumo.iter().map(|c| c.dias).sum::<Dias>(),
+            Dias::enteros(5)
+        );
+
+        let empleado = empleados_service
+            .obtener_empleado_con_dias("2")
+            .await
+            .unwrap();
+        assert_eq!(
+            empleado.dias_disponibles,
+            antes.dias_disponibles.map(|d| d - Dias::enteros(5))
+        );
+
+        assert!(
+            db.buscar_por_email("juan@ejemplo.com")
+                .await
+                .unwrap()
+                .is_some()
+        );
+        let consumo = &aprobada.consumo[0];
+        assert_eq!(
+            db.obtener_saldo("2", consumo.periodo).await.unwrap(),
+            Some(consumo.dias)
+        );
+
+        // El listado lee los saldos en lote y registra los que faltaban
+        let empleados = empleados_service.listar_empleados_con_dias().await.unwrap();
+        assert_eq!(empleados[1].dias_disponibles, empleado.dias_disponibles);
+        let periodo_1 = empleados[0].antiguedad_anos.unwrap();
+        assert_eq!(
+            db.obtener_saldo("1", periodo_1).await.unwrap(),
+            Some(Dias::CERO)
+        );
+        assert_eq!(
+            solicitudes
+                .listar_solicitudes_por_empleado("1")
+                .await
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paginacion_en_memoria() {
+        let db = MemoryStore::demo();
+
+        let solicitudes = SolicitudService::new(db.clone());
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+        let hoy = Utc::now().date_naive();
+        for semana in 0..3 {
+            solicitudes
+                .crear_solicitud(
+                    &juan,
+                    nueva_desde("2", hoy + Duration::weeks(semana)),
+                    Dias::enteros(1),
+                    "2",
+                )
+                .await
+                .unwrap();
+        }
+
+        let filtro = FiltroSolicitudes {
+            empleado_id: Some("2".to_string()),
+            estado: Some(EstadoSolicitud::Pendiente),
+        };
+        let mut paginacion = Paginacion {
+            limit: Some(2),
+            cursor: None,
+        };
+        let primera = db
+            .listar_solicitudes_pagina(&filtro, &paginacion)
+            .await
+            .unwrap();
+        assert_eq!(primera.elementos.len(), 2);
+        assert!(primera.siguiente_cursor.is_some());
+
+        paginacion.cursor = primera.siguiente_cursor;
+        let segunda = db
+            .listar_solicitudes_pagina(&filtro, &paginacion)
+            .await
+            .unwrap();
+        assert_eq!(segunda.elementos.len(), 1);
+        assert!(segunda.siguiente_cursor.is_none());
+
+        let empleados = db
+            .listar_empleados_pagina(&Paginacion::default())
+            .await
+            .unwrap();
+        assert_eq!(empleados.elementos.len(), 2);
+        assert!(empleados.siguiente_cursor.is_none());
+
+        paginacion.cursor = Some("no-es-un-cursor".to_string());
+        assert!(matches!(
+            db.listar_empleados_pagina(&paginacion).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
@@ -1,3 +1,51 @@
 pub mod dynamodb;
+pub mod esquema;
+pub mod memory;
+pub mod repository;
+pub mod sql;
 
-pub use dynamodb::DynamoDBClient;
\ No newline at end of file
+pub use dynamodb::DynamoDBClient;
+pub use memory::MemoryStore;
+pub use repository::{
+    CadenaRepository, CalendarioRepository, Database, DelegacionRepository, EmpleadoRepository,
+    HorarioRepository, PoliticaRepository, Repository, SessionRepository, SolicitudRepository,
+};
+pub use sql::SqlStore;
+
+use crate::config::{Config, DatabaseBackend};
+use crate::error::AppResult;
+use std::sync::Arc;
+
+/// Contraseña de los empleados de ejemplo del backend en memoria
+pub const MEMORY_DEMO_PASSWORD: &str = "Demo1234";
+
+/// Crea el backend de almacenamiento seleccionado en la configuración
+pub async fn connect(config: &Config) -> AppResult<Database> {
+    match config.database_backend {
+        DatabaseBackend::DynamoDB => {
+            let client = DynamoDBClient::new(config).await;
+            tracing::info!("✅ Conectado a DynamoDB (tabla: {})", client.table_name);
+            Ok(Arc::new(client))
+        }
+        DatabaseBackend::Sqlite | DatabaseBackend::Postgres => {
+            let url = config.database_url.as_deref().unwrap_or_default();
+            let store = SqlStore::connect(url).await?;
+            tracing::info!(
+                "✅ Conectado a {:?} (migraciones aplicadas)",
+                config.database_backend
+            );
+            Ok(Arc::new(store))
+        }
+        DatabaseBackend::Memory => {
+            let store = MemoryStore::new();
+            let emails = store.seed_demo(MEMORY_DEMO_PASSWORD)?;
+            tracing::warn!(
+                "⚠️  Usando almacenamiento en memoria: los datos se pierden al reiniciar"
+            );
+            for email in emails {
+                tracing::info!("   Usuario demo: {} / {}", email, MEMORY_DEMO_PASSWORD);
+            }
+            Ok(Arc::new(store))
+        }
+    }
+}
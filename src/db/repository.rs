@@ -0,0 +1,248 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::error::AppResult;
+use crate::models::{
+    CadenaAprobacion, CalendarioEmpresa, CambioSaldo, Delegacion, Dias, Empleado, EstadoSolicitud,
+    FiltroSolicitudes, HorarioTrabajo, Pagina, Paginacion, PoliticaVacaciones, Sesion,
+    SolicitudVacaciones,
+};
+
+/// Acceso a los empleados, independiente del backend de almacenamiento
+#[async_trait]
+pub trait EmpleadoRepository: Send + Sync {
+    /// Obtiene un empleado por su ID
+    async fn obtener_empleado(&self, empleado_id: &str) -> AppResult<Option<Empleado>>;
+
+    /// Lista todos los empleados
+    async fn listar_empleados(&self) -> AppResult<Vec<Empleado>>;
+
+    /// Lista una página de empleados
+    async fn listar_empleados_pagina(&self, paginacion: &Paginacion)
+    -> AppResult<Pagina<Empleado>>;
+
+    /// Busca un empleado por email ya normalizado (ver `Empleado::normalizar_email`)
+    async fn buscar_por_email(&self, email: &str) -> AppResult<Option<Empleado>>;
+
+    /// Crea un empleado nuevo; `AppError::Conflict` si el ID o el email ya existen
+    async fn crear_empleado(&self, empleado: &Empleado) -> AppResult<()>;
+
+    /// Reemplaza un empleado existente; `AppError::Conflict` si el nuevo email ya pertenece a otro.
+    /// `email_anterior` es el email guardado antes del cambio.
+    async fn actualizar_empleado(&self, empleado: &Empleado, email_anterior: &str)
+    -> AppResult<()>;
+
+    /// Actualiza únicamente el hash del password de un empleado
+    async fn actualizar_password(&self, empleado_id: &str, password_hash: &str) -> AppResult<()>;
+}
+
+/// Acceso a las solicitudes de vacaciones
+#[async_trait]
+pub trait SolicitudRepository: Send + Sync {
+    /// Obtiene una solicitud concreta de un empleado
+    async fn obtener_solicitud(
+        &self,
+        empleado_id: &str,
+        solicitud_id: &str,
+    ) -> AppResult<Option<SolicitudVacaciones>>;
+
+    /// Lista todas las solicitudes
+    async fn listar_solicitudes(&self) -> AppResult<Vec<SolicitudVacaciones>>;
+
+    /// Lista las solicitudes de un empleado
+    async fn listar_solicitudes_por_empleado(
+        &self,
+        empleado_id: &str,
+    ) -> AppResult<Vec<SolicitudVacaciones>>;
+
+    /// Lista una página de las solicitudes que cumplen el filtro
+    async fn listar_solicitudes_pagina(
+        &self,
+        filtro: &FiltroSolicitudes,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<SolicitudVacaciones>>;
+
+    /// Crea o reemplaza una solicitud
+    async fn guardar_solicitud(&self, solicitud: &SolicitudVacaciones) -> AppResult<()>;
+
+    /// Días tomados registrados en el saldo de un empleado para un periodo de servicio
+    /// (`None` si no existe)
+    async fn obtener_saldo(&self, empleado_id: &str, periodo: i32) -> AppResult<Option<Dias>>;
+
+    /// Saldos de varios pares (empleado_id, periodo) en una sola lectura → días tomados.
+    /// Los pares sin saldo registrado no aparecen en el resultado.
+    async fn obtener_saldos(
+        &self,
+        claves: &[(String, i32)],
+    ) -> AppResult<HashMap<(String, i32), Dias>>;
+
+    /// Registra el saldo de un empleado para un periodo si aún no existe; si ya existe no lo toca
+    async fn inicializar_saldo(
+        &self,
+        empleado_id: &str,
+        periodo: i32,
+        dias_tomados: Dias,
+    ) -> AppResult<()>;
+
+    /// Guarda el nuevo estado (y el consumo) de una solicitud junto con los saldos de periodo
+    /// indicados en una sola operación atómica. `AppError::Conflict` si la solicitud ya no está
+    /// en `estado_anterior` con la versión `solicitud.version - 1`, o si algún saldo cambió
+    /// desde que se leyó.
+    async fn cambiar_estado_solicitud(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+    ) -> AppResult<()>;
+
+    /// Como `cambiar_estado_solicitud` para `original`, y en la misma operación atómica crea
+    /// `nueva`: si falla alguna condición no se guarda ninguna de las dos
+    async fn reprogramar_solicitud(
+        &self,
+        original: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+        nueva: &SolicitudVacaciones,
+    ) -> AppResult<()>;
+}
+
+/// Acceso a las sesiones de usuario
+#[async_trait]
+pub trait SessionRepository: Send + Sync {
+    /// Crea o reemplaza una sesión
+    async fn guardar_sesion(&self, sesion: &Sesion) -> AppResult<()>;
+
+    /// Obtiene una sesión por su ID (aunque esté expirada)
+    async fn obtener_sesion(&self, session_id: &str) -> AppResult<Option<Sesion>>;
+
+    /// Elimina una sesión
+    async fn eliminar_sesion(&self, session_id: &str) -> AppResult<()>;
+
+    /// Lista todas las sesiones guardadas (incluidas las expiradas)
+    async fn listar_sesiones(&self) -> AppResult<Vec<Sesion>>;
+
+    /// Lista las sesiones con login de un empleado (incluidas las expiradas)
+    async fn listar_sesiones_empleado(&self, empleado_id: &str) -> AppResult<Vec<Sesion>>;
+
+    /// Elimina las sesiones que expiraron antes de `ahora` (segundos Unix).
+    /// Devuelve cuántas se eliminaron.
+    async fn eliminar_sesiones_expiradas(&self, ahora: i64) -> AppResult<usize>;
+}
+
+/// Acceso a los calendarios de días inhábiles de la empresa
+#[async_trait]
+pub trait CalendarioRepository: Send + Sync {
+    /// Lista todos los calendarios
+    async fn listar_calendarios(&self) -> AppResult<Vec<CalendarioEmpresa>>;
+
+    /// Obtiene un calendario por su ID
+    async fn obtener_calendario(&self, calendario_id: &str)
+    -> AppResult<Option<CalendarioEmpresa>>;
+
+    /// Crea o reemplaza un calendario completo
+    async fn guardar_calendario(&self, calendario: &CalendarioEmpresa) -> AppResult<()>;
+
+    /// Elimina un calendario (`NotFound` si no existe)
+    async fn eliminar_calendario(&self, calendario_id: &str) -> AppResult<()>;
+}
+
+#[async_trait]
+pub trait PoliticaRepository: Send + Sync {
+    /// Lista las políticas de vacaciones registradas (las de la LFT viven en el código)
+    async fn listar_politicas(&self) -> AppResult<Vec<PoliticaVacaciones>>;
+
+    /// Obtiene una política por su ID
+    async fn obtener_politica(&self, politica_id: &str) -> AppResult<Option<PoliticaVacaciones>>;
+
+    /// Crea o reemplaza una política completa
+    async fn guardar_politica(&self, politica: &PoliticaVacaciones) -> AppResult<()>;
+
+    /// Elimina una política (`NotFound` si no existe)
+    async fn eliminar_politica(&self, politica_id: &str) -> AppResult<()>;
+}
+
+/// Acceso a las cadenas de aprobación por departamento
+#[async_trait]
+pub trait CadenaRepository: Send + Sync {
+    /// Lista las cadenas de aprobación registradas
+    async fn listar_cadenas(&self) -> AppResult<Vec<CadenaAprobacion>>;
+
+    /// Obtiene una cadena por su ID
+    async fn obtener_cadena(&self, cadena_id: &str) -> AppResult<Option<CadenaAprobacion>>;
+
+    /// Crea o reemplaza una cadena completa
+    async fn guardar_cadena(&self, cadena: &CadenaAprobacion) -> AppResult<()>;
+
+    /// Elimina una cadena (`NotFound` si no existe)
+    async fn eliminar_cadena(&self, cadena_id: &str) -> AppResult<()>;
+}
+
+/// Acceso a las delegaciones de aprobación
+#[async_trait]
+pub trait DelegacionRepository: Send + Sync {
+    /// Lista todas las delegaciones (vigentes, pasadas y futuras)
+    async fn listar_delegaciones(&self) -> AppResult<Vec<Delegacion>>;
+
+    /// Delegaciones recibidas por `delegado_id`, de la más antigua a la más reciente
+    async fn listar_delegaciones_de_delegado(
+        &self,
+        delegado_id: &str,
+    ) -> AppResult<Vec<Delegacion>>;
+
+    /// Obtiene una delegación por su ID
+    async fn obtener_delegacion(&self, delegacion_id: &str) -> AppResult<Option<Delegacion>>;
+
+    /// Crea o reemplaza una delegación
+    async fn guardar_delegacion(&self, delegacion: &Delegacion) -> AppResult<()>;
+
+    /// Elimina una delegación (`NotFound` si no existe)
+    async fn eliminar_delegacion(&self, delegacion_id: &str) -> AppResult<()>;
+}
+
+/// Acceso a los horarios de trabajo
+#[async_trait]
+pub trait HorarioRepository: Send + Sync {
+    /// Lista los horarios registrados (el de lunes a viernes vive en el código)
+    async fn listar_horarios(&self) -> AppResult<Vec<HorarioTrabajo>>;
+
+    /// Obtiene un horario por su ID
+    async fn obtener_horario(&self, horario_id: &str) -> AppResult<Option<HorarioTrabajo>>;
+
+    /// Crea o reemplaza un horario completo
+    async fn guardar_horario(&self, horario: &HorarioTrabajo) -> AppResult<()>;
+
+    /// Elimina un horario (`NotFound` si no existe)
+    async fn eliminar_horario(&self, horario_id: &str) -> AppResult<()>;
+}
+
+/// Backend completo de almacenamiento: agrupa todos los repositorios
+pub trait Repository:
+    EmpleadoRepository
+    + SolicitudRepository
+    + SessionRepository
+    + CalendarioRepository
+    + PoliticaRepository
+    + CadenaRepository
+    + DelegacionRepository
+    + HorarioRepository
+    + Debug
+{
+}
+
+impl<T> Repository for T where
+    T: EmpleadoRepository
+        + SolicitudRepository
+        + SessionRepository
+        + CalendarioRepository
+        + PoliticaRepository
+        + CadenaRepository
+        + DelegacionRepository
+        + HorarioRepository
+        + Debug
+{
+}
+
+/// Backend compartido por handlers, servicios y session store
+pub type Database = Arc<dyn Repository>;
@@ -0,0 +1,1826 @@
+use async_trait::async_trait;
+use sqlx::any::{AnyPoolOptions, AnyRow};
+use sqlx::{AnyPool, Row};
+use std::collections::HashMap;
+
+use crate::db::repository::{
+    CadenaRepository, CalendarioRepository, DelegacionRepository, EmpleadoRepository,
+    HorarioRepository, PoliticaRepository, SessionRepository, SolicitudRepository,
+};
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    AmbitoPolitica, CadenaAprobacion, CalendarioEmpresa, CambioSaldo, Delegacion, Dias, Empleado,
+    EstadoSolicitud, FiltroSolicitudes, HorarioTrabajo, Jornada, Pagina, Paginacion,
+    PoliticaVacaciones, Sesion, SolicitudVacaciones,
+};
+
+/// Migraciones embebidas en el binario (directorio `migrations/`)
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+/// Backend relacional (SQLite o PostgreSQL según la URL de conexión)
+#[derive(Clone, Debug)]
+pub struct SqlStore {
+    pool: AnyPool,
+}
+
+impl SqlStore {
+    /// Conecta a `database_url` y aplica las migraciones pendientes
+    pub async fn connect(database_url: &str) -> AppResult<Self> {
+        sqlx::any::install_default_drivers();
+
+        // Una base SQLite en memoria solo existe dentro de su conexión
+        let max_connections = if database_url.contains(":memory:") {
+            1
+        } else {
+            10
+        };
+
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(database_url)
+            .await
+            .map_err(db_error)?;
+
+        MIGRATOR.run(&pool).await.map_err(db_error)?;
+
+        Ok(Self { pool })
+    }
+}
+
+fn db_error<E: std::fmt::Display>(e: E) -> AppError {
+    AppError::DatabaseError(e.to_string())
+}
+
+/// Las violaciones de índices únicos se reportan como `AppError::Conflict`
+fn conflict_or_db_error(e: sqlx::Error, mensaje: &str) -> AppError {
+    match e.as_database_error() {
+        Some(db_err) if db_err.is_unique_violation() => AppError::Conflict(mensaje.to_string()),
+        _ => db_error(e),
+    }
+}
+
+fn row_to_empleado(row: &AnyRow) -> AppResult<Empleado> {
+    let es_admin: i64 = row.try_get("es_admin").map_err(db_error)?;
+
+    Ok(Empleado {
+        id: row.try_get("id").map_err(db_error)?,
+        nombre: row.try_get("nombre").map_err(db_error)?,
+        departamento: row.try_get("departamento").map_err(db_error)?,
+        email: row.try_get("email").map_err(db_error)?,
+        es_admin: es_admin != 0,
+        fecha_ingreso: row.try_get("fecha_ingreso").map_err(db_error)?,
+        ubicacion: row.try_get("ubicacion").map_err(db_error)?,
+        jefe_id: row.try_get("jefe_id").map_err(db_error)?,
+        horario_id: row.try_get("horario_id").map_err(db_error)?,
+        password_hash: row.try_get("password_hash").map_err(db_error)?,
+        dias_disponibles: None,
+        dias_tomados: None,
+        antiguedad_anos: None,
+        periodo: None,
+    })
+}
+
+fn row_to_solicitud(row: &AnyRow) -> AppResult<SolicitudVacaciones> {
+    let dias_solicitados: i64 = row.try_get("dias_solicitados").map_err(db_error)?;
+    let consumo: String = row.try_get("consumo").map_err(db_error)?;
+    let estado: String = row.try_get("estado").map_err(db_error)?;
+    let tipo_permiso: String = row.try_get("tipo_permiso").map_err(db_error)?;
+    let medio_dia_inicio: Option<String> = row.try_get("medio_dia_inicio").map_err(db_error)?;
+    let medio_dia_fin: Option<String> = row.try_get("medio_dia_fin").map_err(db_error)?;
+    let horas: Option<i64> = row.try_get("horas").map_err(db_error)?;
+    let pasos: String = row.try_get("pasos").map_err(db_error)?;
+    let aprobaciones: String = row.try_get("aprobaciones").map_err(db_error)?;
+    let historial: String = row.try_get("historial").map_err(db_error)?;
+
+    Ok(SolicitudVacaciones {
+        id: row.try_get("id").map_err(db_error)?,
+        empleado_id: row.try_get("empleado_id").map_err(db_error)?,
+        empleado_nombre: row.try_get("empleado_nombre").map_err(db_error)?,
+        departamento: row.try_get("departamento").map_err(db_error)?,
+        tipo_permiso: tipo_permiso.parse().map_err(db_error)?,
+        documento: row.try_get("documento").map_err(db_error)?,
+        fecha_inicio: row.try_get("fecha_inicio").map_err(db_error)?,
+        fecha_fin: row.try_get("fecha_fin").map_err(db_error)?,
+        jornada: Jornada {
+            medio_dia_inicio: medio_dia_inicio
+                .map(|m| m.parse())
+                .transpose()
+                .map_err(db_error)?,
+            medio_dia_fin: medio_dia_fin
+                .map(|m| m.parse())
+                .transpose()
+                .map_err(db_error)?,
+            horas: horas.map(|h| h as i32),
+        },
+        estado: estado.parse().map_err(db_error)?,
+        dias_solicitados: Dias::desde_milesimos(dias_solicitados as i32),
+        created_at: row.try_get("created_at").map_err(db_error)?,
+        version: row.try_get("version").map_err(db_error)?,
+        consumo: serde_json::from_str(&consumo).map_err(db_error)?,
+        reprograma: row.try_get("reprograma").map_err(db_error)?,
+        resuelto_por: row.try_get("resuelto_por").map_err(db_error)?,
+        resuelto_at: row.try_get("resuelto_at").map_err(db_error)?,
+        comentario: row.try_get("comentario").map_err(db_error)?,
+        pasos: serde_json::from_str(&pasos).map_err(db_error)?,
+        aprobaciones: serde_json::from_str(&aprobaciones).map_err(db_error)?,
+        historial: serde_json::from_str(&historial).map_err(db_error)?,
+    })
+}
+
+fn row_to_sesion(row: &AnyRow) -> AppResult<Sesion> {
+    Ok(Sesion {
+        session_id: row.try_get("session_id").map_err(db_error)?,
+        data: row.try_get("data").map_err(db_error)?,
+        expires_at: row.try_get("expires_at").map_err(db_error)?,
+        created_at: row.try_get("created_at").map_err(db_error)?,
+        empleado_id: row.try_get("empleado_id").map_err(db_error)?,
+        ultima_actividad: row.try_get("ultima_actividad").map_err(db_error)?,
+        ip: row.try_get("ip").map_err(db_error)?,
+        user_agent: row.try_get("user_agent").map_err(db_error)?,
+    })
+}
+
+fn row_to_calendario(row: &AnyRow) -> AppResult<CalendarioEmpresa> {
+    let departamentos: String = row.try_get("departamentos").map_err(db_error)?;
+    let ubicaciones: String = row.try_get("ubicaciones").map_err(db_error)?;
+    let dias: String = row.try_get("dias").map_err(db_error)?;
+
+    Ok(CalendarioEmpresa {
+        id: row.try_get("id").map_err(db_error)?,
+        nombre: row.try_get("nombre").map_err(db_error)?,
+        departamentos: serde_json::from_str(&departamentos).map_err(db_error)?,
+        ubicaciones: serde_json::from_str(&ubicaciones).map_err(db_error)?,
+        dias: serde_json::from_str(&dias).map_err(db_error)?,
+    })
+}
+
+fn row_to_politica(row: &AnyRow) -> AppResult<PoliticaVacaciones> {
+    let ambito: String = row.try_get("ambito").map_err(db_error)?;
+    let vigente_desde: String = row.try_get("vigente_desde").map_err(db_error)?;
+    let tabla: String = row.try_get("tabla").map_err(db_error)?;
+    let incremento: i64 = row.try_get("incremento").map_err(db_error)?;
+    let cada_anos: i64 = row.try_get("cada_anos").map_err(db_error)?;
+
+    Ok(PoliticaVacaciones {
+        id: row.try_get("id").map_err(db_error)?,
+        nombre: row.try_get("nombre").map_err(db_error)?,
+        ambito: AmbitoPolitica::parse(&ambito)
+            .ok_or_else(|| db_error(format!("Ámbito de política inválido: {}", ambito)))?,
+        vigente_desde: vigente_desde.parse().map_err(db_error)?,
+        tabla: serde_json::from_str(&tabla).map_err(db_error)?,
+        incremento: incremento as i32,
+        cada_anos: cada_anos as i32,
+    })
+}
+
+fn row_to_cadena(row: &AnyRow) -> AppResult<CadenaAprobacion> {
+    let pasos: String = row.try_get("pasos").map_err(db_error)?;
+
+    Ok(CadenaAprobacion {
+        id: row.try_get("id").map_err(db_error)?,
+        nombre: row.try_get("nombre").map_err(db_error)?,
+        departamento: row.try_get("departamento").map_err(db_error)?,
+        pasos: serde_json::from_str(&pasos).map_err(db_error)?,
+    })
+}
+
+fn row_to_horario(row: &AnyRow) -> AppResult<HorarioTrabajo> {
+    let patron: String = row.try_get("patron").map_err(db_error)?;
+
+    Ok(HorarioTrabajo {
+        id: row.try_get("id").map_err(db_error)?,
+        nombre: row.try_get("nombre").map_err(db_error)?,
+        patron: serde_json::from_str(&patron).map_err(db_error)?,
+    })
+}
+
+fn row_to_delegacion(row: &AnyRow) -> AppResult<Delegacion> {
+    let desde: String = row.try_get("desde").map_err(db_error)?;
+    let hasta: String = row.try_get("hasta").map_err(db_error)?;
+
+    Ok(Delegacion {
+        id: row.try_get("id").map_err(db_error)?,
+        delegante_id: row.try_get("delegante_id").map_err(db_error)?,
+        delegado_id: row.try_get("delegado_id").map_err(db_error)?,
+        desde: desde.parse().map_err(db_error)?,
+        hasta: hasta.parse().map_err(db_error)?,
+        created_at: row.try_get("created_at").map_err(db_error)?,
+    })
+}
+
+const EMPLEADO_COLUMNS: &str = "id, nombre, departamento, email, es_admin, fecha_ingreso, ubicacion, jefe_id, horario_id, password_hash";
+
+const SOLICITUD_COLUMNS: &str = "id, empleado_id, empleado_nombre, departamento, fecha_inicio, fecha_fin, estado, dias_solicitados, created_at, version, consumo, reprograma, resuelto_por, resuelto_at, comentario, pasos, aprobaciones, historial, tipo_permiso, documento, medio_dia_inicio, medio_dia_fin, horas";
+
+const SESION_COLUMNS: &str =
+    "session_id, data, expires_at, created_at, empleado_id, ultima_actividad, ip, user_agent";
+
+const POLITICA_COLUMNS: &str = "id, nombre, ambito, vigente_desde, tabla, incremento, cada_anos";
+
+const CADENA_COLUMNS: &str = "id, nombre, departamento, pasos";
+const HORARIO_COLUMNS: &str = "id, nombre, patron";
+
+const DELEGACION_COLUMNS: &str = "id, delegante_id, delegado_id, desde, hasta, created_at";
+
+/// Crea o reemplaza una solicitud con `executor` (el pool o una transacción abierta)
+async fn guardar_solicitud_en<'e, E>(executor: E, solicitud: &SolicitudVacaciones) -> AppResult<()>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Any>,
+{
+    sqlx::query(
+        "INSERT INTO solicitudes (id, empleado_id, empleado_nombre, departamento, fecha_inicio, fecha_fin, estado, dias_solicitados, created_at, version, consumo, reprograma, resuelto_por, resuelto_at, comentario, pasos, aprobaciones, historial, tipo_permiso, documento, medio_dia_inicio, medio_dia_fin, horas)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23)
+         ON CONFLICT (id) DO UPDATE SET
+            empleado_id = excluded.empleado_id,
+            empleado_nombre = excluded.empleado_nombre,
+            departamento = excluded.departamento,
+            fecha_inicio = excluded.fecha_inicio,
+            fecha_fin = excluded.fecha_fin,
+            estado = excluded.estado,
+            dias_solicitados = excluded.dias_solicitados,
+            created_at = excluded.created_at,
+            version = excluded.version,
+            consumo = excluded.consumo,
+            reprograma = excluded.reprograma,
+            resuelto_por = excluded.resuelto_por,
+            resuelto_at = excluded.resuelto_at,
+            comentario = excluded.comentario,
+            pasos = excluded.pasos,
+            aprobaciones = excluded.aprobaciones,
+            historial = excluded.historial,
+            tipo_permiso = excluded.tipo_permiso,
+            documento = excluded.documento,
+            medio_dia_inicio = excluded.medio_dia_inicio,
+            medio_dia_fin = excluded.medio_dia_fin,
+            horas = excluded.horas",
+    )
+    .bind(&solicitud.id)
+    .bind(&solicitud.empleado_id)
+    .bind(&solicitud.empleado_nombre)
+    .bind(&solicitud.departamento)
+    .bind(&solicitud.fecha_inicio)
+    .bind(&solicitud.fecha_fin)
+    .bind(solicitud.estado.as_str())
+    .bind(solicitud.dias_solicitados.milesimos() as i64)
+    .bind(&solicitud.created_at)
+    .bind(solicitud.version)
+    .bind(serde_json::to_string(&solicitud.consumo).map_err(db_error)?)
+    .bind(&solicitud.reprograma)
+    .bind(&solicitud.resuelto_por)
+    .bind(&solicitud.resuelto_at)
+    .bind(&solicitud.comentario)
+    .bind(serde_json::to_string(&solicitud.pasos).map_err(db_error)?)
+    .bind(serde_json::to_string(&solicitud.aprobaciones).map_err(db_error)?)
+    .bind(serde_json::to_string(&solicitud.historial).map_err(db_error)?)
+    .bind(solicitud.tipo_permiso.as_str())
+    .bind(&solicitud.documento)
+    .bind(solicitud.jornada.medio_dia_inicio.map(|m| m.as_str()))
+    .bind(solicitud.jornada.medio_dia_fin.map(|m| m.as_str()))
+    .bind(solicitud.jornada.horas.map(|h| h as i64))
+    .execute(executor)
+    .await
+    .map_err(db_error)?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl EmpleadoRepository for SqlStore {
+    async fn obtener_empleado(&self, empleado_id: &str) -> AppResult<Option<Empleado>> {
+        let sql = format!("SELECT {} FROM empleados WHERE id = $1", EMPLEADO_COLUMNS);
+        sqlx::query(&sql)
+            .bind(empleado_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?
+            .as_ref()
+            .map(row_to_empleado)
+            .transpose()
+    }
+
+    async fn listar_empleados(&self) -> AppResult<Vec<Empleado>> {
+        let sql = format!("SELECT {} FROM empleados ORDER BY id", EMPLEADO_COLUMNS);
+        sqlx::query(&sql)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(row_to_empleado)
+            .collect()
+    }
+
+    async fn listar_empleados_pagina(
+        &self,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<Empleado>> {
+        let limite = paginacion.limite();
+        // Paginación por clave: el cursor es el último ID devuelto
+        let despues_de: Option<String> = paginacion.posicion()?;
+
+        let mut sql = format!("SELECT {} FROM empleados", EMPLEADO_COLUMNS);
+        if despues_de.is_some() {
+            sql.push_str(" WHERE id > $2");
+        }
+        sql.push_str(" ORDER BY id LIMIT $1");
+
+        let mut query = sqlx::query(&sql).bind((limite + 1) as i64);
+        if let Some(id) = despues_de {
+            query = query.bind(id);
+        }
+
+        let empleados = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(row_to_empleado)
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(Pagina::recortar(empleados, limite, |e| e.id.clone()))
+    }
+
+    async fn buscar_por_email(&self, email: &str) -> AppResult<Option<Empleado>> {
+        let sql = format!(
+            "SELECT {} FROM empleados WHERE email = $1",
+            EMPLEADO_COLUMNS
+        );
+        sqlx::query(&sql)
+            .bind(email)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?
+            .as_ref()
+            .map(row_to_empleado)
+            .transpose()
+    }
+
+    async fn crear_empleado(&self, empleado: &Empleado) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO empleados (id, nombre, departamento, email, es_admin, fecha_ingreso, ubicacion, jefe_id, horario_id, password_hash)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(&empleado.id)
+        .bind(&empleado.nombre)
+        .bind(&empleado.departamento)
+        .bind(Empleado::normalizar_email(&empleado.email))
+        .bind(empleado.es_admin as i64)
+        .bind(&empleado.fecha_ingreso)
+        .bind(&empleado.ubicacion)
+        .bind(&empleado.jefe_id)
+        .bind(&empleado.horario_id)
+        .bind(&empleado.password_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| conflict_or_db_error(e, "Ya existe un empleado con ese ID o email"))?;
+
+        Ok(())
+    }
+
+    async fn actualizar_empleado(
+        &self,
+        empleado: &Empleado,
+        _email_anterior: &str,
+    ) -> AppResult<()> {
+        let result = sqlx::query(
+            "UPDATE empleados SET nombre = $1, departamento = $2, email = $3, es_admin = $4,
+                fecha_ingreso = $5, ubicacion = $6, jefe_id = $7, horario_id = $8, password_hash = $9
+             WHERE id = $10",
+        )
+        .bind(&empleado.nombre)
+        .bind(&empleado.departamento)
+        .bind(Empleado::normalizar_email(&empleado.email))
+        .bind(empleado.es_admin as i64)
+        .bind(&empleado.fecha_ingreso)
+        .bind(&empleado.ubicacion)
+        .bind(&empleado.jefe_id)
+        .bind(&empleado.horario_id)
+        .bind(&empleado.password_hash)
+        .bind(&empleado.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            conflict_or_db_error(e, &format!("El email {} ya está registrado", empleado.email))
+        })?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Empleado {} no encontrado",
+                empleado.id
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn actualizar_password(&self, empleado_id: &str, password_hash: &str) -> AppResult<()> {
+        let result = sqlx::query("UPDATE empleados SET password_hash = $1 WHERE id = $2")
+            .bind(password_hash)
+            .bind(empleado_id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Empleado {} no encontrado",
+                empleado_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SolicitudRepository for SqlStore {
+    async fn obtener_solicitud(
+        &self,
+        empleado_id: &str,
+        solicitud_id: &str,
+    ) -> AppResult<Option<SolicitudVacaciones>> {
+        let sql = format!(
+            "SELECT {} FROM solicitudes WHERE empleado_id = $1 AND id = $2",
+            SOLICITUD_COLUMNS
+        );
+        sqlx::query(&sql)
+            .bind(empleado_id)
+            .bind(solicitud_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?
+            .as_ref()
+            .map(row_to_solicitud)
+            .transpose()
+    }
+
+    async fn listar_solicitudes(&self) -> AppResult<Vec<SolicitudVacaciones>> {
+        let sql = format!(
+            "SELECT {} FROM solicitudes ORDER BY created_at",
+            SOLICITUD_COLUMNS
+        );
+        sqlx::query(&sql)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(row_to_solicitud)
+            .collect()
+    }
+
+    async fn listar_solicitudes_por_empleado(
+        &self,
+        empleado_id: &str,
+    ) -> AppResult<Vec<SolicitudVacaciones>> {
+        let sql = format!(
+            "SELECT {} FROM solicitudes WHERE empleado_id = $1 ORDER BY created_at",
+            SOLICITUD_COLUMNS
+        );
+        sqlx::query(&sql)
+            .bind(empleado_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(row_to_solicitud)
+            .collect()
+    }
+
+    async fn listar_solicitudes_pagina(
+        &self,
+        filtro: &FiltroSolicitudes,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<SolicitudVacaciones>> {
+        let limite = paginacion.limite();
+        // Paginación por clave: el cursor es (created_at, id) de la última solicitud devuelta
+        let despues_de: Option<(String, String)> = paginacion.posicion()?;
+
+        let mut condiciones = Vec::new();
+        let mut valores = Vec::new();
+        if let Some(empleado_id) = &filtro.empleado_id {
+            valores.push(empleado_id.clone());
+            condiciones.push(format!("empleado_id = ${}", valores.len() + 1));
+        }
+        if let Some(estado) = &filtro.estado {
+            valores.push(estado.to_string());
+            condiciones.push(format!("estado = ${}", valores.len() + 1));
+        }
+        if let Some((created_at, id)) = despues_de {
+            valores.push(created_at);
+            valores.push(id);
+            let (p_fecha, p_id) = (valores.len(), valores.len() + 1);
+            condiciones.push(format!(
+                "(created_at > ${p_fecha} OR (created_at = ${p_fecha} AND id > ${p_id}))"
+            ));
+        }
+
+        let mut sql = format!("SELECT {} FROM solicitudes", SOLICITUD_COLUMNS);
+        if !condiciones.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&condiciones.join(" AND "));
+        }
+        sql.push_str(" ORDER BY created_at, id LIMIT $1");
+
+        let mut query = sqlx::query(&sql).bind((limite + 1) as i64);
+        for valor in valores {
+            query = query.bind(valor);
+        }
+
+        let solicitudes = query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(row_to_solicitud)
+            .collect::<AppResult<Vec<_>>>()?;
+
+        Ok(Pagina::recortar(solicitudes, limite, |s| {
+            (s.created_at.clone(), s.id.clone())
+        }))
+    }
+
+    async fn guardar_solicitud(&self, solicitud: &SolicitudVacaciones) -> AppResult<()> {
+        guardar_solicitud_en(&self.pool, solicitud).await
+    }
+
+    async fn obtener_saldo(&self, empleado_id: &str, periodo: i32) -> AppResult<Option<Dias>> {
+        let dias: Option<i64> = sqlx::query_scalar(
+            "SELECT dias_tomados FROM saldos_periodo WHERE empleado_id = $1 AND periodo = $2",
+        )
+        .bind(empleado_id)
+        .bind(periodo as i64)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(dias.map(|d| Dias::desde_milesimos(d as i32)))
+    }
+
+    async fn obtener_saldos(
+        &self,
+        claves: &[(String, i32)],
+    ) -> AppResult<HashMap<(String, i32), Dias>> {
+        if claves.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let condiciones: Vec<String> = (0..claves.len())
+            .map(|i| {
+                format!(
+                    "(empleado_id = ${} AND periodo = ${})",
+                    2 * i + 1,
+                    2 * i + 2
+                )
+            })
+            .collect();
+        let sql = format!(
+            "SELECT empleado_id, periodo, dias_tomados FROM saldos_periodo WHERE {}",
+            condiciones.join(" OR ")
+        );
+
+        let mut query = sqlx::query(&sql);
+        for (empleado_id, periodo) in claves {
+            query = query.bind(empleado_id).bind(*periodo as i64);
+        }
+
+        query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(|row| {
+                let periodo: i64 = row.try_get("periodo").map_err(db_error)?;
+                let dias: i64 = row.try_get("dias_tomados").map_err(db_error)?;
+                Ok((
+                    (
+                        row.try_get("empleado_id").map_err(db_error)?,
+                        periodo as i32,
+                    ),
+                    Dias::desde_milesimos(dias as i32),
+                ))
+            })
+            .collect()
+    }
+
+    async fn inicializar_saldo(
+        &self,
+        empleado_id: &str,
+        periodo: i32,
+        dias_tomados: Dias,
+    ) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO saldos_periodo (empleado_id, periodo, dias_tomados) VALUES ($1, $2, $3)
+             ON CONFLICT (empleado_id, periodo) DO NOTHING",
+        )
+        .bind(empleado_id)
+        .bind(periodo as i64)
+        .bind(dias_tomados.milesimos() as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn cambiar_estado_solicitud(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+    ) -> AppResult<()> {
+        self.cambiar_estado(solicitud, estado_anterior, saldos, None)
+            .await
+    }
+
+    async fn reprogramar_solicitud(
+        &self,
+        original: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+        nueva: &SolicitudVacaciones,
+    ) -> AppResult<()> {
+        self.cambiar_estado(original, estado_anterior, saldos, Some(nueva))
+            .await
+    }
+}
+
+impl SqlStore {
+    /// Cambio de estado de `cambiar_estado_solicitud` que además guarda `nueva` en la misma transacción
+    async fn cambiar_estado(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        estado_anterior: EstadoSolicitud,
+        saldos: &[CambioSaldo],
+        nueva: Option<&SolicitudVacaciones>,
+    ) -> AppResult<()> {
+        const CONFLICTO_SOLICITUD: &str =
+            "La solicitud fue modificada por otra operación; recarga e intenta de nuevo";
+        const CONFLICTO_SALDO: &str =
+            "El saldo de días del empleado cambió; recarga e intenta de nuevo";
+
+        let mut tx = self.pool.begin().await.map_err(db_error)?;
+
+        let result = sqlx::query(
+            "UPDATE solicitudes SET estado = $1, version = $2, consumo = $3, historial = $4,
+                resuelto_por = $5, resuelto_at = $6, comentario = $7, aprobaciones = $8
+             WHERE empleado_id = $9 AND id = $10 AND estado = $11 AND version = $12",
+        )
+        .bind(solicitud.estado.as_str())
+        .bind(solicitud.version)
+        .bind(serde_json::to_string(&solicitud.consumo).map_err(db_error)?)
+        .bind(serde_json::to_string(&solicitud.historial).map_err(db_error)?)
+        .bind(&solicitud.resuelto_por)
+        .bind(&solicitud.resuelto_at)
+        .bind(&solicitud.comentario)
+        .bind(serde_json::to_string(&solicitud.aprobaciones).map_err(db_error)?)
+        .bind(&solicitud.empleado_id)
+        .bind(&solicitud.id)
+        .bind(estado_anterior.as_str())
+        .bind(solicitud.version - 1)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict(CONFLICTO_SOLICITUD.to_string()));
+        }
+
+        for saldo in saldos {
+            match saldo.anterior {
+                Some(anterior) => {
+                    let result = sqlx::query(
+                        "UPDATE saldos_periodo SET dias_tomados = $1
+                         WHERE empleado_id = $2 AND periodo = $3 AND dias_tomados = $4",
+                    )
+                    .bind(saldo.nuevo.milesimos() as i64)
+                    .bind(&solicitud.empleado_id)
+                    .bind(saldo.periodo as i64)
+                    .bind(anterior.milesimos() as i64)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(db_error)?;
+
+                    if result.rows_affected() == 0 {
+                        return Err(AppError::Conflict(CONFLICTO_SALDO.to_string()));
+                    }
+                }
+                None => {
+                    sqlx::query(
+                        "INSERT INTO saldos_periodo (empleado_id, periodo, dias_tomados) VALUES ($1, $2, $3)",
+                    )
+                    .bind(&solicitud.empleado_id)
+                    .bind(saldo.periodo as i64)
+                    .bind(saldo.nuevo.milesimos() as i64)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| conflict_or_db_error(e, CONFLICTO_SALDO))?;
+                }
+            }
+        }
+
+        if let Some(nueva) = nueva {
+            guardar_solicitud_en(&mut *tx, nueva).await?;
+        }
+
+        tx.commit().await.map_err(db_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SessionRepository for SqlStore {
+    async fn guardar_sesion(&self, sesion: &Sesion) -> AppResult<()> {
+        let sql = format!(
+            "INSERT INTO sesiones ({})
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (session_id) DO UPDATE SET
+                data = excluded.data,
+                expires_at = excluded.expires_at,
+                empleado_id = excluded.empleado_id,
+                ultima_actividad = excluded.ultima_actividad,
+                ip = excluded.ip,
+                user_agent = excluded.user_agent",
+            SESION_COLUMNS
+        );
+        sqlx::query(&sql)
+            .bind(&sesion.session_id)
+            .bind(&sesion.data)
+            .bind(sesion.expires_at)
+            .bind(&sesion.created_at)
+            .bind(&sesion.empleado_id)
+            .bind(&sesion.ultima_actividad)
+            .bind(&sesion.ip)
+            .bind(&sesion.user_agent)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn obtener_sesion(&self, session_id: &str) -> AppResult<Option<Sesion>> {
+        let sql = format!(
+            "SELECT {} FROM sesiones WHERE session_id = $1",
+            SESION_COLUMNS
+        );
+        sqlx::query(&sql)
+            .bind(session_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(db_error)?
+            .as_ref()
+            .map(row_to_sesion)
+            .transpose()
+    }
+
+    async fn eliminar_sesion(&self, session_id: &str) -> AppResult<()> {
+        sqlx::query("DELETE FROM sesiones WHERE session_id = $1")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn listar_sesiones(&self) -> AppResult<Vec<Sesion>> {
+        let sql = format!("SELECT {} FROM sesiones", SESION_COLUMNS);
+        sqlx::query(&sql)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(row_to_sesion)
+            .collect()
+    }
+
+    async fn listar_sesiones_empleado(&self, empleado_id: &str) -> AppResult<Vec<Sesion>> {
+        let sql = format!(
+            "SELECT {} FROM sesiones WHERE empleado_id = $1 ORDER BY created_at",
+            SESION_COLUMNS
+        );
+        sqlx::query(&sql)
+            .bind(empleado_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_error)?
+            .iter()
+            .map(row_to_sesion)
+            .collect()
+    }
+
+    async fn eliminar_sesiones_expiradas(&self, ahora: i64) -> AppResult<usize> {
+        let result = sqlx::query("DELETE FROM sesiones WHERE expires_at < $1")
+            .bind(ahora)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        Ok(result.rows_affected() as usize)
+    }
+}
+
+#[async_trait]
+impl CalendarioRepository for SqlStore {
+    async fn listar_calendarios(&self) -> AppResult<Vec<CalendarioEmpresa>> {
+        sqlx::query(
+            "SELECT id, nombre, departamentos, ubicaciones, dias FROM calendarios ORDER BY nombre",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(row_to_calendario)
+        .collect()
+    }
+
+    async fn obtener_calendario(
+        &self,
+        calendario_id: &str,
+    ) -> AppResult<Option<CalendarioEmpresa>> {
+        sqlx::query(
+            "SELECT id, nombre, departamentos, ubicaciones, dias FROM calendarios WHERE id = $1",
+        )
+        .bind(calendario_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        .as_ref()
+        .map(row_to_calendario)
+        .transpose()
+    }
+
+    async fn guardar_calendario(&self, calendario: &CalendarioEmpresa) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO calendarios (id, nombre, departamentos, ubicaciones, dias)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (id) DO UPDATE SET
+                nombre = excluded.nombre,
+                departamentos = excluded.departamentos,
+                ubicaciones = excluded.ubicaciones,
+                dias = excluded.dias",
+        )
+        .bind(&calendario.id)
+        .bind(&calendario.nombre)
+        .bind(serde_json::to_string(&calendario.departamentos).map_err(db_error)?)
+        .bind(serde_json::to_string(&calendario.ubicaciones).map_err(db_error)?)
+        .bind(serde_json::to_string(&calendario.dias).map_err(db_error)?)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_calendario(&self, calendario_id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM calendarios WHERE id = $1")
+            .bind(calendario_id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Calendario {} no encontrado",
+                calendario_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PoliticaRepository for SqlStore {
+    async fn listar_politicas(&self) -> AppResult<Vec<PoliticaVacaciones>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM politicas ORDER BY vigente_desde",
+            POLITICA_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(row_to_politica)
+        .collect()
+    }
+
+    async fn obtener_politica(&self, politica_id: &str) -> AppResult<Option<PoliticaVacaciones>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM politicas WHERE id = $1",
+            POLITICA_COLUMNS
+        ))
+        .bind(politica_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        .as_ref()
+        .map(row_to_politica)
+        .transpose()
+    }
+
+    async fn guardar_politica(&self, politica: &PoliticaVacaciones) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO politicas (id, nombre, ambito, vigente_desde, tabla, incremento, cada_anos)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (id) DO UPDATE SET
+                nombre = excluded.nombre,
+                ambito = excluded.ambito,
+                vigente_desde = excluded.vigente_desde,
+                tabla = excluded.tabla,
+                incremento = excluded.incremento,
+                cada_anos = excluded.cada_anos",
+        )
+        .bind(&politica.id)
+        .bind(&politica.nombre)
+        .bind(politica.ambito.as_str())
+        .bind(politica.vigente_desde.to_string())
+        .bind(serde_json::to_string(&politica.tabla).map_err(db_error)?)
+        .bind(politica.incremento as i64)
+        .bind(politica.cada_anos as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_politica(&self, politica_id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM politicas WHERE id = $1")
+            .bind(politica_id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Política {} no encontrada",
+                politica_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CadenaRepository for SqlStore {
+    async fn listar_cadenas(&self) -> AppResult<Vec<CadenaAprobacion>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM cadenas ORDER BY nombre",
+            CADENA_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(row_to_cadena)
+        .collect()
+    }
+
+    async fn obtener_cadena(&self, cadena_id: &str) -> AppResult<Option<CadenaAprobacion>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM cadenas WHERE id = $1",
+            CADENA_COLUMNS
+        ))
+        .bind(cadena_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        .as_ref()
+        .map(row_to_cadena)
+        .transpose()
+    }
+
+    async fn guardar_cadena(&self, cadena: &CadenaAprobacion) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO cadenas (id, nombre, departamento, pasos)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (id) DO UPDATE SET
+                nombre = excluded.nombre,
+                departamento = excluded.departamento,
+                pasos = excluded.pasos",
+        )
+        .bind(&cadena.id)
+        .bind(&cadena.nombre)
+        .bind(&cadena.departamento)
+        .bind(serde_json::to_string(&cadena.pasos).map_err(db_error)?)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_cadena(&self, cadena_id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM cadenas WHERE id = $1")
+            .bind(cadena_id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Cadena {} no encontrada",
+                cadena_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HorarioRepository for SqlStore {
+    async fn listar_horarios(&self) -> AppResult<Vec<HorarioTrabajo>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM horarios ORDER BY nombre",
+            HORARIO_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(row_to_horario)
+        .collect()
+    }
+
+    async fn obtener_horario(&self, horario_id: &str) -> AppResult<Option<HorarioTrabajo>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM horarios WHERE id = $1",
+            HORARIO_COLUMNS
+        ))
+        .bind(horario_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        .as_ref()
+        .map(row_to_horario)
+        .transpose()
+    }
+
+    async fn guardar_horario(&self, horario: &HorarioTrabajo) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO horarios (id, nombre, patron)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (id) DO UPDATE SET
+                nombre = excluded.nombre,
+                patron = excluded.patron",
+        )
+        .bind(&horario.id)
+        .bind(&horario.nombre)
+        .bind(serde_json::to_string(&horario.patron).map_err(db_error)?)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_horario(&self, horario_id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM horarios WHERE id = $1")
+            .bind(horario_id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Horario {} no encontrado",
+                horario_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DelegacionRepository for SqlStore {
+    async fn listar_delegaciones(&self) -> AppResult<Vec<Delegacion>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM delegaciones ORDER BY desde",
+            DELEGACION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(row_to_delegacion)
+        .collect()
+    }
+
+    async fn listar_delegaciones_de_delegado(
+        &self,
+        delegado_id: &str,
+    ) -> AppResult<Vec<Delegacion>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM delegaciones WHERE delegado_id = $1 ORDER BY desde",
+            DELEGACION_COLUMNS
+        ))
+        .bind(delegado_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(db_error)?
+        .iter()
+        .map(row_to_delegacion)
+        .collect()
+    }
+
+    async fn obtener_delegacion(&self, delegacion_id: &str) -> AppResult<Option<Delegacion>> {
+        sqlx::query(&format!(
+            "SELECT {} FROM delegaciones WHERE id = $1",
+            DELEGACION_COLUMNS
+        ))
+        .bind(delegacion_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(db_error)?
+        .as_ref()
+        .map(row_to_delegacion)
+        .transpose()
+    }
+
+    async fn guardar_delegacion(&self, delegacion: &Delegacion) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO delegaciones (id, delegante_id, delegado_id, desde, hasta, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (id) DO UPDATE SET
+                delegante_id = excluded.delegante_id,
+                delegado_id = excluded.delegado_id,
+                desde = excluded.desde,
+                hasta = excluded.hasta",
+        )
+        .bind(&delegacion.id)
+        .bind(&delegacion.delegante_id)
+        .bind(&delegacion.delegado_id)
+        .bind(delegacion.desde.to_string())
+        .bind(delegacion.hasta.to_string())
+        .bind(&delegacion.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    async fn eliminar_delegacion(&self, delegacion_id: &str) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM delegaciones WHERE id = $1")
+            .bind(delegacion_id)
+            .execute(&self.pool)
+            .await
+            .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(format!(
+                "Delegación {} no encontrada",
+                delegacion_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use crate::models::{
+        ConsumoPeriodo, DatosCadena, MedioDia, NuevaDelegacion, PasoAprobacion, TipoPermiso,
+    };
+    use crate::services::solicitud::tests::nueva;
+    use crate::services::{CadenaService, DelegacionService, SolicitudService};
+    use chrono::Duration;
+    use std::sync::Arc;
+
+    fn empleado() -> Empleado {
+        Empleado {
+            id: "1".to_string(),
+            nombre: "Juan Pérez".to_string(),
+            departamento: "Tecnología".to_string(),
+            email: "juan@ejemplo.com".to_string(),
+            es_admin: true,
+            fecha_ingreso: "2020-01-15".to_string(),
+            ubicacion: Some("CDMX".to_string()),
+            jefe_id: None,
+            horario_id: None,
+            password_hash: None,
+            dias_disponibles: None,
+            dias_tomados: None,
+            antiguedad_anos: None,
+            periodo: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_en_memoria() {
+        let store = SqlStore::connect("sqlite::memory:").await.unwrap();
+
+        store.crear_empleado(&empleado()).await.unwrap();
+        store.actualizar_password("1", "hash").await.unwrap();
+        let guardado = store
+            .buscar_por_email("juan@ejemplo.com")
+            .await
+            .unwrap()
+            .unwrap();
+        let duplicado = Empleado {
+            id: "2".to_string(),
+            email: "Juan@Ejemplo.com".to_string(),
+            ..empleado()
+        };
+        assert!(matches!(
+            store.crear_empleado(&duplicado).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(guardado.es_admin);
+        assert_eq!(guardado.password_hash.as_deref(), Some("hash"));
+
+        let mut solicitud = SolicitudVacaciones {
+            id: "s1".to_string(),
+            empleado_id: "1".to_string(),
+            empleado_nombre: "Juan Pérez".to_string(),
+            departamento: "Sistemas".to_string(),
+            tipo_permiso: TipoPermiso::Vacaciones,
+            documento: None,
+            fecha_inicio: "2025-03-10".to_string(),
+            fecha_fin: "2025-03-14".to_string(),
+            jornada: Jornada::default(),
+            estado: EstadoSolicitud::Pendiente,
+            dias_solicitados: Dias::enteros(5),
+            created_at: "2025-02-01T10:30:00Z".to_string(),
+            version: 0,
+            consumo: Vec::new(),
+            reprograma: None,
+            resuelto_por: None,
+            resuelto_at: None,
+            comentario: None,
+            pasos: Vec::new(),
+            aprobaciones: Vec::new(),
+            historial: Vec::new(),
+        };
+        store.guardar_solicitud(&solicitud).await.unwrap();
+        solicitud.cambiar_estado(
+            EstadoSolicitud::Aprobada,
+            "2",
+            Some("Buen viaje".to_string()),
+        );
+        solicitud.consumo = vec![ConsumoPeriodo {
+            periodo: 3,
+            dias: Dias::enteros(5),
+        }];
+        store.guardar_solicitud(&solicitud).await.unwrap();
+
+        let solicitudes = store.listar_solicitudes_por_empleado("1").await.unwrap();
+        assert_eq!(solicitudes.len(), 1);
+        assert_eq!(solicitudes[0].estado, EstadoSolicitud::Aprobada);
+        assert_eq!(solicitudes[0].dias_solicitados, Dias::enteros(5));
+        assert_eq!(solicitudes[0].consumo, solicitud.consumo);
+        assert_eq!(solicitudes[0].resuelto_por.as_deref(), Some("2"));
+        assert_eq!(solicitudes[0].comentario.as_deref(), Some("Buen viaje"));
+
+        for (id, created_at) in [
+            ("s2", "2025-02-02T00:00:00Z"),
+            ("s3", "2025-02-02T00:00:00Z"),
+        ] {
+            store
+                .guardar_solicitud(&SolicitudVacaciones {
+                    id: id.to_string(),
+                    created_at: created_at.to_string(),
+                    ..solicitud.clone()
+                })
+                .await
+                .unwrap();
+        }
+        let filtro = FiltroSolicitudes {
+            empleado_id: Some("1".to_string()),
+            estado: Some(EstadoSolicitud::Aprobada),
+        };
+        let mut paginacion = Paginacion {
+            limit: Some(2),
+            cursor: None,
+        };
+        let primera = store
+            .listar_solicitudes_pagina(&filtro, &paginacion)
+            .await
+            .unwrap();
+        assert_eq!(primera.elementos.len(), 2);
+        assert_eq!(primera.elementos[1].id, "s2");
+
+        paginacion.cursor = primera.siguiente_cursor;
+        let segunda = store
+            .listar_solicitudes_pagina(&filtro, &paginacion)
+            .await
+            .unwrap();
+        assert_eq!(segunda.elementos.len(), 1);
+        assert_eq!(segunda.elementos[0].id, "s3");
+        assert!(segunda.siguiente_cursor.is_none());
+
+        let mut revocada = SolicitudVacaciones {
+            version: 1,
+            consumo: Vec::new(),
+            ..solicitud.clone()
+        };
+        revocada.cambiar_estado(
+            EstadoSolicitud::Revocada,
+            "2",
+            Some("Cierre de proyecto".to_string()),
+        );
+        let saldo = CambioSaldo {
+            periodo: 3,
+            anterior: None,
+            nuevo: Dias::CERO,
+        };
+        store
+            .cambiar_estado_solicitud(&revocada, EstadoSolicitud::Aprobada, &[saldo])
+            .await
+            .unwrap();
+        let guardada = store.obtener_solicitud("1", "s1").await.unwrap().unwrap();
+        assert!(guardada.consumo.is_empty());
+        assert_eq!(guardada.estado, EstadoSolicitud::Revocada);
+        assert_eq!(guardada.historial, revocada.historial);
+
+        let incapacidad = SolicitudVacaciones {
+            id: "s4".to_string(),
+            tipo_permiso: TipoPermiso::IncapacidadImss,
+            documento: Some("IMSS-123".to_string()),
+            ..solicitud.clone()
+        };
+        store.guardar_solicitud(&incapacidad).await.unwrap();
+        let guardada = store.obtener_solicitud("1", "s4").await.unwrap().unwrap();
+        assert_eq!(guardada.tipo_permiso, TipoPermiso::IncapacidadImss);
+        assert_eq!(guardada.documento.as_deref(), Some("IMSS-123"));
+
+        let por_horas = SolicitudVacaciones {
+            id: "s5".to_string(),
+            fecha_fin: "2025-03-10".to_string(),
+            jornada: Jornada {
+                horas: Some(3),
+                ..Jornada::default()
+            },
+            dias_solicitados: Dias::horas(3),
+            ..solicitud.clone()
+        };
+        let medio_dia = SolicitudVacaciones {
+            id: "s6".to_string(),
+            jornada: Jornada {
+                medio_dia_inicio: Some(MedioDia::Tarde),
+                ..Jornada::default()
+            },
+            dias_solicitados: Dias::desde_milesimos(4500),
+            ..solicitud.clone()
+        };
+        for fraccionada in [&por_horas, &medio_dia] {
+            store.guardar_solicitud(fraccionada).await.unwrap();
+            let guardada = store
+                .obtener_solicitud("1", &fraccionada.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(guardada.jornada, fraccionada.jornada);
+            assert_eq!(guardada.dias_solicitados, fraccionada.dias_solicitados);
+        }
+        assert_eq!(store.obtener_saldo("1", 3).await.unwrap(), Some(Dias::CERO));
+        store
+            .inicializar_saldo("1", 3, Dias::enteros(7))
+            .await
+            .unwrap();
+        store
+            .inicializar_saldo("1", 2, Dias::enteros(4))
+            .await
+            .unwrap();
+        store.inicializar_saldo("2", 1, Dias::MEDIO).await.unwrap();
+        let saldos = store
+            .obtener_saldos(&[
+                ("1".to_string(), 3),
+                ("2".to_string(), 1),
+                ("2".to_string(), 2),
+                ("3".to_string(), 1),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(saldos.get(&("1".to_string(), 3)), Some(&Dias::CERO));
+        assert_eq!(saldos.get(&("2".to_string(), 1)), Some(&Dias::MEDIO));
+        assert_eq!(saldos.len(), 2);
+        assert!(matches!(
+            store
+                .cambiar_estado_solicitud(&revocada, EstadoSolicitud::Aprobada, &[])
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+
+        // La reprogramación no guarda la nueva si la original cambió, y la guarda si no
+        let nueva = SolicitudVacaciones {
+            id: "s7".to_string(),
+            reprograma: Some("s4".to_string()),
+            ..incapacidad.clone()
+        };
+        let mut cancelada = SolicitudVacaciones {
+            version: 1,
+            ..incapacidad.clone()
+        };
+        cancelada.cambiar_estado(EstadoSolicitud::Cancelada, "1", None);
+        assert!(matches!(
+            store
+                .reprogramar_solicitud(&cancelada, EstadoSolicitud::Pendiente, &[], &nueva)
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(store.obtener_solicitud("1", "s7").await.unwrap().is_none());
+
+        store
+            .reprogramar_solicitud(&cancelada, EstadoSolicitud::Aprobada, &[], &nueva)
+            .await
+            .unwrap();
+        let guardada = store.obtener_solicitud("1", "s4").await.unwrap().unwrap();
+        assert_eq!(guardada.estado, EstadoSolicitud::Cancelada);
+        let guardada = store.obtener_solicitud("1", "s7").await.unwrap().unwrap();
+        assert_eq!(guardada.reprograma.as_deref(), Some("s4"));
+    }
+
+    #[tokio::test]
+    async fn test_eliminar_sesiones_expiradas() {
+        let store = SqlStore::connect("sqlite::memory:").await.unwrap();
+
+        for (id, expires_at) in [("vieja", 100), ("limite", 200), ("vigente", 300)] {
+            store
+                .guardar_sesion(&Sesion {
+                    session_id: id.to_string(),
+                    data: "{}".to_string(),
+                    expires_at,
+                    created_at: "2025-01-01T00:00:00Z".to_string(),
+                    empleado_id: Some("1".to_string()),
+                    ultima_actividad: None,
+                    ip: Some("10.0.0.1".to_string()),
+                    user_agent: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(store.eliminar_sesiones_expiradas(200).await.unwrap(), 1);
+        assert!(store.obtener_sesion("vieja").await.unwrap().is_none());
+        assert_eq!(store.listar_sesiones().await.unwrap().len(), 2);
+        let del_empleado = store.listar_sesiones_empleado("1").await.unwrap();
+        assert_eq!(del_empleado.len(), 2);
+        assert_eq!(del_empleado[0].ip.as_deref(), Some("10.0.0.1"));
+        assert!(
+            store
+                .listar_sesiones_empleado("2")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    /// Guarda `original` y luego `actualizado` (que lo reemplaza), lo lee de vuelta por su id y
+    /// en el listado, y lo elimina: eliminarlo otra vez es `NotFound`
+    async fn ida_y_vuelta<T: Clone + PartialEq + std::fmt::Debug>(
+        original: T,
+        actualizado: T,
+        guardar: impl AsyncFn(&T) -> AppResult<()>,
+        obtener: impl AsyncFn() -> AppResult<Option<T>>,
+        listar: impl AsyncFn() -> AppResult<Vec<T>>,
+        eliminar: impl AsyncFn() -> AppResult<()>,
+    ) {
+        guardar(&original).await.unwrap();
+        guardar(&actualizado).await.unwrap();
+        assert_eq!(obtener().await.unwrap(), Some(actualizado.clone()));
+        assert_eq!(listar().await.unwrap(), vec![actualizado]);
+
+        eliminar().await.unwrap();
+        assert_eq!(obtener().await.unwrap(), None);
+        assert!(matches!(eliminar().await, Err(AppError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_catalogos_ida_y_vuelta() {
+        let store = SqlStore::connect("sqlite::memory:").await.unwrap();
+        let fecha = |texto: &str| texto.parse::<chrono::NaiveDate>().unwrap();
+
+        let calendario = CalendarioEmpresa {
+            id: "cal-1".to_string(),
+            nombre: "Monterrey".to_string(),
+            departamentos: vec![],
+            ubicaciones: vec!["Monterrey".to_string()],
+            dias: vec![crate::models::Feriado {
+                fecha: fecha("2025-12-12"),
+                nombre: "Día de la Virgen de Guadalupe".to_string(),
+                medio_dia: Some(MedioDia::Tarde),
+            }],
+        };
+        ida_y_vuelta(
+            calendario.clone(),
+            CalendarioEmpresa {
+                departamentos: vec!["Ventas".to_string()],
+                ..calendario
+            },
+            async |c| store.guardar_calendario(c).await,
+            async || store.obtener_calendario("cal-1").await,
+            async || store.listar_calendarios().await,
+            async || store.eliminar_calendario("cal-1").await,
+        )
+        .await;
+
+        let horario = HorarioTrabajo {
+            id: "turno".to_string(),
+            nombre: "Turno 4x3".to_string(),
+            patron: crate::models::PatronHorario::Rotativo {
+                inicio: fecha("2025-03-06"),
+                trabaja: 4,
+                descansa: 3,
+            },
+        };
+        ida_y_vuelta(
+            horario.clone(),
+            HorarioTrabajo {
+                patron: crate::models::PatronHorario::Semanal {
+                    dias: vec![1, 2, 3, 4, 5, 6],
+                },
+                ..horario
+            },
+            async |h| store.guardar_horario(h).await,
+            async || store.obtener_horario("turno").await,
+            async || store.listar_horarios().await,
+            async || store.eliminar_horario("turno").await,
+        )
+        .await;
+
+        let cadena = CadenaAprobacion {
+            id: "cad-1".to_string(),
+            nombre: "Jefe y RH".to_string(),
+            departamento: Some("Tecnología".to_string()),
+            pasos: vec![
+                PasoAprobacion::JefeDirecto,
+                PasoAprobacion::Empleado {
+                    empleado_id: "9".to_string(),
+                },
+            ],
+        };
+        ida_y_vuelta(
+            cadena.clone(),
+            CadenaAprobacion {
+                departamento: None,
+                pasos: cadena.pasos.iter().rev().cloned().collect(),
+                ..cadena
+            },
+            async |c| store.guardar_cadena(c).await,
+            async || store.obtener_cadena("cad-1").await,
+            async || store.listar_cadenas().await,
+            async || store.eliminar_cadena("cad-1").await,
+        )
+        .await;
+
+        let delegacion = Delegacion {
+            id: "del-1".to_string(),
+            delegante_id: "1".to_string(),
+            delegado_id: "2".to_string(),
+            desde: fecha("2025-07-01"),
+            hasta: fecha("2025-07-15"),
+            created_at: "2025-06-20T09:00:00Z".to_string(),
+        };
+        ida_y_vuelta(
+            delegacion.clone(),
+            Delegacion {
+                hasta: fecha("2025-07-31"),
+                ..delegacion
+            },
+            async |d| store.guardar_delegacion(d).await,
+            async || store.obtener_delegacion("del-1").await,
+            async || store.listar_delegaciones().await,
+            async || store.eliminar_delegacion("del-1").await,
+        )
+        .await;
+
+        let subordinado = Empleado {
+            id: "2".to_string(),
+            email: "ana@ejemplo.com".to_string(),
+            jefe_id: Some("1".to_string()),
+            horario_id: Some("turno".to_string()),
+            ..empleado()
+        };
+        store.crear_empleado(&subordinado).await.unwrap();
+        let guardado = store.obtener_empleado("2").await.unwrap().unwrap();
+        assert_eq!(guardado.jefe_id.as_deref(), Some("1"));
+        assert_eq!(guardado.horario_id.as_deref(), Some("turno"));
+    }
+
+    /// Base SQLite en memoria con un jefe ("1", administrador), su subordinado ("2") y un
+    /// aprobador de Recursos Humanos ("3")
+    async fn db_con_equipo() -> Database {
+        let store = SqlStore::connect("sqlite::memory:").await.unwrap();
+        for (id, jefe_id) in [("1", None), ("2", Some("1")), ("3", None)] {
+            store
+                .crear_empleado(&Empleado {
+                    id: id.to_string(),
+                    email: format!("{}@ejemplo.com", id),
+                    es_admin: id == "1",
+                    jefe_id: jefe_id.map(str::to_string),
+                    ..empleado()
+                })
+                .await
+                .unwrap();
+        }
+        Arc::new(store)
+    }
+
+    #[tokio::test]
+    async fn test_delegaciones_sin_traslapes() {
+        let db = db_con_equipo().await;
+        let service = DelegacionService::new(db.clone());
+        let jefe = db.obtener_empleado("1").await.unwrap().unwrap();
+        let hoy = chrono::Utc::now().date_naive();
+        let datos = |delegado: &str, desde, hasta| NuevaDelegacion {
+            delegante_id: None,
+            delegado_id: delegado.to_string(),
+            desde,
+            hasta,
+        };
+
+        service
+            .crear(&jefe, datos("2", hoy, hoy + Duration::days(7)))
+            .await
+            .unwrap();
+        // Comparte el último día con la primera, aunque sea a otro delegado
+        assert!(matches!(
+            service
+                .crear(
+                    &jefe,
+                    datos("3", hoy + Duration::days(7), hoy + Duration::days(9))
+                )
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+        service
+            .crear(
+                &jefe,
+                datos("3", hoy + Duration::days(8), hoy + Duration::days(9)),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(service.delegantes_vigentes("2", hoy).await.unwrap(), ["1"]);
+        assert!(
+            service
+                .delegantes_vigentes("3", hoy)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            service
+                .delegantes_vigentes("3", hoy + Duration::days(8))
+                .await
+                .unwrap(),
+            ["1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pasos_de_cadena_en_orden() {
+        let db = db_con_equipo().await;
+        let solicitudes = SolicitudService::new(db.clone());
+        let empleado = |id: &'static str| {
+            let db = db.clone();
+            async move { db.obtener_empleado(id).await.unwrap().unwrap() }
+        };
+        let (jefe, juan, rh) = (
+            empleado("1").await,
+            empleado("2").await,
+            empleado("3").await,
+        );
+
+        CadenaService::new(db.clone())
+            .crear(DatosCadena {
+                nombre: "Jefe y RH".to_string(),
+                departamento: Some(juan.departamento.clone()),
+                pasos: vec![
+                    PasoAprobacion::JefeDirecto,
+                    PasoAprobacion::Empleado {
+                        empleado_id: "3".to_string(),
+                    },
+                ],
+            })
+            .await
+            .unwrap();
+        let creada = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(2), "2")
+            .await
+            .unwrap();
+
+        // RH no puede adelantarse al jefe, y el jefe no decide el paso de RH
+        assert!(matches!(
+            solicitudes
+                .decidir("2", &creada.id, &rh, &[], true, None)
+                .await,
+            Err(AppError::Forbidden(_))
+        ));
+        let paso_1 = solicitudes
+            .decidir("2", &creada.id, &jefe, &[], true, None)
+            .await
+            .unwrap();
+        assert_eq!(paso_1.estado, EstadoSolicitud::Pendiente);
+        assert!(matches!(
+            solicitudes
+                .decidir("2", &creada.id, &jefe, &[], true, None)
+                .await,
+            Err(AppError::Forbidden(_))
+        ));
+
+        solicitudes
+            .decidir("2", &creada.id, &rh, &[], true, None)
+            .await
+            .unwrap();
+        let guardada = db
+            .obtener_solicitud("2", &creada.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(guardada.estado, EstadoSolicitud::Aprobada);
+        assert_eq!(
+            guardada
+                .aprobaciones
+                .iter()
+                .map(|d| (d.paso, d.aprobador.as_str()))
+                .collect::<Vec<_>>(),
+            [(0, "1"), (1, "3")]
+        );
+        let consumo = &guardada.consumo[0];
+        assert_eq!(
+            db.obtener_saldo("2", consumo.periodo).await.unwrap(),
+            Some(consumo.dias)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_conflictos_al_cambiar_estado() {
+        let db = db_con_equipo().await;
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+        let creada = SolicitudService::new(db.clone())
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(2), "2")
+            .await
+            .unwrap();
+
+        let mut aprobada = creada.clone();
+        aprobada.cambiar_estado(EstadoSolicitud::Aprobada, "1", None);
+        aprobada.version += 1;
+        let saldo = |anterior, nuevo| CambioSaldo {
+            periodo: 1,
+            anterior,
+            nuevo,
+        };
+
+        // Estado anterior o versión que ya no corresponden
+        assert!(matches!(
+            db.cambiar_estado_solicitud(&aprobada, EstadoSolicitud::Aprobada, &[])
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+        let adelantada = SolicitudVacaciones {
+            version: 2,
+            ..aprobada.clone()
+        };
+        assert!(matches!(
+            db.cambiar_estado_solicitud(&adelantada, EstadoSolicitud::Pendiente, &[])
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+
+        // Un saldo que cambió desde que se leyó revierte también el cambio de la solicitud
+        db.inicializar_saldo("2", 1, Dias::enteros(3))
+            .await
+            .unwrap();
+        for cambio in [
+            saldo(None, Dias::enteros(2)),
+            saldo(Some(Dias::enteros(1)), Dias::enteros(3)),
+        ] {
+            assert!(matches!(
+                db.cambiar_estado_solicitud(&aprobada, EstadoSolicitud::Pendiente, &[cambio])
+                    .await,
+                Err(AppError::Conflict(_))
+            ));
+            let guardada = db
+                .obtener_solicitud("2", &creada.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(guardada.estado, EstadoSolicitud::Pendiente);
+            assert_eq!(
+                db.obtener_saldo("2", 1).await.unwrap(),
+                Some(Dias::enteros(3))
+            );
+        }
+
+        db.cambiar_estado_solicitud(
+            &aprobada,
+            EstadoSolicitud::Pendiente,
+            &[saldo(Some(Dias::enteros(3)), Dias::enteros(5))],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            db.obtener_saldo("2", 1).await.unwrap(),
+            Some(Dias::enteros(5))
+        );
+        assert!(matches!(
+            db.cambiar_estado_solicitud(&aprobada, EstadoSolicitud::Pendiente, &[])
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+}
@@ -1,11 +1,28 @@
 use axum::{
+    Json,
     http::StatusCode,
     response::{IntoResponse, Redirect, Response},
-    Json,
 };
+use serde::Serialize;
 use serde_json::json;
 use thiserror::Error;
 
+/// Error de validación de un campo concreto de la petición
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorCampo {
+    pub campo: String,
+    pub mensaje: String,
+}
+
+impl ErrorCampo {
+    pub fn new(campo: &str, mensaje: impl Into<String>) -> Self {
+        Self {
+            campo: campo.to_string(),
+            mensaje: mensaje.into(),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("Not found: {0}")]
@@ -20,6 +37,12 @@ pub enum AppError {
     #[error("Forbidden: {0}")]
     Forbidden(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Validation error: {0:?}")]
+    Validation(Vec<ErrorCampo>),
+
     #[error("Internal error: {0}")]
     InternalError(String),
 
@@ -47,6 +70,16 @@ impl IntoResponse for AppError {
                     .into_response();
             }
             AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::Validation(detalles) => {
+                // 422 con el detalle por campo; `error` resume todos los mensajes
+                let resumen: Vec<&str> = detalles.iter().map(|d| d.mensaje.as_str()).collect();
+                let body = Json(json!({
+                    "error": resumen.join(". "),
+                    "detalles": detalles,
+                }));
+                return (StatusCode::UNPROCESSABLE_ENTITY, body).into_response();
+            }
             AppError::InternalError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             AppError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             AppError::TemplateError(msg) => (
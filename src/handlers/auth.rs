@@ -1,17 +1,24 @@
 use axum::{
-    extract::State,
+    Extension, Form,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, header},
     response::{Html, IntoResponse, Redirect},
-    Form,
 };
 use serde::Deserialize;
+use std::net::SocketAddr;
 use tower_sessions::Session;
 
 use crate::{
-    db::DynamoDBClient,
+    config::ProxiesConfiables,
+    db::Database,
     error::{AppError, AppResult},
     services::auth::AuthService,
+    session,
 };
 
+/// Largo máximo del user agent que se guarda con la sesión
+const MAX_USER_AGENT: usize = 256;
+
 #[derive(Deserialize)]
 pub struct LoginForm {
     email: String,
@@ -201,8 +208,11 @@ pub async fn login_page(session: Session) -> AppResult<impl IntoResponse> {
 
 /// POST /login - Procesar login
 pub async fn login_submit(
-    State(db): State<DynamoDBClient>,
+    State(db): State<Database>,
     session: Session,
+    headers: HeaderMap,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    proxies: Option<Extension<ProxiesConfiables>>,
     Form(form): Form<LoginForm>,
 ) -> AppResult<impl IntoResponse> {
     let auth_service = AuthService::new(db);
@@ -228,9 +238,19 @@ pub async fn login_submit(
         ));
     }
 
-    // Crear sesión
-    session
-        .insert("empleado_id", empleado.id.clone())
+    // Crear sesión con los datos del dispositivo
+    // La IP es la de la conexión; X-Forwarded-For solo cuenta si viene de un proxy de confianza
+    let forwarded_for = headers.get("x-forwarded-for").and_then(|v| v.to_str().ok());
+    let proxies = proxies.map(|Extension(p)| p).unwrap_or_default();
+    let ip = connect_info.map(|Extension(ConnectInfo(addr))| {
+        proxies.ip_cliente(addr.ip(), forwarded_for).to_string()
+    });
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| ua.chars().take(MAX_USER_AGENT).collect());
+
+    session::iniciar(&session, &empleado.id, ip, user_agent)
         .await
         .map_err(|e| AppError::InternalError(format!("Failed to create session: {}", e)))?;
 
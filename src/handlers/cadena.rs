@@ -0,0 +1,57 @@
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::auth::AdminUser;
+use crate::db::Database;
+use crate::error::AppResult;
+use crate::models::{CadenaAprobacion, DatosCadena};
+use crate::services::CadenaService;
+
+/// GET /api/cadenas - Cadenas de aprobación por departamento
+#[debug_handler]
+pub async fn listar_cadenas_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<Json<Vec<CadenaAprobacion>>> {
+    let service = CadenaService::new(db);
+    Ok(Json(service.listar().await?))
+}
+
+/// POST /api/cadenas - Registra la cadena de un departamento (o la general, sin departamento)
+#[debug_handler]
+pub async fn crear_cadena_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosCadena>,
+) -> AppResult<(StatusCode, Json<CadenaAprobacion>)> {
+    let service = CadenaService::new(db);
+    let cadena = service.crear(datos).await?;
+    Ok((StatusCode::CREATED, Json(cadena)))
+}
+
+/// PUT /api/cadenas/:id - Reemplaza una cadena (no afecta a las solicitudes ya creadas)
+#[debug_handler]
+pub async fn actualizar_cadena_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosCadena>,
+) -> AppResult<Json<CadenaAprobacion>> {
+    let service = CadenaService::new(db);
+    Ok(Json(service.actualizar(&id, datos).await?))
+}
+
+/// DELETE /api/cadenas/:id
+#[debug_handler]
+pub async fn eliminar_cadena_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<StatusCode> {
+    let service = CadenaService::new(db);
+    service.eliminar(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,119 @@
+use askama::Template;
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{Html, IntoResponse},
+};
+
+use crate::auth::AdminUser;
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::{CalendarioEmpresa, DatosCalendario};
+use crate::services::CalendarioService;
+
+// calendarios.html recibe:
+//   - calendarios : Vec<CalendarioEmpresa>
+#[derive(Template)]
+#[template(path = "calendarios.html")]
+struct CalendariosTemplate {
+    calendarios: Vec<CalendarioEmpresa>,
+}
+
+// calendario_detalle.html recibe:
+//   - calendario : CalendarioEmpresa
+#[derive(Template)]
+#[template(path = "calendario_detalle.html")]
+struct CalendarioDetalleTemplate {
+    calendario: CalendarioEmpresa,
+}
+
+/// GET /calendarios - Calendarios de días inhábiles de la empresa (solo admin)
+#[debug_handler]
+pub async fn listar_calendarios(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<impl IntoResponse> {
+    let service = CalendarioService::new(db);
+    let calendarios = service.listar().await?;
+
+    let template = CalendariosTemplate { calendarios };
+    let html = template
+        .render()
+        .map_err(|e| AppError::TemplateError(format!("Error rendering template: {}", e)))?;
+    Ok(Html(html))
+}
+
+/// GET /calendarios/:id - Alcance y días de un calendario (solo admin)
+#[debug_handler]
+pub async fn obtener_calendario(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<impl IntoResponse> {
+    let service = CalendarioService::new(db);
+    let calendario = service.obtener(&id).await?;
+
+    let template = CalendarioDetalleTemplate { calendario };
+    let html = template
+        .render()
+        .map_err(|e| AppError::TemplateError(format!("Error rendering template: {}", e)))?;
+    Ok(Html(html))
+}
+
+/// GET /api/calendarios
+#[debug_handler]
+pub async fn listar_calendarios_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<Json<Vec<CalendarioEmpresa>>> {
+    let service = CalendarioService::new(db);
+    Ok(Json(service.listar().await?))
+}
+
+/// GET /api/calendarios/:id
+#[debug_handler]
+pub async fn obtener_calendario_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<Json<CalendarioEmpresa>> {
+    let service = CalendarioService::new(db);
+    Ok(Json(service.obtener(&id).await?))
+}
+
+/// POST /api/calendarios - Crea un calendario de días inhábiles
+#[debug_handler]
+pub async fn crear_calendario_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosCalendario>,
+) -> AppResult<(StatusCode, Json<CalendarioEmpresa>)> {
+    let service = CalendarioService::new(db);
+    let calendario = service.crear(datos).await?;
+    Ok((StatusCode::CREATED, Json(calendario)))
+}
+
+/// PUT /api/calendarios/:id - Reemplaza nombre, alcance y días del calendario
+#[debug_handler]
+pub async fn actualizar_calendario_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosCalendario>,
+) -> AppResult<Json<CalendarioEmpresa>> {
+    let service = CalendarioService::new(db);
+    Ok(Json(service.actualizar(&id, datos).await?))
+}
+
+/// DELETE /api/calendarios/:id
+#[debug_handler]
+pub async fn eliminar_calendario_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<StatusCode> {
+    let service = CalendarioService::new(db);
+    service.eliminar(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,46 @@
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::auth::AuthUser;
+use crate::db::Database;
+use crate::error::AppResult;
+use crate::models::{Delegacion, NuevaDelegacion};
+use crate::services::DelegacionService;
+
+/// GET /api/delegaciones - Delegaciones de aprobación que el usuario dio o recibió
+#[debug_handler]
+pub async fn listar_delegaciones_json(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Vec<Delegacion>>> {
+    let service = DelegacionService::new(db);
+    Ok(Json(service.listar(&auth_user.empleado.id).await?))
+}
+
+/// POST /api/delegaciones - Delega la aprobación del usuario (o, si es admin, la de
+/// `delegante_id`) en otro empleado entre dos fechas
+#[debug_handler]
+pub async fn crear_delegacion_json(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    Json(datos): Json<NuevaDelegacion>,
+) -> AppResult<(StatusCode, Json<Delegacion>)> {
+    let service = DelegacionService::new(db);
+    let delegacion = service.crear(&auth_user.empleado, datos).await?;
+    Ok((StatusCode::CREATED, Json(delegacion)))
+}
+
+/// DELETE /api/delegaciones/:id - Retira una delegación (su delegante o un admin)
+#[debug_handler]
+pub async fn eliminar_delegacion_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    auth_user: AuthUser,
+) -> AppResult<StatusCode> {
+    let service = DelegacionService::new(db);
+    service.eliminar(&auth_user.empleado, &id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
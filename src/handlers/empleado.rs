@@ -1,49 +1,106 @@
-use crate::db::DynamoDBClient;
-use crate::error::AppResult;
-use crate::models::Empleado;
-use crate::services::EmpleadoService;
+use crate::auth::{AdminUser, AuthUser};
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::horario::dia_corto;
+use crate::models::{
+    ActualizarEmpleado, DiasPorVencer, Empleado, FiltroSaldos, FiltroVencimientos, HorarioTrabajo,
+    NuevoEmpleado, Pagina, Paginacion, PeriodoVacaciones, SaldoPermiso,
+};
+use crate::services::{EmpleadoService, HorarioService};
 
 use askama::Template;
 use axum::{
     Json, debug_handler,
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::StatusCode,
     response::{Html, IntoResponse},
 };
+use chrono::{Datelike, Duration, NaiveDate, Utc};
 
 #[derive(Template)]
 #[template(path = "empleados.html")]
 struct EmpleadosTemplate {
     empleados: Vec<Empleado>,
+    primera_url: Option<String>,
+    siguiente_url: Option<String>,
 }
 
 #[derive(Template)]
 #[template(path = "empleado_detalle.html")]
 struct EmpleadoDetalleTemplate {
     empleado: Empleado,
+    // Periodos y saldos de permisos: solo para el propio empleado o un admin
+    periodos: Option<Vec<PeriodoVacaciones>>,
+    saldos: Option<Vec<SaldoPermiso>>,
+    hoy: NaiveDate,
+    horario: HorarioTrabajo,
+    // Dos semanas desde el lunes actual: (día, fecha, si trabaja)
+    dias_horario: Vec<(&'static str, NaiveDate, bool)>,
+}
+
+#[derive(Template)]
+#[template(path = "vencimientos.html")]
+struct VencimientosTemplate {
+    por_vencer: Vec<DiasPorVencer>,
+    dias: i64,
 }
 
-/// GET /empleados - Lista todos los empleados con sus días calculados
+/// GET /empleados - Lista una página de empleados con sus días calculados
 #[debug_handler]
-pub async fn listar_empleados(State(db): State<DynamoDBClient>) -> AppResult<impl IntoResponse> {
+pub async fn listar_empleados(
+    State(db): State<Database>,
+    Query(paginacion): Query<Paginacion>,
+) -> AppResult<impl IntoResponse> {
     let service = EmpleadoService::new(db);
-    let empleados = service.listar_empleados_con_dias().await?;
-    let template = EmpleadosTemplate { empleados };
+    let pagina = service
+        .listar_empleados_con_dias_pagina(&paginacion)
+        .await?;
+    let url = |cursor| super::url_pagina("/empleados", &[], paginacion.limite(), cursor);
+    let template = EmpleadosTemplate {
+        primera_url: paginacion.cursor.is_some().then(|| url(None)),
+        siguiente_url: pagina.siguiente_cursor.as_deref().map(|c| url(Some(c))),
+        empleados: pagina.elementos,
+    };
     let html = template.render().map_err(|e| {
         crate::error::AppError::TemplateError(format!("Error rendering template: {}", e))
     })?;
     Ok(Html(html))
 }
 
-/// GET /empleados/:id - Obtiene un empleado específico
+/// GET /empleados/:id - Obtiene un empleado específico; los periodos y los saldos de
+/// permisos solo se muestran al propio empleado o a un admin
 #[debug_handler]
 pub async fn obtener_empleado(
     Path(id): Path<String>,
-    State(db): State<DynamoDBClient>,
+    State(db): State<Database>,
+    auth_user: Option<AuthUser>,
 ) -> AppResult<impl IntoResponse> {
-    let service = EmpleadoService::new(db);
+    let service = EmpleadoService::new(db.clone());
     let empleado = service.obtener_empleado_con_dias(&id).await?;
+    let hoy = Utc::now().date_naive();
+    let ve_saldos = auth_user.is_some_and(|u| verificar_propio_o_admin(&u, &id).is_ok());
+    let (periodos, saldos) = if ve_saldos {
+        (
+            Some(service.listar_periodos(&id).await?),
+            Some(service.listar_saldos_permisos(&id, hoy.year()).await?),
+        )
+    } else {
+        (None, None)
+    };
+    let horario = HorarioService::new(db).horario_de(&empleado).await?;
+    let lunes = hoy - Duration::days(i64::from(hoy.weekday().num_days_from_monday()));
+    let dias_horario = lunes
+        .iter_days()
+        .take(14)
+        .map(|fecha| (dia_corto(fecha), fecha, horario.trabaja(fecha)))
+        .collect();
     let template = EmpleadoDetalleTemplate {
         empleado: empleado.clone(),
+        periodos,
+        saldos,
+        hoy,
+        horario,
+        dias_horario,
     };
     let html = template.render().map_err(|e| {
         crate::error::AppError::TemplateError(format!("Error rendering template: {}", e))
@@ -51,25 +108,161 @@ pub async fn obtener_empleado(
     Ok(Html(html))
 }
 
+/// GET /vencimientos - Días sin tomar que vencen pronto, para que RH actúe (solo admin)
+#[debug_handler]
+pub async fn listar_vencimientos(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Query(filtro): Query<FiltroVencimientos>,
+) -> AppResult<impl IntoResponse> {
+    let service = EmpleadoService::new(db);
+    let dias = filtro.dias();
+    let por_vencer = service.listar_por_vencer(dias).await?;
+    let template = VencimientosTemplate { por_vencer, dias };
+    let html = template.render().map_err(|e| {
+        crate::error::AppError::TemplateError(format!("Error rendering template: {}", e))
+    })?;
+    Ok(Html(html))
+}
+
 // ============ HANDLERS JSON (API) ============
 
-/// GET /api/empleados - API JSON de empleados
+/// Los saldos de un empleado solo los ve él mismo o un admin (incluyen incapacidades)
+fn verificar_propio_o_admin(auth_user: &AuthUser, empleado_id: &str) -> AppResult<()> {
+    if !auth_user.empleado.es_admin && auth_user.empleado.id != empleado_id {
+        return Err(AppError::Forbidden(
+            "No puedes consultar los saldos de otros empleados".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /api/empleados - API JSON de empleados (paginada con `?limit=&cursor=`)
 #[debug_handler]
 pub async fn listar_empleados_json(
-    State(db): State<DynamoDBClient>,
-) -> AppResult<Json<Vec<Empleado>>> {
+    State(db): State<Database>,
+    Query(paginacion): Query<Paginacion>,
+) -> AppResult<Json<Pagina<Empleado>>> {
     let service = EmpleadoService::new(db);
-    let empleados = service.listar_empleados_con_dias().await?;
-    Ok(Json(empleados))
+    let pagina = service
+        .listar_empleados_con_dias_pagina(&paginacion)
+        .await?;
+    Ok(Json(pagina))
 }
 
 /// GET /api/empleados/:id - API JSON de empleado específico
 #[debug_handler]
 pub async fn obtener_empleado_json(
     Path(id): Path<String>,
-    State(db): State<DynamoDBClient>,
+    State(db): State<Database>,
 ) -> AppResult<Json<Empleado>> {
     let service = EmpleadoService::new(db);
     let empleado = service.obtener_empleado_con_dias(&id).await?;
     Ok(Json(empleado))
 }
+
+/// GET /api/empleados/:id/periodos - Periodos de servicio con derecho, tomados y disponibles
+/// (solo el propio empleado o un admin)
+#[debug_handler]
+pub async fn listar_periodos_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Vec<PeriodoVacaciones>>> {
+    verificar_propio_o_admin(&auth_user, &id)?;
+    let service = EmpleadoService::new(db);
+    Ok(Json(service.listar_periodos(&id).await?))
+}
+
+/// GET /api/empleados/:id/permisos?ano=2025 - Saldo de cada tipo de permiso en el año
+/// (por omisión, el año en curso); solo el propio empleado o un admin
+#[debug_handler]
+pub async fn listar_saldos_permisos_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    Query(filtro): Query<FiltroSaldos>,
+) -> AppResult<Json<Vec<SaldoPermiso>>> {
+    verificar_propio_o_admin(&auth_user, &id)?;
+    let service = EmpleadoService::new(db);
+    let ano = filtro.ano.unwrap_or_else(|| Utc::now().year());
+    Ok(Json(service.listar_saldos_permisos(&id, ano).await?))
+}
+
+/// GET /api/vencimientos?dias=90 - Periodos con días sin tomar que vencen en los próximos días
+#[debug_handler]
+pub async fn listar_vencimientos_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Query(filtro): Query<FiltroVencimientos>,
+) -> AppResult<Json<Vec<DiasPorVencer>>> {
+    let service = EmpleadoService::new(db);
+    Ok(Json(service.listar_por_vencer(filtro.dias()).await?))
+}
+
+/// POST /api/empleados - Da de alta un empleado (solo admin)
+#[debug_handler]
+pub async fn crear_empleado_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(nuevo): Json<NuevoEmpleado>,
+) -> AppResult<(StatusCode, Json<Empleado>)> {
+    let service = EmpleadoService::new(db);
+    let empleado = service.crear_empleado(nuevo).await?;
+    Ok((StatusCode::CREATED, Json(empleado)))
+}
+
+/// PUT /api/empleados/:id - Actualiza datos de un empleado (solo admin)
+#[debug_handler]
+pub async fn actualizar_empleado_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(cambios): Json<ActualizarEmpleado>,
+) -> AppResult<Json<Empleado>> {
+    let service = EmpleadoService::new(db);
+    let empleado = service.actualizar_empleado(&id, cambios).await?;
+    Ok(Json(empleado))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::memory::MemoryStore;
+
+    async fn detalle(db: &Database, id: &str, visitante: Option<&str>) -> String {
+        let auth_user = match visitante {
+            Some(visitante) => Some(AuthUser {
+                empleado: db.obtener_empleado(visitante).await.unwrap().unwrap(),
+            }),
+            None => None,
+        };
+        let respuesta = obtener_empleado(Path(id.to_string()), State(db.clone()), auth_user)
+            .await
+            .unwrap()
+            .into_response();
+        let cuerpo = axum::body::to_bytes(respuesta.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(cuerpo.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_detalle_oculta_saldos_ajenos() {
+        let db = MemoryStore::demo();
+
+        // Anónimo, u otro empleado sin ser admin: la página sí, los saldos no
+        for visitante in [None, Some("2")] {
+            let html = detalle(&db, "1", visitante).await;
+            assert!(html.contains("Ana Administradora"));
+            assert!(!html.contains("<h2>Permisos"));
+            assert!(!html.contains("<h2>Periodos de servicio"));
+        }
+        // El propio empleado y un admin sí los ven
+        for (id, visitante) in [("2", "2"), ("2", "1")] {
+            let html = detalle(&db, id, Some(visitante)).await;
+            assert!(html.contains("<h2>Permisos"));
+            assert!(html.contains("<h2>Periodos de servicio"));
+        }
+    }
+}
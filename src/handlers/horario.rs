@@ -0,0 +1,57 @@
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::auth::AdminUser;
+use crate::db::Database;
+use crate::error::AppResult;
+use crate::models::{DatosHorario, HorarioTrabajo};
+use crate::services::HorarioService;
+
+/// GET /api/horarios - Horarios de trabajo (incluye el de lunes a viernes)
+#[debug_handler]
+pub async fn listar_horarios_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<Json<Vec<HorarioTrabajo>>> {
+    let service = HorarioService::new(db);
+    Ok(Json(service.listar().await?))
+}
+
+/// POST /api/horarios - Registra un horario semanal o rotativo
+#[debug_handler]
+pub async fn crear_horario_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosHorario>,
+) -> AppResult<(StatusCode, Json<HorarioTrabajo>)> {
+    let service = HorarioService::new(db);
+    let horario = service.crear(datos).await?;
+    Ok((StatusCode::CREATED, Json(horario)))
+}
+
+/// PUT /api/horarios/:id - Reemplaza un horario (no afecta a las solicitudes ya creadas)
+#[debug_handler]
+pub async fn actualizar_horario_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosHorario>,
+) -> AppResult<Json<HorarioTrabajo>> {
+    let service = HorarioService::new(db);
+    Ok(Json(service.actualizar(&id, datos).await?))
+}
+
+/// DELETE /api/horarios/:id
+#[debug_handler]
+pub async fn eliminar_horario_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<StatusCode> {
+    let service = HorarioService::new(db);
+    service.eliminar(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,5 +1,11 @@
 pub mod auth;
+pub mod cadena;
+pub mod calendario;
+pub mod delegacion;
 pub mod empleado;
+pub mod horario;
+pub mod politica;
+pub mod sesion;
 pub mod solicitud;
 
 use crate::error::AppResult;
@@ -18,6 +24,26 @@ pub async fn home() -> AppResult<impl IntoResponse> {
     Ok(Html(html))
 }
 
+/// URL de una página de un listado HTML, conservando sus filtros
+/// (sin `cursor` es la primera página)
+fn url_pagina(
+    ruta: &str,
+    filtros: &[(&str, Option<&str>)],
+    limite: usize,
+    cursor: Option<&str>,
+) -> String {
+    let mut url = format!("{}?limit={}", ruta, limite);
+    if let Some(cursor) = cursor {
+        url.push_str(&format!("&cursor={}", cursor));
+    }
+    for (nombre, valor) in filtros {
+        if let Some(valor) = valor {
+            url.push_str(&format!("&{}={}", nombre, urlencoding::encode(valor)));
+        }
+    }
+    url
+}
+
 pub async fn health() -> &'static str {
     "OK"
 }
@@ -0,0 +1,57 @@
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+    http::StatusCode,
+};
+
+use crate::auth::AdminUser;
+use crate::db::Database;
+use crate::error::AppResult;
+use crate::models::{DatosPolitica, PoliticaVacaciones};
+use crate::services::PoliticaService;
+
+/// GET /api/politicas - Políticas de días por antigüedad (LFT y de la empresa)
+#[debug_handler]
+pub async fn listar_politicas_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<Json<Vec<PoliticaVacaciones>>> {
+    let service = PoliticaService::new(db);
+    Ok(Json(service.listar().await?))
+}
+
+/// POST /api/politicas - Registra una política (de la empresa o una reforma futura)
+#[debug_handler]
+pub async fn crear_politica_json(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosPolitica>,
+) -> AppResult<(StatusCode, Json<PoliticaVacaciones>)> {
+    let service = PoliticaService::new(db);
+    let politica = service.crear(datos).await?;
+    Ok((StatusCode::CREATED, Json(politica)))
+}
+
+/// PUT /api/politicas/:id - Reemplaza una política registrada
+#[debug_handler]
+pub async fn actualizar_politica_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Json(datos): Json<DatosPolitica>,
+) -> AppResult<Json<PoliticaVacaciones>> {
+    let service = PoliticaService::new(db);
+    Ok(Json(service.actualizar(&id, datos).await?))
+}
+
+/// DELETE /api/politicas/:id
+#[debug_handler]
+pub async fn eliminar_politica_json(
+    Path(id): Path<String>,
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+) -> AppResult<StatusCode> {
+    let service = PoliticaService::new(db);
+    service.eliminar(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,134 @@
+use askama::Template;
+use axum::{
+    Json, debug_handler,
+    extract::{Path, State},
+    response::{Html, IntoResponse, Redirect},
+};
+use serde::Serialize;
+use tower_sessions::Session;
+
+use crate::auth::{AdminUser, AuthUser};
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::Sesion;
+use crate::services::SesionService;
+
+/// Fila de la tabla de "Mis sesiones"
+struct SesionVista {
+    id: String,
+    dispositivo: String,
+    ip: String,
+    inicio: String,
+    ultima_actividad: String,
+    actual: bool,
+}
+
+impl SesionVista {
+    fn new(sesion: Sesion, actual: Option<&str>) -> Self {
+        let fecha = |texto: &str| {
+            chrono::DateTime::parse_from_rfc3339(texto)
+                .map(|f| f.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|_| texto.to_string())
+        };
+        Self {
+            actual: actual == Some(sesion.session_id.as_str()),
+            dispositivo: sesion
+                .user_agent
+                .unwrap_or_else(|| "Desconocido".to_string()),
+            ip: sesion.ip.unwrap_or_else(|| "—".to_string()),
+            inicio: fecha(&sesion.created_at),
+            ultima_actividad: sesion
+                .ultima_actividad
+                .as_deref()
+                .map(fecha)
+                .unwrap_or_default(),
+            id: sesion.session_id,
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "mis_sesiones.html")]
+struct MisSesionesTemplate {
+    sesiones: Vec<SesionVista>,
+}
+
+#[derive(Serialize)]
+pub struct SesionesRevocadas {
+    pub revocadas: usize,
+}
+
+/// GET /mis-sesiones - Sesiones abiertas del usuario en sus dispositivos
+#[debug_handler]
+pub async fn mis_sesiones(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    session: Session,
+) -> AppResult<impl IntoResponse> {
+    let service = SesionService::new(db);
+    let actual = session.id().map(|id| id.to_string());
+    let sesiones = service
+        .listar_activas(&auth_user.empleado.id)
+        .await?
+        .into_iter()
+        .map(|s| SesionVista::new(s, actual.as_deref()))
+        .collect();
+
+    let template = MisSesionesTemplate { sesiones };
+    let html = template
+        .render()
+        .map_err(|e| AppError::TemplateError(format!("Error rendering template: {}", e)))?;
+    Ok(Html(html))
+}
+
+/// POST /mis-sesiones/:session_id/revocar - Cierra una sesión propia
+#[debug_handler]
+pub async fn revocar_sesion(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    session: Session,
+    Path(session_id): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    let service = SesionService::new(db);
+    service.revocar(&auth_user.empleado.id, &session_id).await?;
+
+    // Revocar la sesión actual equivale a cerrar sesión
+    if session.id().is_some_and(|id| id.to_string() == session_id) {
+        session
+            .flush()
+            .await
+            .map_err(|e| AppError::InternalError(format!("Failed to destroy session: {}", e)))?;
+        return Ok(Redirect::to("/login"));
+    }
+
+    Ok(Redirect::to("/mis-sesiones"))
+}
+
+/// POST /mis-sesiones/revocar-otras - Cierra todas las sesiones salvo la actual
+#[debug_handler]
+pub async fn revocar_otras_sesiones(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    session: Session,
+) -> AppResult<impl IntoResponse> {
+    let service = SesionService::new(db);
+    let actual = session.id().map(|id| id.to_string());
+    service
+        .revocar_todas(&auth_user.empleado.id, actual.as_deref())
+        .await?;
+
+    Ok(Redirect::to("/mis-sesiones"))
+}
+
+/// POST /api/empleados/:id/sesiones/revocar - Cierra todas las sesiones de un
+/// empleado (baja, cambio de contraseña, sesión robada)
+#[debug_handler]
+pub async fn revocar_sesiones_empleado(
+    State(db): State<Database>,
+    _admin_user: AdminUser,
+    Path(empleado_id): Path<String>,
+) -> AppResult<Json<SesionesRevocadas>> {
+    let service = SesionService::new(db);
+    let revocadas = service.revocar_todas(&empleado_id, None).await?;
+    Ok(Json(SesionesRevocadas { revocadas }))
+}
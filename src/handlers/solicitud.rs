@@ -5,48 +5,109 @@ use axum::{
     http::StatusCode,
     response::{Html, IntoResponse},
 };
-// use std::collections::HashMap;
+use chrono::Datelike;
+use serde::Serialize;
+use std::collections::HashMap;
 
-use crate::auth::{AdminUser, AuthUser};
-use crate::db::DynamoDBClient;
-use crate::error::{AppError, AppResult};
-use crate::models::{Empleado, NuevaSolicitud, SolicitudVacaciones};
-use crate::services::{EmpleadoService, SolicitudService};
-
-use chrono::Utc;
-use uuid::Uuid;
+use crate::auth::{AdminUser, AprobadorUser, AuthUser};
+use crate::db::Database;
+use crate::error::{AppError, AppResult, ErrorCampo};
+use crate::models::calendario::{self, Feriado};
+use crate::models::dias::HORAS_POR_DIA;
+use crate::models::horario::horario_de;
+use crate::models::{
+    Empleado, EstadoSolicitud, FiltroSolicitudes, HorarioTrabajo, NuevaSolicitud, Pagina,
+    Paginacion, PatronHorario, Reprogramacion, Resolucion, Revocacion, SolicitudPorAprobar,
+    SolicitudVacaciones, TipoPermiso,
+};
+use crate::services::{CalendarioService, EmpleadoService, SolicitudService};
 
 // ─── templates ──────────────────────────────────────────────
 // solicitudes.html recibe:
 //   - solicitudes          : Vec<SolicitudVacaciones>
 //   - empleado_nombres     : HashMap<String, String>   // id → nombre
 //   - estado_filtro        : Option<String>            // query param ?estado=…
+//   - primera_url          : Option<String>            // enlace a la primera página (si no se está en ella)
+//   - siguiente_url        : Option<String>            // enlace a la página siguiente
+//   - es_admin             : bool                      // muestra revocar
+//   - resolutores          : HashMap<String, String>   // resuelto_por y delegantes → nombre
 #[derive(Template)]
 #[template(path = "solicitudes.html")]
 struct SolicitudesTemplate {
     solicitudes: Vec<SolicitudVacaciones>,
     //empleado_nombres: HashMap<String, String>,
     estado_filtro: Option<String>,
+    primera_url: Option<String>,
+    siguiente_url: Option<String>,
+    es_admin: bool,
+    resolutores: HashMap<String, String>,
     total: usize,
     pendientes: usize,
     aprobadas: usize,
     rechazadas: usize,
 }
 
+// por_aprobar.html recibe:
+//   - por_aprobar          : Vec<SolicitudPorAprobar>  // pendientes cuyo paso actual decide el usuario (o un delegante suyo)
+//   - delegantes           : HashMap<String, String>   // en_nombre_de → nombre
+#[derive(Template)]
+#[template(path = "por_aprobar.html")]
+struct PorAprobarTemplate {
+    por_aprobar: Vec<SolicitudPorAprobar>,
+    delegantes: HashMap<String, String>,
+}
+
 // nueva_solicitud.html recibe:
 //   - empleados                : Vec<Empleado>   (con dias_disponibles calculados)
 //   - empleado_preseleccionado : Option<String>  // query param ?empleado_id=…
+//   - feriados                 : Vec<Feriado>    // de este año y el siguiente, para el preview
+//   - dias_empresa             : Vec<(String, Vec<Feriado>)>  // empleado_id → días de sus calendarios
+//   - horarios                 : Vec<(String, HorarioTrabajo)>  // empleado_id → días que trabaja
+//   - tipos                    : [TipoPermiso; 7] // con sus reglas, para el select y el preview
 #[derive(Template)]
 #[template(path = "nueva_solicitud.html")]
 struct NuevaSolicitudTemplate {
     empleados: Vec<Empleado>,
     empleado_preseleccionado: Option<String>,
+    feriados: Vec<Feriado>,
+    dias_empresa: Vec<(String, Vec<Feriado>)>,
+    horarios: Vec<(String, HorarioTrabajo)>,
+    tipos: [TipoPermiso; 7],
+    horas_por_dia: i32,
+}
+
+// ─── respuestas ─────────────────────────────────────────────
+/// Solicitud recién creada junto con los feriados que no se contaron como días
+#[derive(Serialize)]
+pub struct SolicitudCreada {
+    #[serde(flatten)]
+    pub solicitud: SolicitudVacaciones,
+    pub feriados_excluidos: Vec<Feriado>,
 }
 
 // ─── query params ───────────────────────────────────────────
 #[derive(serde::Deserialize)]
 pub struct SolicitudesQuery {
-    pub estado: Option<String>,
+    pub estado: Option<EstadoSolicitud>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+impl SolicitudesQuery {
+    fn paginacion(&self) -> Paginacion {
+        Paginacion {
+            limit: self.limit,
+            cursor: self.cursor.clone(),
+        }
+    }
+
+    /// Admins ven todas las solicitudes, empleados solo las suyas
+    fn filtro(&self, auth_user: &AuthUser) -> FiltroSolicitudes {
+        FiltroSolicitudes {
+            empleado_id: (!auth_user.empleado.es_admin).then(|| auth_user.empleado.id.clone()),
+            estado: self.estado,
+        }
+    }
 }
 
 #[derive(serde::Deserialize)]
@@ -59,43 +120,54 @@ pub struct NuevaSolicitudQuery {
 /// GET /solicitudes
 #[debug_handler]
 pub async fn listar_solicitudes(
-    State(db): State<DynamoDBClient>,
+    State(db): State<Database>,
     auth_user: AuthUser,
     Query(query): Query<SolicitudesQuery>,
 ) -> AppResult<impl IntoResponse> {
-    // 1. traer solicitudes según permisos: admins ven todas, empleados solo las suyas
+    // 1. traer una página de solicitudes según permisos y ?estado=…
     let service = SolicitudService::new(db.clone());
-    let solicitudes = if auth_user.empleado.es_admin {
-        service.listar_solicitudes().await?
-    } else {
-        service
-            .listar_solicitudes_por_empleado(&auth_user.empleado.id)
-            .await?
+    let paginacion = query.paginacion();
+    let pagina = service
+        .listar_solicitudes_pagina(&query.filtro(&auth_user), &paginacion)
+        .await?;
+    let url = |cursor| {
+        super::url_pagina(
+            "/solicitudes",
+            &[("estado", query.estado.map(|e| e.as_str()))],
+            paginacion.limite(),
+            cursor,
+        )
     };
+    let primera_url = paginacion.cursor.is_some().then(|| url(None));
+    let siguiente_url = pagina.siguiente_cursor.as_deref().map(|c| url(Some(c)));
+    let solicitudes = pagina.elementos;
 
-    // 2. filtrar client-side si viene ?estado=…
-    let solicitudes: Vec<_> = match &query.estado {
-        Some(estado) => solicitudes
-            .into_iter()
-            .filter(|s| s.estado == *estado)
-            .collect(),
-        None => solicitudes,
-    };
+    // 2. resumen de la página mostrada
     let total = solicitudes.len();
     let pendientes = solicitudes
         .iter()
-        .filter(|s| s.estado == "pendiente")
+        .filter(|s| s.estado == EstadoSolicitud::Pendiente)
         .count();
     let aprobadas = solicitudes
         .iter()
-        .filter(|s| s.estado == "aprobada")
+        .filter(|s| s.estado == EstadoSolicitud::Aprobada)
         .count();
     let rechazadas = solicitudes
         .iter()
-        .filter(|s| s.estado == "rechazada")
+        .filter(|s| s.estado == EstadoSolicitud::Rechazada)
         .count();
 
-    // 3. traer nombres de empleados para mostrar en la tabla
+    // 3. nombres de quienes aprobaron o rechazaron las solicitudes de la página y de
+    //    aquellos en cuyo nombre se decidió
+    let ids = solicitudes
+        .iter()
+        .flat_map(|s| [s.resuelto_por.as_ref(), s.resuelta_en_nombre_de()])
+        .flatten()
+        .cloned()
+        .collect();
+    let resolutores = EmpleadoService::new(db.clone()).nombres(ids).await?;
+
+    // traer nombres de empleados para mostrar en la tabla
     // let service_empleados = EmpleadoService::new(db.clone());
     // let empleados = service_empleados.listar_empleados_con_dias().await?;
 
@@ -107,7 +179,11 @@ pub async fn listar_solicitudes(
     let template = SolicitudesTemplate {
         solicitudes,
         // empleado_nombres,
-        estado_filtro: query.estado,
+        estado_filtro: query.estado.map(|e| e.to_string()),
+        primera_url,
+        siguiente_url,
+        es_admin: auth_user.empleado.es_admin,
+        resolutores,
         total,
         pendientes,
         aprobadas,
@@ -121,20 +197,73 @@ pub async fn listar_solicitudes(
     Ok(Html(html))
 }
 
+/// GET /api/solicitudes - API JSON de solicitudes (paginada con `?limit=&cursor=`)
+#[debug_handler]
+pub async fn listar_solicitudes_json(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    Query(query): Query<SolicitudesQuery>,
+) -> AppResult<Json<Pagina<SolicitudVacaciones>>> {
+    let service = SolicitudService::new(db);
+    let pagina = service
+        .listar_solicitudes_pagina(&query.filtro(&auth_user), &query.paginacion())
+        .await?;
+    Ok(Json(pagina))
+}
+
 /// GET /solicitudes/nueva
 #[debug_handler]
 pub async fn nueva_solicitud_form(
-    State(db): State<DynamoDBClient>,
-    _auth_user: AuthUser,
+    State(db): State<Database>,
+    auth_user: AuthUser,
     Query(query): Query<NuevaSolicitudQuery>,
 ) -> AppResult<impl IntoResponse> {
-    // traer empleados con días disponibles calculados
-
+    // traer empleados con días disponibles calculados: un empleado solo puede
+    // solicitar para sí mismo, así que solo los admins necesitan la lista completa
     let service_empleados = EmpleadoService::new(db.clone());
-    let empleados = service_empleados.listar_empleados_con_dias().await?;
+    let empleados = if auth_user.empleado.es_admin {
+        service_empleados.listar_empleados_con_dias().await?
+    } else {
+        vec![
+            service_empleados
+                .obtener_empleado_con_dias(&auth_user.empleado.id)
+                .await?,
+        ]
+    };
+    let ano = chrono::Utc::now().year();
+
+    // días inhábiles de la empresa que aplican a cada empleado del formulario
+    let calendarios = CalendarioService::new(db.clone()).listar().await?;
+    let dias_empresa = empleados
+        .iter()
+        .map(|emp| {
+            let dias = calendarios
+                .iter()
+                .filter(|c| c.aplica_a(emp))
+                .flat_map(|c| c.dias.iter().cloned())
+                .filter(|d| d.fecha.year() >= ano)
+                .collect();
+            (emp.id.clone(), dias)
+        })
+        .collect();
+
+    // y el horario de cada uno, para no contar sus días de descanso
+    let registrados = db.listar_horarios().await?;
+    let horarios = empleados
+        .iter()
+        .map(|emp| (emp.id.clone(), horario_de(&registrados, emp)))
+        .collect();
+
     let template = NuevaSolicitudTemplate {
         empleados,
         empleado_preseleccionado: query.empleado_id,
+        feriados: (ano..=ano + 1)
+            .flat_map(calendario::feriados_oficiales)
+            .collect(),
+        dias_empresa,
+        horarios,
+        tipos: TipoPermiso::TODOS,
+        horas_por_dia: HORAS_POR_DIA,
     };
     let html = template.render().map_err(|e| {
         crate::error::AppError::TemplateError(format!("Error rendering template: {}", e))
@@ -144,10 +273,10 @@ pub async fn nueva_solicitud_form(
 
 /// POST /solicitudes - Crea una nueva solicitud de vacaciones
 pub async fn crear_solicitud(
-    State(db): State<DynamoDBClient>,
+    State(db): State<Database>,
     auth_user: AuthUser,
     Json(solicitud): Json<NuevaSolicitud>,
-) -> AppResult<(StatusCode, Json<SolicitudVacaciones>)> {
+) -> AppResult<(StatusCode, Json<SolicitudCreada>)> {
     // Validar: usuario solo puede crear para su propio empleado_id (admins pueden para otros)
     if !auth_user.empleado.es_admin && solicitud.empleado_id != auth_user.empleado.id {
         return Err(AppError::Forbidden(
@@ -155,95 +284,255 @@ pub async fn crear_solicitud(
         ));
     }
 
-    // Calcular días solicitados (simplificado, asume formato YYYY-MM-DD)
-    let dias = calcular_dias_entre_fechas(&solicitud.fecha_inicio, &solicitud.fecha_fin)?;
-
-    let nueva_solicitud = SolicitudVacaciones {
-        id: Uuid::new_v4().to_string(),
-        empleado_id: solicitud.empleado_id,
-        empleado_nombre: solicitud.empleado_nombre,
-        fecha_inicio: solicitud.fecha_inicio,
-        fecha_fin: solicitud.fecha_fin,
-        estado: "pendiente".to_string(),
-        dias_solicitados: dias,
-        created_at: Utc::now().to_rfc3339(),
+    // El empleado se resuelve desde su registro: de ahí salen nombre y departamento
+    let empleado = match EmpleadoService::new(db.clone())
+        .obtener_empleado(&solicitud.empleado_id)
+        .await
+    {
+        Err(AppError::NotFound(_)) => {
+            return Err(AppError::Validation(vec![ErrorCampo::new(
+                "empleado_id",
+                format!("No existe el empleado {}", solicitud.empleado_id),
+            )]));
+        }
+        resultado => resultado?,
     };
 
-    // Guardar en DynamoDB
-    db.client
-        .put_item()
-        .table_name(&db.table_name)
-        .set_item(Some(nueva_solicitud.to_item()))
-        .send()
-        .await
-        .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
-    Ok((StatusCode::CREATED, Json(nueva_solicitud)))
-}
-
-fn calcular_dias_entre_fechas(inicio: &str, fin: &str) -> AppResult<i32> {                                                               
-      use chrono::{NaiveDate, Datelike, Duration};                                                                                         
-                                                                                                                                           
-      let fecha_inicio = NaiveDate::parse_from_str(inicio, "%Y-%m-%d").map_err(|_| {                                                       
-          AppError::BadRequest("Formato de fecha_inicio inválido. Use YYYY-MM-DD".to_string())                                             
-      })?;                                                                                                                                 
-                                                                                                                                           
-      let fecha_fin = NaiveDate::parse_from_str(fin, "%Y-%m-%d").map_err(|_| {                                                             
-          AppError::BadRequest("Formato de fecha_fin inválido. Use YYYY-MM-DD".to_string())                                                
-      })?;                                                                                                                                 
-                                                                                                                                           
-      if fecha_fin < fecha_inicio {                                                                                                        
-          return Err(AppError::BadRequest(                                                                                                 
-              "La fecha_fin debe ser posterior a fecha_inicio".to_string(),                                                                
-          ));                                                                                                                              
-      }                                                                                                                                    
-                                                                                                                                           
-      // Contar solo días laborables (lunes a viernes)                                                                                     
-      let mut dias_laborables = 0;                                                                                                         
-      let mut fecha_actual = fecha_inicio;                                                                                                 
-                                                                                                                                           
-      while fecha_actual <= fecha_fin {                                                                                                    
-          // weekday(): 0=Lunes, 1=Martes, ..., 4=Viernes, 5=Sábado, 6=Domingo                                                             
-          let dia_semana = fecha_actual.weekday().num_days_from_monday();                                                                  
-                                                                                                                                           
-          if dia_semana < 5 {  // Lunes a Viernes (0-4)                                                                                    
-              dias_laborables += 1;                                                                                                        
-          }                                                                                                                                
-                                                                                                                                           
-          fecha_actual += Duration::days(1);                                                                                               
-      }                                                                                                                                    
-                                                                                                                                           
-      Ok(dias_laborables)                                                                                                                  
-  }
-
-/// POST /api/solicitudes/:empleado_id/:solicitud_id/aprobar
+    // Calcular días solicitados (formato YYYY-MM-DD) con los calendarios del empleado, o
+    // naturales si el tipo de permiso así los cuenta
+    let (dias, conteo) = CalendarioService::new(db.clone())
+        .contar_dias_solicitud(
+            &empleado,
+            solicitud.tipo_permiso,
+            &solicitud.fecha_inicio,
+            &solicitud.fecha_fin,
+            &solicitud.jornada,
+        )
+        .await?;
+
+    let service = SolicitudService::new(db);
+    let nueva_solicitud = service
+        .crear_solicitud(&empleado, solicitud, dias, &auth_user.empleado.id)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(SolicitudCreada {
+            solicitud: nueva_solicitud,
+            feriados_excluidos: conteo.feriados_excluidos,
+        }),
+    ))
+}
+
+/// POST /api/solicitudes/:empleado_id/:solicitud_id/cancelar - El empleado retira su
+/// solicitud pendiente, o una aprobada antes de que empiece (los días vuelven al saldo)
+#[debug_handler]
+pub async fn cancelar_solicitud(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    Path((empleado_id, solicitud_id)): Path<(String, String)>,
+) -> AppResult<Json<SolicitudVacaciones>> {
+    if !auth_user.empleado.es_admin && empleado_id != auth_user.empleado.id {
+        return Err(AppError::Forbidden(
+            "No puedes cancelar solicitudes de otros empleados".to_string(),
+        ));
+    }
+
+    let service = SolicitudService::new(db);
+    let solicitud = service
+        .cancelar(&empleado_id, &solicitud_id, &auth_user.empleado.id, None)
+        .await?;
+    Ok(Json(solicitud))
+}
+
+/// POST /api/solicitudes/:empleado_id/:solicitud_id/reprogramar - Crea una nueva solicitud
+/// pendiente con otras fechas y cancela la original
+#[debug_handler]
+pub async fn reprogramar_solicitud(
+    State(db): State<Database>,
+    auth_user: AuthUser,
+    Path((empleado_id, solicitud_id)): Path<(String, String)>,
+    Json(reprogramacion): Json<Reprogramacion>,
+) -> AppResult<(StatusCode, Json<SolicitudCreada>)> {
+    if !auth_user.empleado.es_admin && empleado_id != auth_user.empleado.id {
+        return Err(AppError::Forbidden(
+            "No puedes reprogramar solicitudes de otros empleados".to_string(),
+        ));
+    }
+
+    let empleado = EmpleadoService::new(db.clone())
+        .obtener_empleado(&empleado_id)
+        .await?;
+    let service = SolicitudService::new(db.clone());
+    // La nueva solicitud es del mismo tipo que la original y sus días se cuentan igual
+    let original = service
+        .obtener_solicitud(&empleado_id, &solicitud_id)
+        .await?;
+    let (dias, conteo) = CalendarioService::new(db)
+        .contar_dias_solicitud(
+            &empleado,
+            original.tipo_permiso,
+            &reprogramacion.fecha_inicio,
+            &reprogramacion.fecha_fin,
+            &reprogramacion.jornada,
+        )
+        .await?;
+
+    let fechas = NuevaSolicitud {
+        empleado_id: empleado.id.clone(),
+        fecha_inicio: reprogramacion.fecha_inicio,
+        fecha_fin: reprogramacion.fecha_fin,
+        jornada: reprogramacion.jornada,
+        tipo_permiso: original.tipo_permiso,
+        documento: original.documento,
+    };
+    let nueva_solicitud = service
+        .reprogramar(
+            &empleado,
+            &solicitud_id,
+            fechas,
+            dias,
+            &auth_user.empleado.id,
+        )
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(SolicitudCreada {
+            solicitud: nueva_solicitud,
+            feriados_excluidos: conteo.feriados_excluidos,
+        }),
+    ))
+}
+
+/// GET /solicitudes/por-aprobar - Bandeja de solicitudes (de cualquier empleado) que esperan
+/// la decisión del usuario, por sí mismo o por las delegaciones que tiene vigentes
+#[debug_handler]
+pub async fn por_aprobar(
+    State(db): State<Database>,
+    aprobador: AprobadorUser,
+) -> AppResult<impl IntoResponse> {
+    let por_aprobar = SolicitudService::new(db.clone())
+        .listar_por_aprobar(&aprobador.empleado, &aprobador.delegantes)
+        .await?;
+    let ids = por_aprobar
+        .iter()
+        .filter_map(|p| p.en_nombre_de.clone())
+        .collect();
+    let delegantes = EmpleadoService::new(db).nombres(ids).await?;
+
+    let template = PorAprobarTemplate {
+        por_aprobar,
+        delegantes,
+    };
+    let html = template.render().map_err(|e| {
+        crate::error::AppError::TemplateError(format!("Error rendering template: {}", e))
+    })?;
+
+    Ok(Html(html))
+}
+
+/// GET /api/solicitudes/por-aprobar - Solicitudes cuyo paso actual decide el usuario, o un
+/// empleado que le delegó su aprobación (`en_nombre_de`)
+#[debug_handler]
+pub async fn listar_por_aprobar_json(
+    State(db): State<Database>,
+    aprobador: AprobadorUser,
+) -> AppResult<Json<Vec<SolicitudPorAprobar>>> {
+    let service = SolicitudService::new(db);
+    Ok(Json(
+        service
+            .listar_por_aprobar(&aprobador.empleado, &aprobador.delegantes)
+            .await?,
+    ))
+}
+
+/// POST /api/solicitudes/:empleado_id/:solicitud_id/aprobar - Aprueba el paso actual de la
+/// cadena (solo quien lo decide o su delegado); acepta un comentario opcional
 #[debug_handler]
 pub async fn aprobar_solicitud(
-    State(db): State<DynamoDBClient>,
-    _admin_user: AdminUser,
+    State(db): State<Database>,
+    aprobador: AprobadorUser,
     Path((empleado_id, solicitud_id)): Path<(String, String)>,
+    resolucion: Option<Json<Resolucion>>,
 ) -> AppResult<Json<SolicitudVacaciones>> {
-    use crate::services::SolicitudService;
+    let comentario = resolucion
+        .and_then(|Json(r)| r.comentario)
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty());
 
     let service = SolicitudService::new(db);
     let solicitud = service
-        .actualizar_estado(&empleado_id, &solicitud_id, "aprobada")
+        .decidir(
+            &empleado_id,
+            &solicitud_id,
+            &aprobador.empleado,
+            &aprobador.delegantes,
+            true,
+            comentario,
+        )
         .await?;
     Ok(Json(solicitud))
 }
 
-/// POST /api/solicitudes/:empleado_id/:solicitud_id/rechazar
+/// POST /api/solicitudes/:empleado_id/:solicitud_id/rechazar - Rechaza en el paso actual de
+/// la cadena (solo quien lo decide o su delegado); el comentario (motivo del rechazo) es
+/// obligatorio
 #[debug_handler]
 pub async fn rechazar_solicitud(
-    State(db): State<DynamoDBClient>,
-    _admin_user: AdminUser,
+    State(db): State<Database>,
+    aprobador: AprobadorUser,
     Path((empleado_id, solicitud_id)): Path<(String, String)>,
+    Json(resolucion): Json<Resolucion>,
 ) -> AppResult<Json<SolicitudVacaciones>> {
-    use crate::services::SolicitudService;
+    let comentario = resolucion.comentario.unwrap_or_default().trim().to_string();
+    if comentario.is_empty() {
+        return Err(AppError::Validation(vec![ErrorCampo::new(
+            "comentario",
+            "Indica el motivo del rechazo",
+        )]));
+    }
+
+    let service = SolicitudService::new(db);
+    let solicitud = service
+        .decidir(
+            &empleado_id,
+            &solicitud_id,
+            &aprobador.empleado,
+            &aprobador.delegantes,
+            false,
+            Some(comentario),
+        )
+        .await?;
+    Ok(Json(solicitud))
+}
+
+/// POST /api/solicitudes/:empleado_id/:solicitud_id/revocar - Retira una aprobación y
+/// devuelve los días al saldo; el motivo es obligatorio
+#[debug_handler]
+pub async fn revocar_solicitud(
+    State(db): State<Database>,
+    admin_user: AdminUser,
+    Path((empleado_id, solicitud_id)): Path<(String, String)>,
+    Json(revocacion): Json<Revocacion>,
+) -> AppResult<Json<SolicitudVacaciones>> {
+    let motivo = revocacion.motivo.trim();
+    if motivo.is_empty() {
+        return Err(AppError::Validation(vec![ErrorCampo::new(
+            "motivo",
+            "Indica el motivo de la revocación",
+        )]));
+    }
 
     let service = SolicitudService::new(db);
     let solicitud = service
-        .actualizar_estado(&empleado_id, &solicitud_id, "rechazada")
+        .actualizar_estado(
+            &empleado_id,
+            &solicitud_id,
+            EstadoSolicitud::Revocada,
+            &admin_user.empleado.id,
+            Some(motivo.to_string()),
+        )
         .await?;
     Ok(Json(solicitud))
 }
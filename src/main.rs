@@ -1,16 +1,6 @@
-mod auth;
-mod config;
-mod db;
-mod error;
-mod handlers;
-mod models;
-mod routes;
-mod services;
-mod session;
-
-// use axum::{Router, routing::get};
 use std::net::SocketAddr;
-use tracing_subscriber;
+use tower_sessions_core::ExpiredDeletion;
+use vacaciones_app::{config, db, routes, services, session};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -27,32 +17,68 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    tracing::info!("Conectando a DynamoDB...");
-    let db_client = db::DynamoDBClient::new(&config).await;
-    tracing::info!("✅ Conectado a DynamoDB (tabla: {})", db_client.table_name);
+    tracing::info!(
+        "Conectando a almacenamiento ({:?})...",
+        config.database_backend
+    );
+    let db = db::connect(&config).await.inspect_err(|e| {
+        eprintln!("❌ Error conectando al almacenamiento: {}", e);
+    })?;
 
     // Crear session store
-    let session_store = session::DynamoDBSessionStore::new(db_client.clone());
+    let session_store = session::DatabaseSessionStore::new(db.clone());
+
+    // Limpieza periódica de sesiones expiradas; si una pasada falla se vuelve
+    // a intentar en el siguiente intervalo
+    let limpieza = session_store.clone();
+    let periodo = tokio::time::Duration::from_secs(config.session_cleanup_minutes * 60);
+    tokio::task::spawn(async move {
+        loop {
+            if let Err(e) = limpieza.clone().continuously_delete_expired(periodo).await {
+                tracing::warn!("Error eliminando sesiones expiradas: {}", e);
+            }
+        }
+    });
+
+    // Cada `REQUEST_EXPIRY_MINUTES` se marcan como expiradas las solicitudes
+    // pendientes cuya fecha de inicio ya pasó sin que nadie las resolviera
+    let expiracion = services::SolicitudService::new(db.clone());
+    let periodo_expiracion = tokio::time::Duration::from_secs(config.request_expiry_minutes * 60);
+    tokio::task::spawn(async move {
+        let mut intervalo = tokio::time::interval(periodo_expiracion);
+        loop {
+            intervalo.tick().await;
+            match expiracion
+                .expirar_pendientes(chrono::Utc::now().date_naive())
+                .await
+            {
+                Ok(0) => {}
+                Ok(n) => tracing::info!("{} solicitudes pendientes expiradas", n),
+                Err(e) => tracing::warn!("Error expirando solicitudes pendientes: {}", e),
+            }
+        }
+    });
+
     let session_layer = tower_sessions::SessionManagerLayer::new(session_store)
         .with_secure(false) // true en producción con HTTPS
         .with_same_site(tower_sessions::cookie::SameSite::Lax)
         .with_http_only(true)
-        .with_expiry(tower_sessions::Expiry::OnInactivity(
-            time::Duration::days(config.session_ttl_days),
-        ));
+        .with_expiry(tower_sessions::Expiry::OnInactivity(time::Duration::days(
+            config.session_ttl_days,
+        )));
 
-    let app = routes::create_router(db_client).layer(session_layer);
+    let app = routes::create_router(db)
+        .layer(session_layer)
+        .layer(axum::Extension(config.trusted_proxies.clone()));
 
-    let addr: SocketAddr = config.server_address().parse().map_err(|e| {
+    let addr: SocketAddr = config.server_address().parse().inspect_err(|_| {
         eprintln!("❌ Dirección de servidor inválida");
-        e
     })?;
 
-    let listener = tokio::net::TcpListener::bind(addr).await.map_err(|e| {
+    let listener = tokio::net::TcpListener::bind(addr).await.inspect_err(|e| {
         eprintln!("❌ No se pudo vincular al puerto {}", addr.port());
         eprintln!("   Razón: {}", e);
         eprintln!("   Sugerencia: ¿Otro proceso está usando este puerto?");
-        e
     })?;
 
     tracing::info!("🚀 Servidor corriendo en http://{}", addr);
@@ -63,10 +89,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("   GET  /empleados/:id");
     tracing::info!("   POST /solicitudes");
 
-    axum::serve(listener, app).await.map_err(|e| {
+    // ConnectInfo da la IP del cliente para "Mis sesiones" (o la del proxy de confianza)
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .inspect_err(|e| {
         eprintln!("❌ Error crítico en el servidor: {}", e);
         eprintln!("   Momento: {}", chrono::Local::now());
-        e
     })?;
     Ok(())
 }
@@ -0,0 +1,246 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Quién decide un paso de una cadena de aprobación
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum PasoAprobacion {
+    /// El jefe del solicitante (`Empleado::jefe_id`)
+    JefeDirecto,
+    /// Un empleado concreto, p. ej. el responsable de Recursos Humanos
+    Empleado { empleado_id: String },
+    /// Cualquier administrador
+    Administrador,
+}
+
+impl fmt::Display for PasoAprobacion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasoAprobacion::JefeDirecto => f.write_str("jefe directo"),
+            PasoAprobacion::Empleado { empleado_id } => write!(f, "empleado {}", empleado_id),
+            PasoAprobacion::Administrador => f.write_str("administrador"),
+        }
+    }
+}
+
+/// Pasos por los que pasa una solicitud de un departamento antes de quedar aprobada.
+/// La cadena sin `departamento` aplica a los departamentos que no tienen una propia.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CadenaAprobacion {
+    pub id: String,
+    pub nombre: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub departamento: Option<String>,
+    pub pasos: Vec<PasoAprobacion>,
+}
+
+/// Datos de una cadena para crearla o reemplazarla (POST/PUT /api/cadenas)
+#[derive(Debug, Deserialize)]
+pub struct DatosCadena {
+    pub nombre: String,
+    pub departamento: Option<String>,
+    pub pasos: Vec<PasoAprobacion>,
+}
+
+/// Decisión tomada en un paso de la cadena de una solicitud
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionPaso {
+    /// Índice del paso en `SolicitudVacaciones::pasos`
+    pub paso: usize,
+    /// ID del empleado que decidió
+    pub aprobador: String,
+    pub aprobada: bool,
+    pub fecha: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comentario: Option<String>,
+    /// ID del empleado a quien le tocaba el paso, si `aprobador` decidió por delegación
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub en_nombre_de: Option<String>,
+}
+
+/// Pasos que aplican cuando no hay ninguna cadena registrada: cualquier administrador
+/// aprueba en un solo paso
+pub fn pasos_por_defecto() -> Vec<PasoAprobacion> {
+    vec![PasoAprobacion::Administrador]
+}
+
+/// Pasos para una solicitud de `departamento`: los de su cadena, o los de la cadena
+/// general si no tiene una, o `pasos_por_defecto`
+pub fn pasos_para(cadenas: &[CadenaAprobacion], departamento: &str) -> Vec<PasoAprobacion> {
+    cadenas
+        .iter()
+        .find(|c| {
+            c.departamento
+                .as_deref()
+                .is_some_and(|d| d.eq_ignore_ascii_case(departamento.trim()))
+        })
+        .or_else(|| cadenas.iter().find(|c| c.departamento.is_none()))
+        .map(|c| c.pasos.clone())
+        .unwrap_or_else(pasos_por_defecto)
+}
+
+impl CadenaAprobacion {
+    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("CADENA#{}", self.id)),
+        );
+        item.insert("SK".to_string(), AttributeValue::S("METADATA".to_string()));
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert("nombre".to_string(), AttributeValue::S(self.nombre.clone()));
+        if let Some(departamento) = &self.departamento {
+            item.insert(
+                "departamento".to_string(),
+                AttributeValue::S(departamento.clone()),
+            );
+        }
+        item.insert("pasos".to_string(), pasos_to_attr(&self.pasos));
+        item.insert("tipo".to_string(), AttributeValue::S("cadena".to_string()));
+        item
+    }
+
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        Some(CadenaAprobacion {
+            id: item.get("id")?.as_s().ok()?.clone(),
+            nombre: item.get("nombre")?.as_s().ok()?.clone(),
+            departamento: item
+                .get("departamento")
+                .and_then(|v| v.as_s().ok())
+                .cloned(),
+            pasos: pasos_from_attr(item.get("pasos")?)?,
+        })
+    }
+}
+
+/// Pasos como lista de mapas de DynamoDB (`tipo` y, si aplica, `empleado_id`)
+pub(crate) fn pasos_to_attr(pasos: &[PasoAprobacion]) -> AttributeValue {
+    AttributeValue::L(
+        pasos
+            .iter()
+            .map(|paso| {
+                let (tipo, empleado_id) = match paso {
+                    PasoAprobacion::JefeDirecto => ("jefe_directo", None),
+                    PasoAprobacion::Empleado { empleado_id } => ("empleado", Some(empleado_id)),
+                    PasoAprobacion::Administrador => ("administrador", None),
+                };
+                let mut m =
+                    HashMap::from([("tipo".to_string(), AttributeValue::S(tipo.to_string()))]);
+                if let Some(id) = empleado_id {
+                    m.insert("empleado_id".to_string(), AttributeValue::S(id.clone()));
+                }
+                AttributeValue::M(m)
+            })
+            .collect(),
+    )
+}
+
+pub(crate) fn pasos_from_attr(valor: &AttributeValue) -> Option<Vec<PasoAprobacion>> {
+    valor
+        .as_l()
+        .ok()?
+        .iter()
+        .map(|v| {
+            let m = v.as_m().ok()?;
+            match m.get("tipo")?.as_s().ok()?.as_str() {
+                "jefe_directo" => Some(PasoAprobacion::JefeDirecto),
+                "empleado" => Some(PasoAprobacion::Empleado {
+                    empleado_id: m.get("empleado_id")?.as_s().ok()?.clone(),
+                }),
+                "administrador" => Some(PasoAprobacion::Administrador),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Decisiones como lista de mapas de DynamoDB
+pub(crate) fn decisiones_to_attr(decisiones: &[DecisionPaso]) -> AttributeValue {
+    AttributeValue::L(
+        decisiones
+            .iter()
+            .map(|d| {
+                let mut m = HashMap::from([
+                    ("paso".to_string(), AttributeValue::N(d.paso.to_string())),
+                    (
+                        "aprobador".to_string(),
+                        AttributeValue::S(d.aprobador.clone()),
+                    ),
+                    ("aprobada".to_string(), AttributeValue::Bool(d.aprobada)),
+                    ("fecha".to_string(), AttributeValue::S(d.fecha.clone())),
+                ]);
+                if let Some(comentario) = &d.comentario {
+                    m.insert(
+                        "comentario".to_string(),
+                        AttributeValue::S(comentario.clone()),
+                    );
+                }
+                if let Some(en_nombre_de) = &d.en_nombre_de {
+                    m.insert(
+                        "en_nombre_de".to_string(),
+                        AttributeValue::S(en_nombre_de.clone()),
+                    );
+                }
+                AttributeValue::M(m)
+            })
+            .collect(),
+    )
+}
+
+pub(crate) fn decision_from_attr(valor: &AttributeValue) -> Option<DecisionPaso> {
+    let m = valor.as_m().ok()?;
+    Some(DecisionPaso {
+        paso: m.get("paso")?.as_n().ok()?.parse().ok()?,
+        aprobador: m.get("aprobador")?.as_s().ok()?.clone(),
+        aprobada: *m.get("aprobada")?.as_bool().ok()?,
+        fecha: m.get("fecha")?.as_s().ok()?.clone(),
+        comentario: m.get("comentario").and_then(|v| v.as_s().ok()).cloned(),
+        en_nombre_de: m.get("en_nombre_de").and_then(|v| v.as_s().ok()).cloned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cadena(departamento: Option<&str>, pasos: Vec<PasoAprobacion>) -> CadenaAprobacion {
+        CadenaAprobacion {
+            id: departamento.unwrap_or("general").to_string(),
+            nombre: "Cadena".to_string(),
+            departamento: departamento.map(str::to_string),
+            pasos,
+        }
+    }
+
+    #[test]
+    fn test_pasos_por_departamento() {
+        assert_eq!(pasos_para(&[], "Ventas"), pasos_por_defecto());
+
+        let rh = PasoAprobacion::Empleado {
+            empleado_id: "rh".to_string(),
+        };
+        let cadenas = [
+            cadena(
+                Some("Ventas"),
+                vec![PasoAprobacion::JefeDirecto, rh.clone()],
+            ),
+            cadena(None, vec![PasoAprobacion::JefeDirecto]),
+        ];
+        assert_eq!(
+            pasos_para(&cadenas, "ventas"),
+            vec![PasoAprobacion::JefeDirecto, rh.clone()]
+        );
+        assert_eq!(
+            pasos_para(&cadenas, "Sistemas"),
+            vec![PasoAprobacion::JefeDirecto]
+        );
+
+        let item = cadenas[0].to_item();
+        assert_eq!(
+            CadenaAprobacion::from_item(&item).as_ref(),
+            Some(&cadenas[0])
+        );
+    }
+}
@@ -0,0 +1,481 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::{Dias, Empleado, HorarioTrabajo, MedioDia};
+
+/// Día de descanso obligatorio que no cuenta como día de vacaciones. Los días inhábiles de
+/// la empresa pueden cerrar solo media jornada (p. ej. la tarde del 24 de diciembre): ese
+/// día cuenta medio día menos.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Feriado {
+    pub fecha: NaiveDate,
+    pub nombre: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medio_dia: Option<MedioDia>,
+}
+
+impl Feriado {
+    /// Nombre para mostrar en las vistas, con la media jornada si no es el día completo
+    pub fn descripcion(&self) -> String {
+        match self.medio_dia {
+            Some(medio_dia) => format!("{} (solo la {})", self.nombre, medio_dia.nombre()),
+            None => self.nombre.clone(),
+        }
+    }
+}
+
+/// Calendario de días inhábiles propio de la empresa (Jueves y Viernes Santo,
+/// 12 de diciembre, feriados locales…). Sin departamentos ni ubicaciones aplica
+/// a todos los empleados; si indica alguno, solo a los que coincidan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarioEmpresa {
+    pub id: String,
+    pub nombre: String,
+    #[serde(default)]
+    pub departamentos: Vec<String>,
+    #[serde(default)]
+    pub ubicaciones: Vec<String>,
+    #[serde(default)]
+    pub dias: Vec<Feriado>,
+}
+
+/// Datos de un calendario para crearlo o reemplazarlo (POST/PUT /api/calendarios)
+#[derive(Debug, Deserialize)]
+pub struct DatosCalendario {
+    pub nombre: String,
+    #[serde(default)]
+    pub departamentos: Vec<String>,
+    #[serde(default)]
+    pub ubicaciones: Vec<String>,
+    #[serde(default)]
+    pub dias: Vec<Feriado>,
+}
+
+impl CalendarioEmpresa {
+    pub fn es_general(&self) -> bool {
+        self.departamentos.is_empty() && self.ubicaciones.is_empty()
+    }
+
+    /// Indica si el calendario aplica al empleado (sin distinguir mayúsculas)
+    pub fn aplica_a(&self, empleado: &Empleado) -> bool {
+        let coincide = |valores: &[String], valor: &str| {
+            valores
+                .iter()
+                .any(|v| v.trim().eq_ignore_ascii_case(valor.trim()))
+        };
+
+        self.es_general()
+            || coincide(&self.departamentos, &empleado.departamento)
+            || empleado
+                .ubicacion
+                .as_deref()
+                .is_some_and(|u| coincide(&self.ubicaciones, u))
+    }
+
+    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let lista = |valores: &[String]| {
+            AttributeValue::L(valores.iter().cloned().map(AttributeValue::S).collect())
+        };
+        let dias = self
+            .dias
+            .iter()
+            .map(|d| {
+                let mut dia = HashMap::from([
+                    ("fecha".to_string(), AttributeValue::S(d.fecha.to_string())),
+                    ("nombre".to_string(), AttributeValue::S(d.nombre.clone())),
+                ]);
+                if let Some(medio_dia) = d.medio_dia {
+                    dia.insert(
+                        "medio_dia".to_string(),
+                        AttributeValue::S(medio_dia.as_str().to_string()),
+                    );
+                }
+                AttributeValue::M(dia)
+            })
+            .collect();
+
+        let mut item = HashMap::new();
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("CALENDARIO#{}", self.id)),
+        );
+        item.insert("SK".to_string(), AttributeValue::S("METADATA".to_string()));
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert("nombre".to_string(), AttributeValue::S(self.nombre.clone()));
+        item.insert("departamentos".to_string(), lista(&self.departamentos));
+        item.insert("ubicaciones".to_string(), lista(&self.ubicaciones));
+        item.insert("dias".to_string(), AttributeValue::L(dias));
+        item.insert(
+            "tipo".to_string(),
+            AttributeValue::S("calendario".to_string()),
+        );
+        item
+    }
+
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let lista = |nombre: &str| -> Vec<String> {
+            item.get(nombre)
+                .and_then(|v| v.as_l().ok())
+                .map(|l| l.iter().filter_map(|v| v.as_s().ok().cloned()).collect())
+                .unwrap_or_default()
+        };
+        let dias = item
+            .get("dias")
+            .and_then(|v| v.as_l().ok())
+            .map(|l| {
+                l.iter()
+                    .filter_map(|v| {
+                        let dia = v.as_m().ok()?;
+                        Some(Feriado {
+                            fecha: dia.get("fecha")?.as_s().ok()?.parse().ok()?,
+                            nombre: dia.get("nombre")?.as_s().ok()?.clone(),
+                            medio_dia: dia
+                                .get("medio_dia")
+                                .and_then(|v| v.as_s().ok())
+                                .and_then(|m| m.parse().ok()),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(CalendarioEmpresa {
+            id: item.get("id")?.as_s().ok()?.clone(),
+            nombre: item.get("nombre")?.as_s().ok()?.clone(),
+            departamentos: lista("departamentos"),
+            ubicaciones: lista("ubicaciones"),
+            dias,
+        })
+    }
+}
+
+/// Resultado de contar los días laborables de un periodo
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConteoDias {
+    pub dias_laborables: Dias,
+    /// Fechas que sí se contaron, para descontar medios días y horas en los extremos
+    #[serde(skip)]
+    pub fechas: Vec<NaiveDate>,
+    /// Feriados entre semana que caen dentro del periodo y no se contaron
+    pub feriados_excluidos: Vec<Feriado>,
+    /// Días de la empresa cerrados media jornada que caen en `fechas`: cuentan medio día
+    pub medios_dias: Vec<Feriado>,
+}
+
+/// N-ésimo `dia` de la semana de un mes (p. ej. el tercer lunes de marzo)
+fn nesimo_dia(ano: i32, mes: u32, dia: Weekday, n: u8) -> Option<NaiveDate> {
+    NaiveDate::from_weekday_of_month_opt(ano, mes, dia, n)
+}
+
+/// Días de descanso obligatorio del art. 74 de la Ley Federal del Trabajo para un año.
+///
+/// - 1 de enero, 1 de mayo, 16 de septiembre y 25 de diciembre
+/// - Primer lunes de febrero, tercer lunes de marzo y tercer lunes de noviembre
+/// - Transmisión del Poder Ejecutivo Federal cada seis años: 1 de octubre desde 2024
+///   (1 de diciembre hasta 2018)
+///
+/// Las jornadas electorales (fracción IX) dependen de cada elección y no se incluyen.
+pub fn feriados_oficiales(ano: i32) -> Vec<Feriado> {
+    let fijo = |mes, dia| NaiveDate::from_ymd_opt(ano, mes, dia);
+    let mut fechas = vec![
+        (fijo(1, 1), "Año Nuevo"),
+        (
+            nesimo_dia(ano, 2, Weekday::Mon, 1),
+            "Día de la Constitución",
+        ),
+        (
+            nesimo_dia(ano, 3, Weekday::Mon, 3),
+            "Natalicio de Benito Juárez",
+        ),
+        (fijo(5, 1), "Día del Trabajo"),
+        (fijo(9, 16), "Día de la Independencia"),
+        (nesimo_dia(ano, 11, Weekday::Mon, 3), "Día de la Revolución"),
+        (fijo(12, 25), "Navidad"),
+    ];
+
+    if ano >= 2024 && (ano - 2024) % 6 == 0 {
+        fechas.push((fijo(10, 1), "Transmisión del Poder Ejecutivo Federal"));
+    } else if ano <= 2018 && (2018 - ano) % 6 == 0 {
+        fechas.push((fijo(12, 1), "Transmisión del Poder Ejecutivo Federal"));
+    }
+
+    let mut feriados: Vec<Feriado> = fechas
+        .into_iter()
+        .filter_map(|(fecha, nombre)| {
+            Some(Feriado {
+                fecha: fecha?,
+                nombre: nombre.to_string(),
+                medio_dia: None,
+            })
+        })
+        .collect();
+    feriados.sort_by_key(|f| f.fecha);
+    feriados
+}
+
+/// Feriados oficiales entre dos fechas (inclusive)
+pub fn feriados_entre(inicio: NaiveDate, fin: NaiveDate) -> Vec<Feriado> {
+    (inicio.year()..=fin.year())
+        .flat_map(feriados_oficiales)
+        .filter(|f| f.fecha >= inicio && f.fecha <= fin)
+        .collect()
+}
+
+/// Cuenta los días laborables entre dos fechas inclusive: los que se trabajan según
+/// `horario`, sin contar los feriados oficiales ni los días inhábiles de `calendarios`
+/// (los que cierran media jornada cuentan medio día)
+pub fn contar_dias_laborables(
+    inicio: NaiveDate,
+    fin: NaiveDate,
+    calendarios: &[CalendarioEmpresa],
+    horario: &HorarioTrabajo,
+) -> ConteoDias {
+    let oficiales = feriados_entre(inicio, fin);
+    let mut feriados: HashMap<NaiveDate, &Feriado> = HashMap::new();
+    // Un día que ya es feriado oficial se reporta una sola vez, y si un calendario lo cierra
+    // completo y otro solo media jornada, pesa el día completo
+    for dia in oficiales
+        .iter()
+        .chain(calendarios.iter().flat_map(|c| &c.dias))
+    {
+        if dia.fecha >= inicio && dia.fecha <= fin {
+            let feriado = feriados.entry(dia.fecha).or_insert(dia);
+            if feriado.medio_dia.is_some() && dia.medio_dia.is_none() {
+                *feriado = dia;
+            }
+        }
+    }
+    let mut conteo = ConteoDias::default();
+
+    let mut fecha = inicio;
+    while fecha <= fin {
+        if horario.trabaja(fecha) {
+            match feriados.get(&fecha) {
+                Some(feriado) if feriado.medio_dia.is_none() => {
+                    conteo.feriados_excluidos.push((*feriado).clone())
+                }
+                Some(feriado) => {
+                    conteo.dias_laborables += Dias::MEDIO;
+                    conteo.fechas.push(fecha);
+                    conteo.medios_dias.push((*feriado).clone());
+                }
+                None => {
+                    conteo.dias_laborables += Dias::enteros(1);
+                    conteo.fechas.push(fecha);
+                }
+            }
+        }
+        fecha += Duration::days(1);
+    }
+
+    conteo
+}
+
+/// Cuenta todos los días (naturales) entre dos fechas inclusive
+pub fn contar_dias_naturales(inicio: NaiveDate, fin: NaiveDate) -> ConteoDias {
+    let fechas: Vec<NaiveDate> = inicio.iter_days().take_while(|f| *f <= fin).collect();
+    ConteoDias {
+        dias_laborables: Dias::enteros(fechas.len() as i32),
+        fechas,
+        feriados_excluidos: Vec::new(),
+        medios_dias: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PatronHorario;
+    use crate::models::horario::horario_por_defecto;
+
+    fn fecha(texto: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(texto, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_feriados_oficiales() {
+        let fechas: Vec<String> = feriados_oficiales(2025)
+            .iter()
+            .map(|f| f.fecha.to_string())
+            .collect();
+        assert_eq!(
+            fechas,
+            [
+                "2025-01-01",
+                "2025-02-03",
+                "2025-03-17",
+                "2025-05-01",
+                "2025-09-16",
+                "2025-11-17",
+                "2025-12-25"
+            ]
+        );
+
+        // Cambio de gobierno cada seis años
+        assert!(
+            feriados_oficiales(2024)
+                .iter()
+                .any(|f| f.fecha == fecha("2024-10-01"))
+        );
+        assert!(
+            feriados_oficiales(2030)
+                .iter()
+                .any(|f| f.fecha == fecha("2030-10-01"))
+        );
+        assert!(
+            feriados_oficiales(2018)
+                .iter()
+                .any(|f| f.fecha == fecha("2018-12-01"))
+        );
+        assert_eq!(feriados_oficiales(2026).len(), 7);
+    }
+
+    #[test]
+    fn test_contar_dias_laborables() {
+        let lunes_a_viernes = horario_por_defecto();
+
+        // Semana del 15 al 19 de septiembre de 2025: el 16 (martes) es feriado
+        let conteo = contar_dias_laborables(
+            fecha("2025-09-15"),
+            fecha("2025-09-19"),
+            &[],
+            &lunes_a_viernes,
+        );
+        assert_eq!(conteo.dias_laborables, Dias::enteros(4));
+        assert_eq!(conteo.feriados_excluidos.len(), 1);
+        assert_eq!(conteo.feriados_excluidos[0].fecha, fecha("2025-09-16"));
+
+        // Un feriado en fin de semana no descuenta nada (1 de mayo de 2027 es sábado)
+        let conteo = contar_dias_laborables(
+            fecha("2027-04-26"),
+            fecha("2027-05-02"),
+            &[],
+            &lunes_a_viernes,
+        );
+        assert_eq!(conteo.dias_laborables, Dias::enteros(5));
+        assert!(conteo.feriados_excluidos.is_empty());
+
+        // ... salvo a quien trabaja ese sábado
+        let almacen = HorarioTrabajo {
+            id: "almacen".to_string(),
+            nombre: "Almacén".to_string(),
+            patron: PatronHorario::Semanal {
+                dias: vec![1, 2, 3, 4, 5, 6],
+            },
+        };
+        let conteo =
+            contar_dias_laborables(fecha("2027-04-26"), fecha("2027-05-02"), &[], &almacen);
+        assert_eq!(conteo.dias_laborables, Dias::enteros(5));
+        assert_eq!(conteo.feriados_excluidos.len(), 1);
+        let conteo =
+            contar_dias_laborables(fecha("2025-03-03"), fecha("2025-03-09"), &[], &almacen);
+        assert_eq!(conteo.dias_laborables, Dias::enteros(6));
+
+        // Turno 4x3 de jueves a domingo: la semana completa descuenta 4 días
+        let turno = HorarioTrabajo {
+            id: "turno".to_string(),
+            nombre: "Turno 4x3".to_string(),
+            patron: PatronHorario::Rotativo {
+                inicio: fecha("2025-03-06"),
+                trabaja: 4,
+                descansa: 3,
+            },
+        };
+        let conteo = contar_dias_laborables(fecha("2025-03-10"), fecha("2025-03-16"), &[], &turno);
+        assert_eq!(conteo.dias_laborables, Dias::enteros(4));
+        assert_eq!(conteo.fechas.first(), Some(&fecha("2025-03-13")));
+
+        // Periodo que cruza de año
+        let conteo = contar_dias_laborables(
+            fecha("2025-12-22"),
+            fecha("2026-01-02"),
+            &[],
+            &lunes_a_viernes,
+        );
+        assert_eq!(conteo.dias_laborables, Dias::enteros(8));
+        assert_eq!(conteo.feriados_excluidos.len(), 2);
+    }
+
+    #[test]
+    fn test_calendario_empresa() {
+        let empresa = CalendarioEmpresa {
+            id: "c1".to_string(),
+            nombre: "Monterrey".to_string(),
+            departamentos: vec![],
+            ubicaciones: vec!["monterrey".to_string()],
+            dias: vec![
+                Feriado {
+                    fecha: fecha("2025-12-12"),
+                    nombre: "Día de la Virgen de Guadalupe".to_string(),
+                    medio_dia: None,
+                },
+                Feriado {
+                    fecha: fecha("2025-12-25"),
+                    nombre: "Navidad".to_string(),
+                    medio_dia: None,
+                },
+                Feriado {
+                    fecha: fecha("2025-12-24"),
+                    nombre: "Nochebuena".to_string(),
+                    medio_dia: Some(MedioDia::Tarde),
+                },
+            ],
+        };
+        let mut empleado = Empleado {
+            id: "1".to_string(),
+            nombre: "Test".to_string(),
+            departamento: "IT".to_string(),
+            email: "test@test.com".to_string(),
+            es_admin: false,
+            fecha_ingreso: "2020-01-01".to_string(),
+            ubicacion: Some("Monterrey".to_string()),
+            jefe_id: None,
+            horario_id: None,
+            password_hash: None,
+            dias_disponibles: None,
+            dias_tomados: None,
+            antiguedad_anos: None,
+            periodo: None,
+        };
+        assert!(empresa.aplica_a(&empleado));
+        empleado.ubicacion = Some("CDMX".to_string());
+        assert!(!empresa.aplica_a(&empleado));
+
+        // Del 8 al 26 de diciembre de 2025: 15 días entre semana, menos el 12 y el 25, y
+        // medio día por la tarde del 24
+        let conteo = contar_dias_laborables(
+            fecha("2025-12-08"),
+            fecha("2025-12-26"),
+            std::slice::from_ref(&empresa),
+            &horario_por_defecto(),
+        );
+        assert_eq!(conteo.dias_laborables, Dias::desde_milesimos(12_500));
+        assert_eq!(conteo.feriados_excluidos.len(), 2);
+        assert_eq!(conteo.fechas.len(), 13);
+        assert_eq!(conteo.medios_dias, [empresa.dias[2].clone()]);
+
+        // Si otro calendario cierra el 24 completo, pesa el día completo
+        let otro = CalendarioEmpresa {
+            dias: vec![Feriado {
+                medio_dia: None,
+                ..empresa.dias[2].clone()
+            }],
+            ..empresa.clone()
+        };
+        let conteo = contar_dias_laborables(
+            fecha("2025-12-24"),
+            fecha("2025-12-24"),
+            &[empresa.clone(), otro],
+            &horario_por_defecto(),
+        );
+        assert_eq!(conteo.dias_laborables, Dias::CERO);
+        assert!(conteo.medios_dias.is_empty());
+
+        let item = CalendarioEmpresa::from_item(&empresa.to_item()).unwrap();
+        assert_eq!(item.dias, empresa.dias);
+        assert_eq!(empresa.dias[2].descripcion(), "Nochebuena (solo la tarde)");
+        assert_eq!(item.ubicaciones, empresa.ubicaciones);
+    }
+}
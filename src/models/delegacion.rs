@@ -0,0 +1,85 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Facultad de aprobación que un empleado (`delegante`, p. ej. un jefe de vacaciones) cede a
+/// otro (`delegado`) entre dos fechas, ambas incluidas. El delegado decide los pasos que le
+/// tocarían al delegante y la decisión queda registrada "en nombre de" él.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delegacion {
+    pub id: String,
+    pub delegante_id: String,
+    pub delegado_id: String,
+    pub desde: NaiveDate,
+    pub hasta: NaiveDate,
+    pub created_at: String,
+}
+
+/// Cuerpo de POST /api/delegaciones; `delegante_id` solo lo indica un administrador
+/// (por omisión es quien hace la petición)
+#[derive(Debug, Deserialize)]
+pub struct NuevaDelegacion {
+    pub delegante_id: Option<String>,
+    pub delegado_id: String,
+    pub desde: NaiveDate,
+    pub hasta: NaiveDate,
+}
+
+impl Delegacion {
+    /// Si está vigente en `fecha`
+    pub fn vigente_al(&self, fecha: NaiveDate) -> bool {
+        self.desde <= fecha && fecha <= self.hasta
+    }
+
+    /// Si sus fechas se cruzan con las de `otra`
+    pub fn se_traslapa(&self, otra: &Delegacion) -> bool {
+        self.desde <= otra.hasta && otra.desde <= self.hasta
+    }
+
+    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("DELEGACION#{}", self.id)),
+        );
+        item.insert("SK".to_string(), AttributeValue::S("METADATA".to_string()));
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert(
+            "delegante_id".to_string(),
+            AttributeValue::S(self.delegante_id.clone()),
+        );
+        item.insert(
+            "delegado_id".to_string(),
+            AttributeValue::S(self.delegado_id.clone()),
+        );
+        item.insert(
+            "desde".to_string(),
+            AttributeValue::S(self.desde.to_string()),
+        );
+        item.insert(
+            "hasta".to_string(),
+            AttributeValue::S(self.hasta.to_string()),
+        );
+        item.insert(
+            "created_at".to_string(),
+            AttributeValue::S(self.created_at.clone()),
+        );
+        item.insert(
+            "tipo".to_string(),
+            AttributeValue::S("delegacion".to_string()),
+        );
+        item
+    }
+
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        Some(Delegacion {
+            id: item.get("id")?.as_s().ok()?.clone(),
+            delegante_id: item.get("delegante_id")?.as_s().ok()?.clone(),
+            delegado_id: item.get("delegado_id")?.as_s().ok()?.clone(),
+            desde: item.get("desde")?.as_s().ok()?.parse().ok()?,
+            hasta: item.get("hasta")?.as_s().ok()?.parse().ok()?,
+            created_at: item.get("created_at")?.as_s().ok()?.clone(),
+        })
+    }
+}
@@ -0,0 +1,545 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::error::ErrorCampo;
+
+/// Horas de una jornada completa (jornada diurna máxima, LFT art. 61): una solicitud por
+/// horas cuesta `horas / HORAS_POR_DIA` días
+pub const HORAS_POR_DIA: i32 = 8;
+
+/// Cantidad de días en punto fijo, en milésimos de día, para que los medios días y las
+/// horas se sumen y resten sin errores de redondeo. En JSON y en las vistas se muestra en
+/// días (`0.5`, `2.375`); SQL guarda los milésimos y DynamoDB el decimal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Dias(i32);
+
+impl Dias {
+    /// Milésimos por día
+    pub const ESCALA: i32 = 1000;
+    pub const CERO: Dias = Dias(0);
+    pub const MEDIO: Dias = Dias(Self::ESCALA / 2);
+
+    /// Días enteros de una cantidad acotada (derechos, constantes); para datos recibidos,
+    /// `checked_enteros`
+    pub const fn enteros(dias: i32) -> Dias {
+        match Self::checked_enteros(dias) {
+            Some(dias) => dias,
+            None => panic!("Cantidad de días fuera de rango"),
+        }
+    }
+
+    /// Días enteros, o `None` si no caben en milésimos
+    pub const fn checked_enteros(dias: i32) -> Option<Dias> {
+        match dias.checked_mul(Self::ESCALA) {
+            Some(milesimos) => Some(Dias(milesimos)),
+            None => None,
+        }
+    }
+
+    pub const fn desde_milesimos(milesimos: i32) -> Dias {
+        Dias(milesimos)
+    }
+
+    /// Horas de jornada expresadas en días, de una cantidad acotada; para datos
+    /// recibidos, `checked_horas`
+    pub const fn horas(horas: i32) -> Dias {
+        match Self::checked_horas(horas) {
+            Some(dias) => dias,
+            None => panic!("Cantidad de horas fuera de rango"),
+        }
+    }
+
+    /// Horas de jornada expresadas en días, o `None` si no caben en milésimos
+    pub const fn checked_horas(horas: i32) -> Option<Dias> {
+        match horas.checked_mul(Self::ESCALA) {
+            Some(milesimos) => Some(Dias(milesimos / HORAS_POR_DIA)),
+            None => None,
+        }
+    }
+
+    pub const fn milesimos(self) -> i32 {
+        self.0
+    }
+
+    pub fn es_positivo(self) -> bool {
+        self.0 > 0
+    }
+
+    fn desde_decimal(dias: f64) -> Dias {
+        Dias((dias * Self::ESCALA as f64).round() as i32)
+    }
+}
+
+impl Add for Dias {
+    type Output = Dias;
+
+    fn add(self, otros: Dias) -> Dias {
+        Dias(self.0 + otros.0)
+    }
+}
+
+impl AddAssign for Dias {
+    fn add_assign(&mut self, otros: Dias) {
+        self.0 += otros.0;
+    }
+}
+
+impl Sub for Dias {
+    type Output = Dias;
+
+    fn sub(self, otros: Dias) -> Dias {
+        Dias(self.0 - otros.0)
+    }
+}
+
+impl SubAssign for Dias {
+    fn sub_assign(&mut self, otros: Dias) {
+        self.0 -= otros.0;
+    }
+}
+
+impl Mul<i32> for Dias {
+    type Output = Dias;
+
+    fn mul(self, factor: i32) -> Dias {
+        Dias(self.0 * factor)
+    }
+}
+
+impl Sum for Dias {
+    fn sum<I: Iterator<Item = Dias>>(iter: I) -> Dias {
+        iter.fold(Dias::CERO, Add::add)
+    }
+}
+
+/// Días sin ceros de más: `5`, `0.5`, `2.375`
+impl fmt::Display for Dias {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let signo = if self.0 < 0 { "-" } else { "" };
+        let (enteros, fraccion) = (self.0.abs() / Self::ESCALA, self.0.abs() % Self::ESCALA);
+        if fraccion == 0 {
+            write!(f, "{}{}", signo, enteros)
+        } else {
+            let fraccion = format!("{:03}", fraccion);
+            write!(f, "{}{}.{}", signo, enteros, fraccion.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for Dias {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|d| d.is_finite())
+            .map(Dias::desde_decimal)
+            .ok_or_else(|| format!("Cantidad de días inválida: {}", s))
+    }
+}
+
+/// Los días enteros se serializan como enteros, igual que antes de los medios días
+impl Serialize for Dias {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0 % Self::ESCALA == 0 {
+            serializer.serialize_i32(self.0 / Self::ESCALA)
+        } else {
+            serializer.serialize_f64(self.0 as f64 / Self::ESCALA as f64)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Dias {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Dias::desde_decimal)
+    }
+}
+
+/// Mitad de la jornada que se toma en un extremo de una solicitud: se puede empezar por la
+/// tarde y terminar por la mañana (en una solicitud de un solo día, cualquiera de las dos).
+/// También la mitad que cierra la empresa en un día inhábil de media jornada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MedioDia {
+    Manana,
+    Tarde,
+}
+
+impl MedioDia {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MedioDia::Manana => "manana",
+            MedioDia::Tarde => "tarde",
+        }
+    }
+
+    /// Nombre para mostrar en las vistas
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            MedioDia::Manana => "mañana",
+            MedioDia::Tarde => "tarde",
+        }
+    }
+}
+
+impl FromStr for MedioDia {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "manana" => Ok(MedioDia::Manana),
+            "tarde" => Ok(MedioDia::Tarde),
+            _ => Err(format!("Medio día inválido: {}", s)),
+        }
+    }
+}
+
+/// Qué parte de la jornada se toma en una solicitud: medio día al empezar o al terminar, o
+/// unas horas de un solo día. Por omisión, días completos.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Jornada {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medio_dia_inicio: Option<MedioDia>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medio_dia_fin: Option<MedioDia>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub horas: Option<i32>,
+}
+
+impl Jornada {
+    /// Días que cuesta el rango `inicio`–`fin` dadas las `fechas` que cuentan en él: las
+    /// horas si es por horas; si no, un día por fecha menos medio por cada extremo que se
+    /// toma a medias. Un extremo que no cuenta (p. ej. un sábado) no descuenta nada.
+    /// De las fechas en `cierres` (la empresa cierra media jornada) no se cobra la mitad
+    /// cerrada. `None` si la cantidad no cabe en `Dias`.
+    pub fn dias(
+        &self,
+        inicio: NaiveDate,
+        fin: NaiveDate,
+        fechas: &[NaiveDate],
+        cierres: &[(NaiveDate, MedioDia)],
+    ) -> Option<Dias> {
+        if let Some(horas) = self.horas {
+            return if fechas.contains(&inicio) {
+                Dias::checked_horas(horas)
+            } else {
+                Some(Dias::CERO)
+            };
+        }
+
+        let mut medios: usize = 0;
+        for &fecha in fechas {
+            let tomada = if inicio == fin {
+                self.medio_dia_inicio.or(self.medio_dia_fin)
+            } else if fecha == inicio {
+                self.medio_dia_inicio
+            } else if fecha == fin {
+                self.medio_dia_fin
+            } else {
+                None
+            };
+            let cerrada = cierres.iter().find(|(f, _)| *f == fecha).map(|(_, m)| *m);
+            medios += match (tomada, cerrada) {
+                (None, None) => 2,
+                (Some(tomada), Some(cerrada)) if tomada == cerrada => 0,
+                _ => 1,
+            };
+        }
+        Some(Dias::desde_milesimos(
+            i32::try_from(medios)
+                .ok()?
+                .checked_mul(Dias::MEDIO.milesimos())?,
+        ))
+    }
+
+    /// Descripción para las vistas, p. ej. "3 h" o "desde la tarde"; `None` en días completos
+    pub fn descripcion(&self) -> Option<String> {
+        if let Some(horas) = self.horas {
+            return Some(format!("{} h", horas));
+        }
+        let partes: Vec<String> = [
+            self.medio_dia_inicio
+                .map(|m| format!("desde la {}", m.nombre())),
+            self.medio_dia_fin
+                .map(|m| format!("hasta la {}", m.nombre())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!partes.is_empty()).then(|| partes.join(", "))
+    }
+
+    /// Las horas son de un solo día y menos que una jornada completa, sin medios días. En un
+    /// rango de varios días solo se empieza por la tarde y se termina por la mañana; en un solo
+    /// día se toma una mitad, no las dos.
+    pub fn validar(&self, inicio: NaiveDate, fin: NaiveDate) -> Vec<ErrorCampo> {
+        let mut errores = Vec::new();
+        if let Some(horas) = self.horas {
+            if inicio != fin {
+                errores.push(ErrorCampo::new(
+                    "horas",
+                    "Las solicitudes por horas son de un solo día",
+                ));
+            }
+            if !(1..HORAS_POR_DIA).contains(&horas) {
+                errores.push(ErrorCampo::new(
+                    "horas",
+                    format!("Las horas deben estar entre 1 y {}", HORAS_POR_DIA - 1),
+                ));
+            }
+            if self.medio_dia_inicio.is_some() || self.medio_dia_fin.is_some() {
+                errores.push(ErrorCampo::new(
+                    "horas",
+                    "Una solicitud por horas no lleva medios días",
+                ));
+            }
+            return errores;
+        }
+
+        if inicio == fin {
+            if let (Some(a), Some(b)) = (self.medio_dia_inicio, self.medio_dia_fin)
+                && a != b
+            {
+                errores.push(ErrorCampo::new(
+                    "medio_dia_fin",
+                    "En un solo día se toma la mañana o la tarde, no ambas",
+                ));
+            }
+            return errores;
+        }
+        if self.medio_dia_inicio == Some(MedioDia::Manana) {
+            errores.push(ErrorCampo::new(
+                "medio_dia_inicio",
+                "Un rango de varios días solo puede empezar por la tarde",
+            ));
+        }
+        if self.medio_dia_fin == Some(MedioDia::Tarde) {
+            errores.push(ErrorCampo::new(
+                "medio_dia_fin",
+                "Un rango de varios días solo puede terminar por la mañana",
+            ));
+        }
+        errores
+    }
+}
+
+/// Días que puede abarcar un rango de fechas, ambas incluidas
+pub const MAX_DIAS_RANGO: i64 = 366;
+
+/// Valida el formato YYYY-MM-DD de ambas fechas, que el fin no sea anterior al inicio y
+/// que el rango no pase de `MAX_DIAS_RANGO` días
+pub fn validar_rango(inicio: &str, fin: &str) -> Result<(NaiveDate, NaiveDate), Vec<ErrorCampo>> {
+    let fecha_inicio = NaiveDate::parse_from_str(inicio, "%Y-%m-%d");
+    let fecha_fin = NaiveDate::parse_from_str(fin, "%Y-%m-%d");
+
+    match (fecha_inicio, fecha_fin) {
+        (Ok(inicio), Ok(fin)) if fin < inicio => Err(vec![ErrorCampo::new(
+            "fecha_fin",
+            "La fecha_fin debe ser igual o posterior a fecha_inicio",
+        )]),
+        (Ok(inicio), Ok(fin)) if (fin - inicio).num_days() >= MAX_DIAS_RANGO => {
+            Err(vec![ErrorCampo::new(
+                "fecha_fin",
+                format!("El rango no puede pasar de {} días", MAX_DIAS_RANGO),
+            )])
+        }
+        (Ok(inicio), Ok(fin)) => Ok((inicio, fin)),
+        (inicio, fin) => {
+            let mut errores = Vec::new();
+            if inicio.is_err() {
+                errores.push(ErrorCampo::new(
+                    "fecha_inicio",
+                    "Formato de fecha_inicio inválido. Use YYYY-MM-DD",
+                ));
+            }
+            if fin.is_err() {
+                errores.push(ErrorCampo::new(
+                    "fecha_fin",
+                    "Formato de fecha_fin inválido. Use YYYY-MM-DD",
+                ));
+            }
+            Err(errores)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn campos(errores: &[ErrorCampo]) -> Vec<&str> {
+        errores.iter().map(|e| e.campo.as_str()).collect()
+    }
+
+    #[test]
+    fn test_dias_en_punto_fijo() {
+        assert_eq!(Dias::horas(3) + Dias::MEDIO, Dias::desde_milesimos(875));
+        assert_eq!(Dias::horas(HORAS_POR_DIA), Dias::enteros(1));
+        assert_eq!(Dias::enteros(2).to_string(), "2");
+        assert_eq!((Dias::enteros(2) + Dias::horas(3)).to_string(), "2.375");
+        assert_eq!((Dias::CERO - Dias::MEDIO).to_string(), "-0.5");
+        assert_eq!("1.5".parse::<Dias>(), Ok(Dias::desde_milesimos(1500)));
+        assert!("medio".parse::<Dias>().is_err());
+        assert_eq!(Dias::checked_enteros(i32::MAX), None);
+        assert_eq!(Dias::checked_horas(3), Some(Dias::horas(3)));
+
+        assert_eq!(serde_json::to_string(&Dias::enteros(5)).unwrap(), "5");
+        assert_eq!(serde_json::to_string(&Dias::MEDIO).unwrap(), "0.5");
+        assert_eq!(serde_json::from_str::<Dias>("3").unwrap(), Dias::enteros(3));
+        assert_eq!(
+            serde_json::from_str::<Dias>("0.125").unwrap(),
+            Dias::horas(1)
+        );
+    }
+
+    #[test]
+    fn test_dias_de_una_jornada() {
+        let fecha = |dia| NaiveDate::from_ymd_opt(2025, 3, dia).unwrap();
+        // Jueves 6 a lunes 10 de marzo de 2025: cuentan jueves, viernes y lunes
+        let fechas = [fecha(6), fecha(7), fecha(10)];
+        let tarde_a_manana = Jornada {
+            medio_dia_inicio: Some(MedioDia::Tarde),
+            medio_dia_fin: Some(MedioDia::Manana),
+            horas: None,
+        };
+        assert_eq!(
+            Jornada::default().dias(fecha(6), fecha(10), &fechas, &[]),
+            Some(Dias::enteros(3))
+        );
+        assert_eq!(
+            tarde_a_manana.dias(fecha(6), fecha(10), &fechas, &[]),
+            Some(Dias::enteros(2))
+        );
+        // El domingo no cuenta, así que terminar a medio día no descuenta nada
+        assert_eq!(
+            tarde_a_manana.dias(fecha(6), fecha(9), &fechas[..2], &[]),
+            Some(Dias::desde_milesimos(1500))
+        );
+
+        let manana = Jornada {
+            medio_dia_inicio: Some(MedioDia::Manana),
+            ..Jornada::default()
+        };
+        assert_eq!(
+            manana.dias(fecha(7), fecha(7), &[fecha(7)], &[]),
+            Some(Dias::MEDIO)
+        );
+        let horas = Jornada {
+            horas: Some(3),
+            ..Jornada::default()
+        };
+        assert_eq!(
+            horas.dias(fecha(7), fecha(7), &[fecha(7)], &[]),
+            Some(Dias::horas(3))
+        );
+        assert_eq!(horas.dias(fecha(8), fecha(8), &[], &[]), Some(Dias::CERO));
+        let desbordadas = Jornada {
+            horas: Some(i32::MAX),
+            ..Jornada::default()
+        };
+        assert_eq!(desbordadas.dias(fecha(7), fecha(7), &[fecha(7)], &[]), None);
+        assert_eq!(horas.descripcion().as_deref(), Some("3 h"));
+
+        // La empresa cierra la tarde del viernes 7: el día completo cuesta medio, y
+        // empezar por la tarde de ese día no cuesta nada
+        let cierre = [(fecha(7), MedioDia::Tarde)];
+        assert_eq!(
+            Jornada::default().dias(fecha(6), fecha(10), &fechas, &cierre),
+            Some(Dias::desde_milesimos(2500))
+        );
+        let desde_la_tarde = Jornada {
+            medio_dia_inicio: Some(MedioDia::Tarde),
+            ..Jornada::default()
+        };
+        assert_eq!(
+            desde_la_tarde.dias(fecha(7), fecha(10), &fechas[1..], &cierre),
+            Some(Dias::enteros(1))
+        );
+        assert_eq!(
+            manana.dias(fecha(7), fecha(7), &[fecha(7)], &cierre),
+            Some(Dias::MEDIO)
+        );
+        assert_eq!(
+            tarde_a_manana.descripcion().as_deref(),
+            Some("desde la tarde, hasta la mañana")
+        );
+    }
+
+    #[test]
+    fn test_validar_jornada() {
+        let dia = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let jornada = |inicio, fin, horas| Jornada {
+            medio_dia_inicio: inicio,
+            medio_dia_fin: fin,
+            horas,
+        };
+
+        assert!(
+            jornada(Some(MedioDia::Tarde), Some(MedioDia::Manana), None)
+                .validar(dia(17), dia(19))
+                .is_empty()
+        );
+        assert!(
+            jornada(Some(MedioDia::Manana), None, None)
+                .validar(dia(17), dia(17))
+                .is_empty()
+        );
+        assert!(
+            jornada(None, None, Some(3))
+                .validar(dia(17), dia(17))
+                .is_empty()
+        );
+
+        // En varios días no se puede empezar por la mañana ni terminar por la tarde
+        assert_eq!(
+            campos(
+                &jornada(Some(MedioDia::Manana), Some(MedioDia::Tarde), None)
+                    .validar(dia(17), dia(19))
+            ),
+            ["medio_dia_inicio", "medio_dia_fin"]
+        );
+        assert_eq!(
+            campos(
+                &jornada(Some(MedioDia::Manana), Some(MedioDia::Tarde), None)
+                    .validar(dia(17), dia(17))
+            ),
+            ["medio_dia_fin"]
+        );
+        assert_eq!(
+            campos(&jornada(None, None, Some(8)).validar(dia(17), dia(18))),
+            ["horas", "horas"]
+        );
+        assert_eq!(
+            campos(&jornada(Some(MedioDia::Tarde), None, Some(2)).validar(dia(17), dia(17))),
+            ["horas"]
+        );
+    }
+
+    #[test]
+    fn test_validar_rango() {
+        let dia = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        assert_eq!(
+            validar_rango("2025-03-17", "2025-03-19"),
+            Ok((dia(17), dia(19)))
+        );
+        // 366 días (2028 es bisiesto) es el máximo
+        assert!(validar_rango("2028-01-01", "2028-12-31").is_ok());
+        assert_eq!(
+            campos(&validar_rango("2025-01-01", "2026-01-02").unwrap_err()),
+            ["fecha_fin"]
+        );
+        assert_eq!(
+            campos(&validar_rango("2025-03-19", "2025-03-17").unwrap_err()),
+            ["fecha_fin"]
+        );
+        assert_eq!(
+            campos(&validar_rango("17/03/2025", "").unwrap_err()),
+            ["fecha_inicio", "fecha_fin"]
+        );
+    }
+}
@@ -1,8 +1,13 @@
 use aws_sdk_dynamodb::types::AttributeValue;
-use chrono::{Datelike, NaiveDate, Utc};
+use chrono::{Datelike, Months, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::models::dias::Dias;
+use crate::models::periodo::{MESES_PARA_DISFRUTAR, PeriodoVacaciones};
+use crate::models::politica::{PoliticaVacaciones, dias_por_antiguedad};
+use crate::models::solicitud::{EstadoSolicitud, SolicitudVacaciones};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Empleado {
     pub id: String,
@@ -11,6 +16,15 @@ pub struct Empleado {
     pub email: String,
     pub es_admin: bool,
     pub fecha_ingreso: String, // Formato: "YYYY-MM-DD"
+    // Oficina o sede; determina qué calendarios de días inhábiles le aplican
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ubicacion: Option<String>,
+    // Jefe directo: aprueba el paso `jefe_directo` de las cadenas de aprobación
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jefe_id: Option<String>,
+    // Horario de trabajo: qué días se descuentan de sus solicitudes (sin él, lunes a viernes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub horario_id: Option<String>,
 
     // Campo de autenticación (no se serializa en respuestas JSON por seguridad)
     #[serde(skip_serializing)]
@@ -18,25 +32,70 @@ pub struct Empleado {
 
     // Campos calculados (no se guardan en DB, se calculan dinámicamente)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub dias_disponibles: Option<i32>,
+    pub dias_disponibles: Option<Dias>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub dias_tomados: Option<i32>,
+    pub dias_tomados: Option<Dias>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub antiguedad_anos: Option<i32>,
+    // Periodo de servicio en curso al que corresponden dias_tomados y dias_disponibles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub periodo: Option<PeriodoVacaciones>,
+}
+
+/// Datos para dar de alta un empleado (POST /api/empleados)
+#[derive(Debug, Deserialize)]
+pub struct NuevoEmpleado {
+    pub id: Option<String>,
+    pub nombre: String,
+    pub departamento: String,
+    pub email: String,
+    #[serde(default)]
+    pub es_admin: bool,
+    pub fecha_ingreso: String,
+    pub ubicacion: Option<String>,
+    pub jefe_id: Option<String>,
+    pub horario_id: Option<String>,
+}
+
+/// Cambios parciales a un empleado (PUT /api/empleados/{id})
+#[derive(Debug, Deserialize)]
+pub struct ActualizarEmpleado {
+    pub nombre: Option<String>,
+    pub departamento: Option<String>,
+    pub email: Option<String>,
+    pub es_admin: Option<bool>,
+    pub fecha_ingreso: Option<String>,
+    pub ubicacion: Option<String>,
+    /// Un ID vacío quita al jefe
+    pub jefe_id: Option<String>,
+    /// Un ID vacío vuelve al horario de lunes a viernes
+    pub horario_id: Option<String>,
 }
 
 impl Empleado {
+    /// Normaliza un email para búsquedas y unicidad (sin espacios, minúsculas)
+    pub fn normalizar_email(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+
+    fn fecha_ingreso(&self) -> NaiveDate {
+        NaiveDate::parse_from_str(&self.fecha_ingreso, "%Y-%m-%d")
+            .unwrap_or_else(|_| Utc::now().date_naive())
+    }
+
     /// Calcula los años de antigüedad desde la fecha de ingreso
     pub fn calcular_antiguedad(&self) -> i32 {
-        let fecha_ingreso = NaiveDate::parse_from_str(&self.fecha_ingreso, "%Y-%m-%d")
-            .unwrap_or_else(|_| Utc::now().date_naive());
+        self.calcular_antiguedad_al(Utc::now().date_naive())
+    }
 
-        let hoy = Utc::now().date_naive();
-        let anos = (hoy.year() - fecha_ingreso.year()) as i32;
+    /// Años de antigüedad cumplidos en `fecha`
+    pub fn calcular_antiguedad_al(&self, fecha: NaiveDate) -> i32 {
+        let fecha_ingreso = self.fecha_ingreso();
+        let anos = fecha.year() - fecha_ingreso.year();
 
-        // Ajustar si aún no ha llegado el aniversario este año
-        if hoy.month() < fecha_ingreso.month()
-            || (hoy.month() == fecha_ingreso.month() && hoy.day() < fecha_ingreso.day())
+        // Ajustar si aún no ha llegado el aniversario ese año
+        if fecha.month() < fecha_ingreso.month()
+            || (fecha.month() == fecha_ingreso.month() && fecha.day() < fecha_ingreso.day())
         {
             anos - 1
         } else {
@@ -44,39 +103,112 @@ impl Empleado {
         }
     }
 
-    /// Calcula días de vacaciones según Ley Federal del Trabajo (México)
-    ///
-    /// Reglas:
-    /// - 1er año: 12 días
-    /// - 2do año: 14 días
-    /// - 3er año: 16 días
-    /// - 4to año: 18 días
-    /// - 5to año: 20 días
-    /// - A partir del 6to año: +2 días cada 5 años
-    pub fn calcular_dias_por_ley(&self) -> i32 {
-        let anos = self.calcular_antiguedad();
-
-        match anos {
-            0 => 0,  // Menos de 1 año = sin vacaciones
-            1 => 12, // 1er año
-            2 => 14, // 2do año
-            3 => 16, // 3er año
-            4 => 18, // 4to año
-            5 => 20, // 5to año
-            n if n >= 6 => {
-                // A partir del 6to año: 20 + 2 días cada 5 años
-                let anos_extra = n - 5;
-                let incrementos = anos_extra / 5;
-                20 + (incrementos * 2)
-            }
-            _ => 0,
+    /// Fecha en que se cumplen `anos` años de servicio (un ingreso el 29 de
+    /// febrero cumple años el 28 en años no bisiestos)
+    pub fn fecha_aniversario(&self, anos: i32) -> NaiveDate {
+        let ingreso = self.fecha_ingreso();
+        let ano = ingreso.year() + anos;
+        NaiveDate::from_ymd_opt(ano, ingreso.month(), ingreso.day())
+            .or_else(|| NaiveDate::from_ymd_opt(ano, ingreso.month(), ingreso.day() - 1))
+            .unwrap_or(ingreso)
+    }
+
+    /// Número del periodo de servicio que contiene `fecha` (años cumplidos en esa fecha)
+    pub fn periodo_de(&self, fecha: NaiveDate) -> i32 {
+        self.calcular_antiguedad_al(fecha).max(0)
+    }
+
+    /// Periodo al que se imputan los días de una solicitud: el de su fecha de inicio
+    pub fn periodo_de_solicitud(&self, solicitud: &SolicitudVacaciones) -> Option<i32> {
+        let inicio = NaiveDate::parse_from_str(&solicitud.fecha_inicio, "%Y-%m-%d").ok()?;
+        Some(self.periodo_de(inicio))
+    }
+
+    /// Suma los días de las solicitudes de vacaciones aprobadas descontados de un periodo
+    /// (los demás tipos de permiso no usan los periodos). Las aprobadas sin `consumo` registrado se imputan completas al periodo de su fecha de inicio.
+    pub fn dias_aprobados_en_periodo(
+        &self,
+        solicitudes: &[SolicitudVacaciones],
+        periodo: i32,
+    ) -> Dias {
+        solicitudes
+            .iter()
+            .filter(|s| s.estado == EstadoSolicitud::Aprobada && s.tipo_permiso.es_vacaciones())
+            .map(|s| {
+                if s.consumo.is_empty() {
+                    if self.periodo_de_solicitud(s) == Some(periodo) {
+                        s.dias_solicitados
+                    } else {
+                        Dias::CERO
+                    }
+                } else {
+                    s.consumo
+                        .iter()
+                        .filter(|c| c.periodo == periodo)
+                        .map(|c| c.dias)
+                        .sum()
+                }
+            })
+            .sum()
+    }
+
+    /// Último día para disfrutar los días de un periodo
+    pub fn vencimiento_periodo(&self, periodo: i32) -> NaiveDate {
+        let inicio = self.fecha_aniversario(periodo);
+        inicio
+            .checked_add_months(Months::new(MESES_PARA_DISFRUTAR))
+            .and_then(|fecha| fecha.pred_opt())
+            .unwrap_or(NaiveDate::MAX)
+    }
+
+    /// Periodos cuyos días se pueden disfrutar en `fecha`, del más antiguo al más reciente
+    pub fn periodos_vigentes_al(&self, fecha: NaiveDate) -> Vec<i32> {
+        (1..=self.periodo_de(fecha))
+            .filter(|&n| self.vencimiento_periodo(n) >= fecha)
+            .collect()
+    }
+
+    /// Días de derecho de un periodo, con la tabla vigente en el aniversario que lo abre
+    pub fn dias_derecho(&self, periodo: i32, politicas: &[PoliticaVacaciones]) -> i32 {
+        if periodo < 1 {
+            return 0; // Antes del primer aniversario = sin vacaciones
+        }
+        dias_por_antiguedad(periodo, self.fecha_aniversario(periodo), politicas)
+    }
+
+    /// Periodo `numero` con su derecho y los días ya tomados en él
+    pub fn periodo(
+        &self,
+        numero: i32,
+        dias_tomados: Dias,
+        politicas: &[PoliticaVacaciones],
+    ) -> PeriodoVacaciones {
+        let dias_derecho = self.dias_derecho(numero, politicas);
+        PeriodoVacaciones {
+            numero,
+            inicio: self.fecha_aniversario(numero),
+            fin: self
+                .fecha_aniversario(numero + 1)
+                .pred_opt()
+                .unwrap_or(NaiveDate::MAX),
+            vence: self.vencimiento_periodo(numero),
+            dias_derecho,
+            dias_tomados,
+            dias_disponibles: (Dias::enteros(dias_derecho) - dias_tomados).max(Dias::CERO),
         }
     }
 
-    /// Calcula los días disponibles restando los días tomados
-    pub fn calcular_dias_disponibles(&self, dias_tomados: i32) -> i32 {
-        let dias_por_ley = self.calcular_dias_por_ley();
-        (dias_por_ley - dias_tomados).max(0)
+    /// Calcula días de vacaciones según la Ley Federal del Trabajo (México)
+    /// vigente en el último aniversario, sin políticas de la empresa
+    pub fn calcular_dias_por_ley(&self) -> i32 {
+        self.calcular_dias_segun(&[])
+    }
+
+    /// Días de vacaciones del periodo de servicio en curso. La tabla se elige por la
+    /// fecha del último aniversario: quien cumplió 6 años en 2022 tuvo 14 días
+    /// (LFT 1970) y quien los cumple desde 2023 tiene 22 (reforma 2023).
+    pub fn calcular_dias_segun(&self, politicas: &[PoliticaVacaciones]) -> i32 {
+        self.dias_derecho(self.calcular_antiguedad(), politicas)
     }
 
     // pub fn to_item(&self) -> HashMap<String, AttributeValue> {
@@ -130,6 +262,24 @@ impl Empleado {
             AttributeValue::S("empleado".to_string()),
         );
 
+        if let Some(ref ubicacion) = self.ubicacion {
+            item.insert(
+                "ubicacion".to_string(),
+                AttributeValue::S(ubicacion.clone()),
+            );
+        }
+
+        if let Some(ref jefe_id) = self.jefe_id {
+            item.insert("jefe_id".to_string(), AttributeValue::S(jefe_id.clone()));
+        }
+
+        if let Some(ref horario_id) = self.horario_id {
+            item.insert(
+                "horario_id".to_string(),
+                AttributeValue::S(horario_id.clone()),
+            );
+        }
+
         // Incluir password_hash si existe
         if let Some(ref password_hash) = self.password_hash {
             item.insert(
@@ -141,6 +291,22 @@ impl Empleado {
         item
     }
 
+    /// Item índice `EMAIL#<email>` → empleado_id; garantiza emails únicos en DynamoDB
+    pub fn to_email_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("EMAIL#{}", Self::normalizar_email(&self.email))),
+        );
+        item.insert("SK".to_string(), AttributeValue::S("EMAIL".to_string()));
+        item.insert(
+            "empleado_id".to_string(),
+            AttributeValue::S(self.id.clone()),
+        );
+        item.insert("tipo".to_string(), AttributeValue::S("email".to_string()));
+        item
+    }
+
     pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
         Some(Empleado {
             id: item.get("id")?.as_s().ok()?.clone(),
@@ -149,13 +315,17 @@ impl Empleado {
             departamento: item.get("departamento")?.as_s().ok()?.clone(),
             es_admin: *item.get("es_admin")?.as_bool().ok()?,
             fecha_ingreso: item.get("fecha_ingreso")?.as_s().ok()?.clone(),
+            ubicacion: item.get("ubicacion").and_then(|v| v.as_s().ok()).cloned(),
+            jefe_id: item.get("jefe_id").and_then(|v| v.as_s().ok()).cloned(),
+            horario_id: item.get("horario_id").and_then(|v| v.as_s().ok()).cloned(),
             password_hash: item
                 .get("password_hash")
                 .and_then(|v| v.as_s().ok())
-                .map(|s| s.clone()),
+                .cloned(),
             dias_disponibles: None,
             dias_tomados: None,
             antiguedad_anos: None,
+            periodo: None,
         })
     }
 }
@@ -163,36 +333,157 @@ impl Empleado {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{ConsumoPeriodo, Jornada, TipoPermiso};
 
     #[test]
     fn test_calcular_dias_por_ley() {
-        // Empleado con 1 año
-        let emp1 = Empleado {
+        let emp = Empleado {
             id: "1".to_string(),
             nombre: "Test".to_string(),
             departamento: "IT".to_string(),
             email: "test@test.com".to_string(),
             es_admin: false,
-            fecha_ingreso: "2024-01-01".to_string(),
+            fecha_ingreso: "2015-01-10".to_string(),
+            ubicacion: None,
+            jefe_id: None,
+            horario_id: None,
             password_hash: None,
             dias_disponibles: None,
             dias_tomados: None,
             antiguedad_anos: None,
+            periodo: None,
+        };
+        let dias_al = |fecha: &str| {
+            let fecha = NaiveDate::parse_from_str(fecha, "%Y-%m-%d").unwrap();
+            let anos = emp.calcular_antiguedad_al(fecha);
+            dias_por_antiguedad(anos, emp.fecha_aniversario(anos), &[])
+        };
+
+        assert_eq!(dias_al("2016-01-09"), 0); // aún sin cumplir el primer año
+        assert_eq!(dias_al("2016-01-10"), 6); // 1 año en 2016 (LFT 1970)
+        assert_eq!(dias_al("2021-06-01"), 14); // 6 años en 2021 (LFT 1970)
+        assert_eq!(dias_al("2022-12-31"), 14); // 7 años, cumplidos antes de la reforma
+        assert_eq!(dias_al("2023-01-10"), 22); // 8 años en 2023 (reforma)
+        assert_eq!(dias_al("2026-01-10"), 24); // 11 años
+
+        let bisiesto = Empleado {
+            fecha_ingreso: "2020-02-29".to_string(),
+            ..emp.clone()
+        };
+        assert_eq!(
+            bisiesto.fecha_aniversario(1),
+            NaiveDate::from_ymd_opt(2021, 2, 28).unwrap()
+        );
+
+        let nuevo = Empleado {
+            fecha_ingreso: Utc::now().date_naive().to_string(),
+            ..emp
         };
-        assert_eq!(emp1.calcular_dias_por_ley(), 14);
+        assert_eq!(nuevo.calcular_dias_por_ley(), 0);
+    }
 
-        // Empleado con 3 años
-        let emp3 = Empleado {
-            fecha_ingreso: "2022-01-01".to_string(),
-            ..emp1.clone()
+    #[test]
+    fn test_periodos_por_aniversario() {
+        let emp = Empleado {
+            id: "1".to_string(),
+            nombre: "Test".to_string(),
+            departamento: "IT".to_string(),
+            email: "test@test.com".to_string(),
+            es_admin: false,
+            fecha_ingreso: "2022-06-15".to_string(),
+            ubicacion: None,
+            jefe_id: None,
+            horario_id: None,
+            password_hash: None,
+            dias_disponibles: None,
+            dias_tomados: None,
+            antiguedad_anos: None,
+            periodo: None,
+        };
+        let fecha = |texto: &str| NaiveDate::parse_from_str(texto, "%Y-%m-%d").unwrap();
+        let solicitud = |inicio: &str, dias: i32, estado: &str| SolicitudVacaciones {
+            id: inicio.to_string(),
+            empleado_id: "1".to_string(),
+            empleado_nombre: "Test".to_string(),
+            departamento: "Sistemas".to_string(),
+            tipo_permiso: TipoPermiso::Vacaciones,
+            documento: None,
+            fecha_inicio: inicio.to_string(),
+            fecha_fin: inicio.to_string(),
+            jornada: Jornada::default(),
+            estado: estado.parse().unwrap(),
+            dias_solicitados: Dias::enteros(dias),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            version: 0,
+            consumo: Vec::new(),
+            reprograma: None,
+            resuelto_por: None,
+            resuelto_at: None,
+            comentario: None,
+            pasos: Vec::new(),
+            aprobaciones: Vec::new(),
+            historial: Vec::new(),
         };
-        assert_eq!(emp3.calcular_dias_por_ley(), 18);
 
-        // Empleado con 10 años (5to año base + 5 años extra / 5 = 1 incremento)
-        let emp10 = Empleado {
-            fecha_ingreso: "2015-01-01".to_string(),
-            ..emp1.clone()
+        assert_eq!(emp.periodo_de(fecha("2023-06-14")), 0);
+        assert_eq!(emp.periodo_de(fecha("2023-06-15")), 1);
+        assert_eq!(emp.periodo_de(fecha("2024-06-14")), 1);
+        assert_eq!(emp.periodo_de(fecha("2024-06-15")), 2);
+
+        // Se imputa al periodo de la fecha de inicio, aunque sea el mismo año calendario
+        let solicitudes = [
+            solicitud("2024-03-04", 5, "aprobada"),
+            solicitud("2024-06-14", 2, "aprobada"),
+            solicitud("2024-06-17", 4, "aprobada"),
+            solicitud("2024-07-01", 3, "rechazada"),
+        ];
+        assert_eq!(
+            emp.dias_aprobados_en_periodo(&solicitudes, 1),
+            Dias::enteros(7)
+        );
+        assert_eq!(
+            emp.dias_aprobados_en_periodo(&solicitudes, 2),
+            Dias::enteros(4)
+        );
+
+        let periodo = emp.periodo(2, Dias::enteros(4), &[]);
+        assert_eq!(periodo.inicio, fecha("2024-06-15"));
+        assert_eq!(periodo.fin, fecha("2025-06-14"));
+        assert_eq!(periodo.dias_derecho, 14);
+        assert_eq!(periodo.dias_disponibles, Dias::enteros(10));
+        assert!(periodo.contiene(fecha("2025-06-14")));
+        assert!(!periodo.contiene(fecha("2025-06-15")));
+        assert_eq!(emp.periodo(0, Dias::CERO, &[]).dias_derecho, 0);
+
+        // Los días del periodo 2 se pueden disfrutar hasta 18 meses después del aniversario
+        assert_eq!(periodo.vence, fecha("2025-12-14"));
+        assert_eq!(emp.periodos_vigentes_al(fecha("2025-12-14")), vec![2, 3]);
+        assert_eq!(emp.periodos_vigentes_al(fecha("2025-12-15")), vec![3]);
+        assert!(emp.periodos_vigentes_al(fecha("2023-06-14")).is_empty());
+
+        // Con consumo registrado cuenta lo descontado de cada periodo
+        let repartida = SolicitudVacaciones {
+            estado: EstadoSolicitud::Aprobada,
+            consumo: vec![
+                ConsumoPeriodo {
+                    periodo: 1,
+                    dias: Dias::enteros(3),
+                },
+                ConsumoPeriodo {
+                    periodo: 2,
+                    dias: Dias::enteros(2),
+                },
+            ],
+            ..solicitud("2024-07-01", 5, "aprobada")
         };
-        assert_eq!(emp10.calcular_dias_por_ley(), 22); // 20 + 2
+        let repartidas = [repartida];
+        assert_eq!(
+            emp.dias_aprobados_en_periodo(&repartidas, 1),
+            Dias::enteros(3)
+        );
+        assert_eq!(
+            emp.dias_aprobados_en_periodo(&repartidas, 2),
+            Dias::enteros(2)
+        );
     }
 }
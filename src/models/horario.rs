@@ -0,0 +1,248 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::Empleado;
+
+/// Qué días se trabaja
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "snake_case")]
+pub enum PatronHorario {
+    /// Los mismos días cada semana, de 1 (lunes) a 7 (domingo)
+    Semanal { dias: Vec<u32> },
+    /// Turnos rotativos: `trabaja` días seguidos y `descansa` días de descanso (p. ej. 4x3),
+    /// con el primer día de trabajo del ciclo en `inicio`
+    Rotativo {
+        inicio: NaiveDate,
+        trabaja: u32,
+        descansa: u32,
+    },
+}
+
+/// Horario de trabajo que se asigna a los empleados (`Empleado::horario_id`); solo se
+/// descuentan de una solicitud los días en que el empleado habría trabajado
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HorarioTrabajo {
+    pub id: String,
+    pub nombre: String,
+    pub patron: PatronHorario,
+}
+
+/// Datos de un horario para crearlo o reemplazarlo (POST/PUT /api/horarios)
+#[derive(Debug, Deserialize)]
+pub struct DatosHorario {
+    pub nombre: String,
+    pub patron: PatronHorario,
+}
+
+/// Id del horario incluido en el código (no se puede editar)
+pub const HORARIO_LUNES_A_VIERNES: &str = "lunes-viernes";
+
+const NOMBRES_DIAS: [&str; 7] = ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"];
+
+/// Horario de quien no tiene uno asignado: lunes a viernes
+pub fn horario_por_defecto() -> HorarioTrabajo {
+    HorarioTrabajo {
+        id: HORARIO_LUNES_A_VIERNES.to_string(),
+        nombre: "Lunes a viernes".to_string(),
+        patron: PatronHorario::Semanal {
+            dias: vec![1, 2, 3, 4, 5],
+        },
+    }
+}
+
+/// Nombre corto del día de la semana de `fecha` ("lun" … "dom")
+pub fn dia_corto(fecha: NaiveDate) -> &'static str {
+    NOMBRES_DIAS[fecha.weekday().num_days_from_monday() as usize]
+}
+
+/// Horario del empleado entre `horarios`; lunes a viernes si no tiene uno asignado o ya
+/// no existe
+pub fn horario_de(horarios: &[HorarioTrabajo], empleado: &Empleado) -> HorarioTrabajo {
+    empleado
+        .horario_id
+        .as_deref()
+        .and_then(|id| horarios.iter().find(|h| h.id == id))
+        .cloned()
+        .unwrap_or_else(horario_por_defecto)
+}
+
+impl HorarioTrabajo {
+    pub fn es_de_codigo(&self) -> bool {
+        self.id == HORARIO_LUNES_A_VIERNES
+    }
+
+    /// Si `fecha` es día de trabajo. Un ciclo rotativo se repite también hacia atrás de
+    /// su `inicio`.
+    pub fn trabaja(&self, fecha: NaiveDate) -> bool {
+        match &self.patron {
+            PatronHorario::Semanal { dias } => dias.contains(&fecha.weekday().number_from_monday()),
+            PatronHorario::Rotativo {
+                inicio,
+                trabaja,
+                descansa,
+            } => {
+                let ciclo = (i64::from(*trabaja) + i64::from(*descansa)).max(1);
+                (fecha - *inicio).num_days().rem_euclid(ciclo) < i64::from(*trabaja)
+            }
+        }
+    }
+
+    /// Descripción para las vistas, p. ej. "lun, mar, mié, jue, vie, sáb" o
+    /// "4x3 desde 2025-01-06"
+    pub fn descripcion(&self) -> String {
+        match &self.patron {
+            PatronHorario::Semanal { dias } => dias
+                .iter()
+                .filter_map(|&d| NOMBRES_DIAS.get(d.checked_sub(1)? as usize))
+                .copied()
+                .collect::<Vec<_>>()
+                .join(", "),
+            PatronHorario::Rotativo {
+                inicio,
+                trabaja,
+                descansa,
+            } => format!("{}x{} desde {}", trabaja, descansa, inicio),
+        }
+    }
+
+    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("HORARIO#{}", self.id)),
+        );
+        item.insert("SK".to_string(), AttributeValue::S("METADATA".to_string()));
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert("nombre".to_string(), AttributeValue::S(self.nombre.clone()));
+        match &self.patron {
+            PatronHorario::Semanal { dias } => {
+                item.insert(
+                    "patron".to_string(),
+                    AttributeValue::S("semanal".to_string()),
+                );
+                item.insert(
+                    "dias".to_string(),
+                    AttributeValue::L(
+                        dias.iter()
+                            .map(|d| AttributeValue::N(d.to_string()))
+                            .collect(),
+                    ),
+                );
+            }
+            PatronHorario::Rotativo {
+                inicio,
+                trabaja,
+                descansa,
+            } => {
+                item.insert(
+                    "patron".to_string(),
+                    AttributeValue::S("rotativo".to_string()),
+                );
+                item.insert("inicio".to_string(), AttributeValue::S(inicio.to_string()));
+                item.insert(
+                    "trabaja".to_string(),
+                    AttributeValue::N(trabaja.to_string()),
+                );
+                item.insert(
+                    "descansa".to_string(),
+                    AttributeValue::N(descansa.to_string()),
+                );
+            }
+        }
+        item.insert("tipo".to_string(), AttributeValue::S("horario".to_string()));
+        item
+    }
+
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let numero = |nombre: &str| -> Option<u32> { item.get(nombre)?.as_n().ok()?.parse().ok() };
+
+        let patron = match item.get("patron")?.as_s().ok()?.as_str() {
+            "semanal" => PatronHorario::Semanal {
+                dias: item
+                    .get("dias")?
+                    .as_l()
+                    .ok()?
+                    .iter()
+                    .filter_map(|v| v.as_n().ok()?.parse().ok())
+                    .collect(),
+            },
+            "rotativo" => PatronHorario::Rotativo {
+                inicio: item.get("inicio")?.as_s().ok()?.parse().ok()?,
+                trabaja: numero("trabaja")?,
+                descansa: numero("descansa")?,
+            },
+            _ => return None,
+        };
+        Some(HorarioTrabajo {
+            id: item.get("id")?.as_s().ok()?.clone(),
+            nombre: item.get("nombre")?.as_s().ok()?.clone(),
+            patron,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fecha(texto: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(texto, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_dias_de_trabajo() {
+        let almacen = HorarioTrabajo {
+            id: "almacen".to_string(),
+            nombre: "Almacén".to_string(),
+            patron: PatronHorario::Semanal {
+                dias: vec![1, 2, 3, 4, 5, 6],
+            },
+        };
+        // 2025-03-08 es sábado
+        assert!(almacen.trabaja(fecha("2025-03-08")));
+        assert!(!almacen.trabaja(fecha("2025-03-09")));
+        assert!(!horario_por_defecto().trabaja(fecha("2025-03-08")));
+        assert_eq!(almacen.descripcion(), "lun, mar, mié, jue, vie, sáb");
+
+        // 4x3 que empieza el jueves 6 de marzo: trabaja jueves a domingo, descansa lunes a miércoles
+        let turno = HorarioTrabajo {
+            id: "4x3".to_string(),
+            nombre: "Turno 4x3".to_string(),
+            patron: PatronHorario::Rotativo {
+                inicio: fecha("2025-03-06"),
+                trabaja: 4,
+                descansa: 3,
+            },
+        };
+        let trabajados: Vec<bool> = fecha("2025-03-03")
+            .iter_days()
+            .take(14)
+            .map(|f| turno.trabaja(f))
+            .collect();
+        assert_eq!(
+            trabajados,
+            [
+                false, false, false, true, true, true, true, false, false, false, true, true, true,
+                true
+            ]
+        );
+        assert_eq!(turno.descripcion(), "4x3 desde 2025-03-06");
+
+        // Un ciclo fuera de rango (p. ej. guardado antes de validarlo) no desborda
+        let extremo = HorarioTrabajo {
+            patron: PatronHorario::Rotativo {
+                inicio: fecha("2025-03-06"),
+                trabaja: u32::MAX,
+                descansa: u32::MAX,
+            },
+            ..turno.clone()
+        };
+        assert!(extremo.trabaja(fecha("2025-03-10")));
+
+        for horario in [almacen, turno] {
+            assert_eq!(HorarioTrabajo::from_item(&horario.to_item()), Some(horario));
+        }
+    }
+}
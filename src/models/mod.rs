@@ -1,6 +1,29 @@
+pub mod cadena;
+pub mod calendario;
+pub mod delegacion;
+pub mod dias;
 pub mod empleado;
+pub mod horario;
+pub mod pagina;
+pub mod periodo;
+pub mod permiso;
+pub mod politica;
+pub mod sesion;
 pub mod solicitud;
 
 // Re-exportar para uso fácil
-pub use empleado::Empleado;
-pub use solicitud::{NuevaSolicitud, SolicitudVacaciones};
+pub use cadena::{CadenaAprobacion, DatosCadena, DecisionPaso, PasoAprobacion};
+pub use calendario::{CalendarioEmpresa, DatosCalendario, Feriado};
+pub use delegacion::{Delegacion, NuevaDelegacion};
+pub use dias::{Dias, Jornada, MedioDia};
+pub use empleado::{ActualizarEmpleado, Empleado, NuevoEmpleado};
+pub use horario::{DatosHorario, HorarioTrabajo, PatronHorario};
+pub use pagina::{Pagina, Paginacion};
+pub use periodo::{ConsumoPeriodo, DiasPorVencer, FiltroVencimientos, PeriodoVacaciones};
+pub use permiso::{FiltroSaldos, SaldoPermiso, TipoPermiso};
+pub use politica::{AmbitoPolitica, DatosPolitica, PoliticaVacaciones};
+pub use sesion::Sesion;
+pub use solicitud::{
+    ACTOR_SISTEMA, CambioEstado, CambioSaldo, EstadoSolicitud, FiltroSolicitudes, NuevaSolicitud,
+    Reprogramacion, Resolucion, Revocacion, SolicitudPorAprobar, SolicitudVacaciones,
+};
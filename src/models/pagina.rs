@@ -0,0 +1,92 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, AppResult};
+
+/// Elementos por página cuando no se indica `limit`
+pub const LIMITE_POR_DEFECTO: usize = 50;
+
+/// Máximo de elementos que se devuelven en una página
+pub const LIMITE_MAXIMO: usize = 200;
+
+/// Parámetros de paginación (`?limit=&cursor=`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Paginacion {
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+impl Paginacion {
+    /// Límite efectivo, acotado entre 1 y `LIMITE_MAXIMO`
+    pub fn limite(&self) -> usize {
+        self.limit
+            .unwrap_or(LIMITE_POR_DEFECTO)
+            .clamp(1, LIMITE_MAXIMO)
+    }
+
+    /// Decodifica la posición guardada en el cursor (`None` en la primera página)
+    pub fn posicion<T: DeserializeOwned>(&self) -> AppResult<Option<T>> {
+        match self.cursor.as_deref() {
+            None | Some("") => Ok(None),
+            Some(cursor) => decodificar_cursor(cursor).map(Some),
+        }
+    }
+}
+
+/// Una página de resultados y el cursor para pedir la siguiente
+#[derive(Debug, Clone, Serialize)]
+pub struct Pagina<T> {
+    pub elementos: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub siguiente_cursor: Option<String>,
+}
+
+impl<T> Pagina<T> {
+    pub fn new<P: Serialize>(elementos: Vec<T>, siguiente: Option<P>) -> Self {
+        Self {
+            elementos,
+            siguiente_cursor: siguiente.as_ref().map(codificar_cursor),
+        }
+    }
+
+    /// Construye la página a partir de hasta `limite + 1` elementos ordenados:
+    /// si sobra uno, hay más resultados y el cursor apunta al último devuelto.
+    pub fn recortar<P: Serialize>(
+        mut elementos: Vec<T>,
+        limite: usize,
+        posicion: impl Fn(&T) -> P,
+    ) -> Self {
+        let siguiente = if elementos.len() > limite {
+            elementos.truncate(limite);
+            elementos.last().map(posicion)
+        } else {
+            None
+        };
+        Self::new(elementos, siguiente)
+    }
+}
+
+/// El cursor es la posición serializada en JSON y codificada en hexadecimal,
+/// para que sea opaco para el cliente y seguro dentro de una URL
+fn codificar_cursor<P: Serialize>(posicion: &P) -> String {
+    serde_json::to_vec(posicion)
+        .unwrap_or_default()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn decodificar_cursor<T: DeserializeOwned>(cursor: &str) -> AppResult<T> {
+    let invalido = || AppError::BadRequest("Cursor de paginación inválido".to_string());
+
+    if !cursor.is_ascii() || !cursor.len().is_multiple_of(2) {
+        return Err(invalido());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalido())?;
+
+    serde_json::from_slice(&bytes).map_err(|_| invalido())
+}
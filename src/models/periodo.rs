@@ -0,0 +1,168 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::models::dias::Dias;
+
+/// Meses desde el aniversario durante los que se pueden disfrutar los días de un periodo:
+/// el patrón tiene 6 meses para otorgarlos (LFT art. 81) y el derecho prescribe un año
+/// después de que es exigible (art. 516).
+pub const MESES_PARA_DISFRUTAR: u32 = 18;
+
+/// Año de servicio de un empleado, de un aniversario al día anterior al siguiente.
+///
+/// `numero` son los años cumplidos al iniciar el periodo: el periodo 1 empieza en el
+/// primer aniversario y da derecho a los días del primer año de servicio. El periodo 0
+/// (antes del primer aniversario) no genera días. Los días no tomados se conservan hasta
+/// `vence` (último día para disfrutarlos).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeriodoVacaciones {
+    pub numero: i32,
+    pub inicio: NaiveDate,
+    pub fin: NaiveDate,
+    pub vence: NaiveDate,
+    pub dias_derecho: i32,
+    pub dias_tomados: Dias,
+    pub dias_disponibles: Dias,
+}
+
+/// Días de una solicitud aprobada descontados de un periodo de servicio
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsumoPeriodo {
+    pub periodo: i32,
+    pub dias: Dias,
+}
+
+/// Días hacia adelante que revisa la vista de vencimientos si no se indica `?dias=`
+pub const DIAS_POR_VENCER: i64 = 90;
+
+/// Parámetros de GET /vencimientos y /api/vencimientos
+#[derive(Debug, Deserialize)]
+pub struct FiltroVencimientos {
+    pub dias: Option<i64>,
+}
+
+impl FiltroVencimientos {
+    /// Días efectivos, acotados entre 0 y dos años
+    pub fn dias(&self) -> i64 {
+        self.dias.unwrap_or(DIAS_POR_VENCER).clamp(0, 730)
+    }
+}
+
+/// Periodo con días sin tomar que vencen pronto (GET /api/vencimientos)
+#[derive(Debug, Clone, Serialize)]
+pub struct DiasPorVencer {
+    pub empleado_id: String,
+    pub empleado_nombre: String,
+    pub departamento: String,
+    pub periodo: PeriodoVacaciones,
+}
+
+impl PeriodoVacaciones {
+    pub fn contiene(&self, fecha: NaiveDate) -> bool {
+        self.inicio <= fecha && fecha <= self.fin
+    }
+
+    /// Si sus días se pueden disfrutar en `fecha` (ya empezó y no ha vencido)
+    pub fn vigente_al(&self, fecha: NaiveDate) -> bool {
+        self.inicio <= fecha && fecha <= self.vence
+    }
+
+    /// Rango legible del periodo, p. ej. "2024-06-15 – 2025-06-14"
+    pub fn etiqueta(&self) -> String {
+        format!("{} – {}", self.inicio, self.fin)
+    }
+
+    /// Reparte `dias` entre los periodos en orden (el más antiguo primero), tomando de cada
+    /// uno lo que tenga disponible. `Err` con el total disponible si no alcanza.
+    pub fn repartir(
+        periodos: &[PeriodoVacaciones],
+        dias: Dias,
+    ) -> Result<Vec<ConsumoPeriodo>, Dias> {
+        let mut consumo = Vec::new();
+        let mut pendientes = dias;
+
+        for periodo in periodos {
+            if pendientes == Dias::CERO {
+                break;
+            }
+            let tomar = pendientes.min(periodo.dias_disponibles);
+            if tomar.es_positivo() {
+                consumo.push(ConsumoPeriodo {
+                    periodo: periodo.numero,
+                    dias: tomar,
+                });
+                pendientes -= tomar;
+            }
+        }
+
+        if pendientes.es_positivo() {
+            return Err(periodos.iter().map(|p| p.dias_disponibles).sum());
+        }
+        Ok(consumo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn periodo(numero: i32, dias_disponibles: i32) -> PeriodoVacaciones {
+        let fecha = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        PeriodoVacaciones {
+            numero,
+            inicio: fecha,
+            fin: fecha,
+            vence: fecha,
+            dias_derecho: dias_disponibles,
+            dias_tomados: Dias::CERO,
+            dias_disponibles: Dias::enteros(dias_disponibles),
+        }
+    }
+
+    #[test]
+    fn test_repartir_primero_el_mas_antiguo() {
+        let periodos = [periodo(2, 3), periodo(3, 0), periodo(4, 16)];
+
+        assert_eq!(
+            PeriodoVacaciones::repartir(&periodos, Dias::enteros(5)),
+            Ok(vec![
+                ConsumoPeriodo {
+                    periodo: 2,
+                    dias: Dias::enteros(3)
+                },
+                ConsumoPeriodo {
+                    periodo: 4,
+                    dias: Dias::enteros(2)
+                },
+            ])
+        );
+        assert_eq!(
+            PeriodoVacaciones::repartir(&periodos, Dias::enteros(2)),
+            Ok(vec![ConsumoPeriodo {
+                periodo: 2,
+                dias: Dias::enteros(2)
+            }])
+        );
+        assert_eq!(
+            PeriodoVacaciones::repartir(&periodos, Dias::enteros(20)),
+            Err(Dias::enteros(19))
+        );
+        // Medio día que queda en un periodo se toma antes de pasar al siguiente
+        let mut medio = periodo(2, 0);
+        medio.dias_disponibles = Dias::MEDIO;
+        let periodos = [medio, periodo(4, 16)];
+        assert_eq!(
+            PeriodoVacaciones::repartir(&periodos, Dias::enteros(1)),
+            Ok(vec![
+                ConsumoPeriodo {
+                    periodo: 2,
+                    dias: Dias::MEDIO
+                },
+                ConsumoPeriodo {
+                    periodo: 4,
+                    dias: Dias::MEDIO
+                },
+            ])
+        );
+    }
+}
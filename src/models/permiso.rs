@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::dias::Dias;
+use crate::models::solicitud::{EstadoSolicitud, SolicitudVacaciones};
+
+/// Tipo de ausencia de una solicitud. Solo las vacaciones se descuentan de los periodos de
+/// servicio (LFT); el resto tiene sus propias reglas (ver `TipoPermiso::reglas`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TipoPermiso {
+    #[default]
+    Vacaciones,
+    PermisoConGoce,
+    PermisoSinGoce,
+    /// LFT art. 132 fracc. XXVII Bis: cinco días laborables con goce de sueldo por
+    /// nacimiento o adopción
+    Paternidad,
+    /// Certificado de incapacidad del IMSS; se registra, no se aprueba
+    IncapacidadImss,
+    DiaPersonal,
+    HomeOffice,
+}
+
+/// Cuántos días puede tomar un empleado de un tipo de permiso
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "regla", content = "dias", rename_all = "snake_case")]
+pub enum Derecho {
+    /// Saldo de los periodos de servicio (tabla de la LFT o política de la empresa)
+    PeriodosDeServicio,
+    /// Días por año calendario (el de la fecha de inicio de cada solicitud)
+    PorAno(i32),
+    /// Días por cada solicitud (p. ej. por cada nacimiento)
+    PorSolicitud(i32),
+    SinLimite,
+}
+
+/// Qué días de un rango cuentan para una solicitud
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiasQueCuentan {
+    /// Lunes a viernes sin feriados ni días inhábiles de la empresa
+    Laborables,
+    /// Todos los días del rango
+    Naturales,
+}
+
+/// Reglas de un tipo de permiso
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReglasPermiso {
+    pub derecho: Derecho,
+    pub dias: DiasQueCuentan,
+    /// Sin aprobación la solicitud queda aprobada al registrarla
+    pub requiere_aprobacion: bool,
+    /// Exige `documento` (folio del certificado, acta, etc.)
+    pub requiere_documento: bool,
+    /// Se puede registrar con fecha de inicio en el pasado
+    pub retroactivo: bool,
+    pub con_goce: bool,
+}
+
+impl ReglasPermiso {
+    /// Si cuenta todos los días del rango, no solo los laborables
+    pub fn cuenta_naturales(&self) -> bool {
+        self.dias == DiasQueCuentan::Naturales
+    }
+}
+
+impl TipoPermiso {
+    pub const TODOS: [TipoPermiso; 7] = [
+        TipoPermiso::Vacaciones,
+        TipoPermiso::PermisoConGoce,
+        TipoPermiso::PermisoSinGoce,
+        TipoPermiso::Paternidad,
+        TipoPermiso::IncapacidadImss,
+        TipoPermiso::DiaPersonal,
+        TipoPermiso::HomeOffice,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TipoPermiso::Vacaciones => "vacaciones",
+            TipoPermiso::PermisoConGoce => "permiso_con_goce",
+            TipoPermiso::PermisoSinGoce => "permiso_sin_goce",
+            TipoPermiso::Paternidad => "paternidad",
+            TipoPermiso::IncapacidadImss => "incapacidad_imss",
+            TipoPermiso::DiaPersonal => "dia_personal",
+            TipoPermiso::HomeOffice => "home_office",
+        }
+    }
+
+    /// Nombre para mostrar en las vistas
+    pub fn nombre(&self) -> &'static str {
+        match self {
+            TipoPermiso::Vacaciones => "Vacaciones",
+            TipoPermiso::PermisoConGoce => "Permiso con goce de sueldo",
+            TipoPermiso::PermisoSinGoce => "Permiso sin goce de sueldo",
+            TipoPermiso::Paternidad => "Permiso de paternidad",
+            TipoPermiso::IncapacidadImss => "Incapacidad IMSS",
+            TipoPermiso::DiaPersonal => "Día personal",
+            TipoPermiso::HomeOffice => "Home office",
+        }
+    }
+
+    pub fn reglas(&self) -> ReglasPermiso {
+        let base = ReglasPermiso {
+            derecho: Derecho::SinLimite,
+            dias: DiasQueCuentan::Laborables,
+            requiere_aprobacion: true,
+            requiere_documento: false,
+            retroactivo: false,
+            con_goce: true,
+        };
+        match self {
+            TipoPermiso::Vacaciones => ReglasPermiso {
+                derecho: Derecho::PeriodosDeServicio,
+                ..base
+            },
+            TipoPermiso::PermisoConGoce => ReglasPermiso {
+                derecho: Derecho::PorAno(3),
+                ..base
+            },
+            TipoPermiso::PermisoSinGoce => ReglasPermiso {
+                con_goce: false,
+                ..base
+            },
+            TipoPermiso::Paternidad => ReglasPermiso {
+                derecho: Derecho::PorSolicitud(5),
+                requiere_documento: true,
+                ..base
+            },
+            TipoPermiso::IncapacidadImss => ReglasPermiso {
+                dias: DiasQueCuentan::Naturales,
+                requiere_aprobacion: false,
+                requiere_documento: true,
+                retroactivo: true,
+                // El IMSS paga el subsidio, no la empresa
+                con_goce: false,
+                ..base
+            },
+            TipoPermiso::DiaPersonal => ReglasPermiso {
+                derecho: Derecho::PorAno(2),
+                ..base
+            },
+            TipoPermiso::HomeOffice => base,
+        }
+    }
+
+    /// Si sus días se descuentan de los periodos de servicio
+    pub fn es_vacaciones(&self) -> bool {
+        *self == TipoPermiso::Vacaciones
+    }
+}
+
+impl fmt::Display for TipoPermiso {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TipoPermiso {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TipoPermiso::TODOS
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| format!("Tipo de permiso inválido: {}", s))
+    }
+}
+
+/// Saldo de un tipo de permiso de un empleado en un año calendario
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SaldoPermiso {
+    pub tipo: TipoPermiso,
+    pub nombre: &'static str,
+    pub derecho: Derecho,
+    /// Días de solicitudes aprobadas que empiezan en el año
+    pub usados: Dias,
+    /// Días de solicitudes pendientes que empiezan en el año
+    pub pendientes: Dias,
+    /// `None` sin límite; para vacaciones, los disponibles en los periodos vigentes
+    pub disponibles: Option<Dias>,
+}
+
+impl SaldoPermiso {
+    /// Derecho para mostrar en las vistas
+    pub fn derecho_texto(&self) -> String {
+        match self.derecho {
+            Derecho::PeriodosDeServicio => "Periodos de servicio".to_string(),
+            Derecho::PorAno(dias) => format!("{} días por año", dias),
+            Derecho::PorSolicitud(dias) => format!("{} días por solicitud", dias),
+            Derecho::SinLimite => "Sin límite".to_string(),
+        }
+    }
+}
+
+/// Query de GET /api/empleados/:id/permisos; por omisión, el año en curso
+#[derive(Debug, Default, Deserialize)]
+pub struct FiltroSaldos {
+    pub ano: Option<i32>,
+}
+
+/// Días de `tipo` en solicitudes con `estado` que empiezan en el año `ano`
+pub fn dias_en_ano(
+    solicitudes: &[SolicitudVacaciones],
+    tipo: TipoPermiso,
+    estado: EstadoSolicitud,
+    ano: i32,
+) -> Dias {
+    solicitudes
+        .iter()
+        .filter(|s| {
+            s.tipo_permiso == tipo
+                && s.estado == estado
+                && s.fecha_inicio.starts_with(&format!("{:04}-", ano))
+        })
+        .map(|s| s.dias_solicitados)
+        .sum()
+}
+
+/// Saldos de todos los tipos de permiso en el año `ano`. `vacaciones_disponibles` viene de
+/// los periodos de servicio, que no dependen del año calendario.
+pub fn saldos_del_ano(
+    solicitudes: &[SolicitudVacaciones],
+    ano: i32,
+    vacaciones_disponibles: Dias,
+) -> Vec<SaldoPermiso> {
+    TipoPermiso::TODOS
+        .into_iter()
+        .map(|tipo| {
+            let derecho = tipo.reglas().derecho;
+            let usados = dias_en_ano(solicitudes, tipo, EstadoSolicitud::Aprobada, ano);
+            SaldoPermiso {
+                tipo,
+                nombre: tipo.nombre(),
+                derecho,
+                usados,
+                pendientes: dias_en_ano(solicitudes, tipo, EstadoSolicitud::Pendiente, ano),
+                disponibles: match derecho {
+                    Derecho::PeriodosDeServicio => Some(vacaciones_disponibles),
+                    Derecho::PorAno(dias) => Some((Dias::enteros(dias) - usados).max(Dias::CERO)),
+                    Derecho::PorSolicitud(_) | Derecho::SinLimite => None,
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tipo_permiso_desde_texto() {
+        for tipo in TipoPermiso::TODOS {
+            assert_eq!(tipo.as_str().parse::<TipoPermiso>(), Ok(tipo));
+        }
+        assert!("vacación".parse::<TipoPermiso>().is_err());
+        assert!(TipoPermiso::IncapacidadImss.reglas().cuenta_naturales());
+        assert!(!TipoPermiso::IncapacidadImss.reglas().requiere_aprobacion);
+    }
+}
@@ -0,0 +1,276 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Origen de una política: la ley fija el mínimo; la empresa puede mejorarlo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AmbitoPolitica {
+    Ley,
+    Empresa,
+}
+
+impl AmbitoPolitica {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AmbitoPolitica::Ley => "ley",
+            AmbitoPolitica::Empresa => "empresa",
+        }
+    }
+
+    pub fn parse(texto: &str) -> Option<Self> {
+        match texto {
+            "ley" => Some(AmbitoPolitica::Ley),
+            "empresa" => Some(AmbitoPolitica::Empresa),
+            _ => None,
+        }
+    }
+}
+
+/// Tabla de días de vacaciones por años de servicio, vigente desde una fecha.
+///
+/// `tabla[n - 1]` son los días del año de servicio `n`; después de la tabla se
+/// suman `incremento` días por cada bloque de `cada_anos` años.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoliticaVacaciones {
+    pub id: String,
+    pub nombre: String,
+    pub ambito: AmbitoPolitica,
+    pub vigente_desde: NaiveDate,
+    pub tabla: Vec<i32>,
+    pub incremento: i32,
+    pub cada_anos: i32,
+}
+
+/// Datos de una política para crearla o reemplazarla (POST/PUT /api/politicas)
+#[derive(Debug, Deserialize)]
+pub struct DatosPolitica {
+    pub nombre: String,
+    pub ambito: AmbitoPolitica,
+    pub vigente_desde: NaiveDate,
+    pub tabla: Vec<i32>,
+    pub incremento: i32,
+    pub cada_anos: i32,
+}
+
+/// Id de las políticas de la LFT incluidas en el código (no se pueden editar)
+pub const POLITICA_LFT_1970: &str = "lft-1970";
+pub const POLITICA_LFT_2023: &str = "lft-2023";
+
+/// Políticas de la Ley Federal del Trabajo (art. 76):
+/// - Hasta 2022: 6, 8, 10 y 12 días los primeros cuatro años; +2 cada 5 años
+/// - Reforma "vacaciones dignas" (DOF 27/12/2022, vigente 1/1/2023): 12, 14, 16,
+///   18 y 20 días los primeros cinco años; +2 cada 5 años (22 del 6to al 10mo)
+pub fn politicas_ley() -> Vec<PoliticaVacaciones> {
+    vec![
+        PoliticaVacaciones {
+            id: POLITICA_LFT_1970.to_string(),
+            nombre: "LFT 1970".to_string(),
+            ambito: AmbitoPolitica::Ley,
+            vigente_desde: NaiveDate::from_ymd_opt(1970, 5, 1).expect("fecha válida"),
+            tabla: vec![6, 8, 10, 12],
+            incremento: 2,
+            cada_anos: 5,
+        },
+        PoliticaVacaciones {
+            id: POLITICA_LFT_2023.to_string(),
+            nombre: "LFT reforma 2023 (vacaciones dignas)".to_string(),
+            ambito: AmbitoPolitica::Ley,
+            vigente_desde: NaiveDate::from_ymd_opt(2023, 1, 1).expect("fecha válida"),
+            tabla: vec![12, 14, 16, 18, 20],
+            incremento: 2,
+            cada_anos: 5,
+        },
+    ]
+}
+
+impl PoliticaVacaciones {
+    pub fn es_de_codigo(&self) -> bool {
+        self.id == POLITICA_LFT_1970 || self.id == POLITICA_LFT_2023
+    }
+
+    /// Días que corresponden al cumplir `anos` años de servicio
+    pub fn dias_para(&self, anos: i32) -> i32 {
+        let Some(&ultimo) = self.tabla.last() else {
+            return 0;
+        };
+        let largo = self.tabla.len() as i32;
+
+        match anos {
+            n if n < 1 => 0,
+            n if n <= largo => self.tabla[(n - 1) as usize],
+            n => ultimo + self.incremento * ((n - largo - 1) / self.cada_anos.max(1) + 1),
+        }
+    }
+
+    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("POLITICA#{}", self.id)),
+        );
+        item.insert("SK".to_string(), AttributeValue::S("METADATA".to_string()));
+        item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
+        item.insert("nombre".to_string(), AttributeValue::S(self.nombre.clone()));
+        item.insert(
+            "ambito".to_string(),
+            AttributeValue::S(self.ambito.as_str().to_string()),
+        );
+        item.insert(
+            "vigente_desde".to_string(),
+            AttributeValue::S(self.vigente_desde.to_string()),
+        );
+        item.insert(
+            "tabla".to_string(),
+            AttributeValue::L(
+                self.tabla
+                    .iter()
+                    .map(|d| AttributeValue::N(d.to_string()))
+                    .collect(),
+            ),
+        );
+        item.insert(
+            "incremento".to_string(),
+            AttributeValue::N(self.incremento.to_string()),
+        );
+        item.insert(
+            "cada_anos".to_string(),
+            AttributeValue::N(self.cada_anos.to_string()),
+        );
+        item.insert(
+            "tipo".to_string(),
+            AttributeValue::S("politica".to_string()),
+        );
+        item
+    }
+
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        let numero = |nombre: &str| -> Option<i32> { item.get(nombre)?.as_n().ok()?.parse().ok() };
+
+        Some(PoliticaVacaciones {
+            id: item.get("id")?.as_s().ok()?.clone(),
+            nombre: item.get("nombre")?.as_s().ok()?.clone(),
+            ambito: AmbitoPolitica::parse(item.get("ambito")?.as_s().ok()?)?,
+            vigente_desde: item.get("vigente_desde")?.as_s().ok()?.parse().ok()?,
+            tabla: item
+                .get("tabla")?
+                .as_l()
+                .ok()?
+                .iter()
+                .filter_map(|v| v.as_n().ok()?.parse().ok())
+                .collect(),
+            incremento: numero("incremento")?,
+            cada_anos: numero("cada_anos")?,
+        })
+    }
+}
+
+/// Política de un ámbito vigente en `fecha` (la de `vigente_desde` más reciente)
+pub fn politica_vigente(
+    politicas: &[PoliticaVacaciones],
+    ambito: AmbitoPolitica,
+    fecha: NaiveDate,
+) -> Option<&PoliticaVacaciones> {
+    politicas
+        .iter()
+        .filter(|p| p.ambito == ambito && p.vigente_desde <= fecha)
+        .max_by_key(|p| p.vigente_desde)
+}
+
+/// Días de vacaciones por `anos` años de servicio cumplidos en `fecha`: los de la
+/// ley vigente en esa fecha o, si es mayor, los de la política de la empresa vigente.
+/// `politicas` son las registradas en la base; las de la LFT se agregan siempre.
+pub fn dias_por_antiguedad(anos: i32, fecha: NaiveDate, politicas: &[PoliticaVacaciones]) -> i32 {
+    let mut todas = politicas_ley();
+    todas.extend(politicas.iter().filter(|p| !p.es_de_codigo()).cloned());
+
+    let ley = politica_vigente(&todas, AmbitoPolitica::Ley, fecha)
+        .map(|p| p.dias_para(anos))
+        .unwrap_or(0);
+    let empresa = politica_vigente(&todas, AmbitoPolitica::Empresa, fecha)
+        .map(|p| p.dias_para(anos))
+        .unwrap_or(0);
+
+    ley.max(empresa)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fecha(texto: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(texto, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_tablas_lft() {
+        let antes = fecha("2022-06-01");
+        let despues = fecha("2023-06-01");
+
+        let esperados_1970 = [
+            (1, 6),
+            (2, 8),
+            (4, 12),
+            (5, 14),
+            (9, 14),
+            (10, 16),
+            (14, 16),
+            (15, 18),
+        ];
+        for (anos, dias) in esperados_1970 {
+            assert_eq!(
+                dias_por_antiguedad(anos, antes, &[]),
+                dias,
+                "{} años antes de 2023",
+                anos
+            );
+        }
+
+        let esperados_2023 = [
+            (0, 0),
+            (1, 12),
+            (2, 14),
+            (5, 20),
+            (6, 22),
+            (10, 22),
+            (11, 24),
+            (15, 24),
+            (16, 26),
+            (21, 28),
+            (31, 32),
+        ];
+        for (anos, dias) in esperados_2023 {
+            assert_eq!(
+                dias_por_antiguedad(anos, despues, &[]),
+                dias,
+                "{} años desde 2023",
+                anos
+            );
+        }
+    }
+
+    #[test]
+    fn test_politica_empresa() {
+        let empresa = PoliticaVacaciones {
+            id: "empresa-2024".to_string(),
+            nombre: "Plan mejorado".to_string(),
+            ambito: AmbitoPolitica::Empresa,
+            vigente_desde: fecha("2024-01-01"),
+            tabla: vec![15],
+            incremento: 1,
+            cada_anos: 1,
+        };
+        let politicas = [empresa.clone()];
+
+        // Antes de su vigencia solo aplica la ley
+        assert_eq!(dias_por_antiguedad(1, fecha("2023-12-31"), &politicas), 12);
+        // Mejora la ley en los primeros años; después la ley vuelve a ser mayor
+        assert_eq!(dias_por_antiguedad(1, fecha("2024-06-01"), &politicas), 15);
+        assert_eq!(dias_por_antiguedad(3, fecha("2024-06-01"), &politicas), 17);
+        assert_eq!(dias_por_antiguedad(6, fecha("2024-06-01"), &politicas), 22);
+
+        let item = PoliticaVacaciones::from_item(&empresa.to_item()).unwrap();
+        assert_eq!(item, empresa);
+    }
+}
@@ -0,0 +1,90 @@
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Atributo con la expiración en segundos Unix (tipo `N`), el formato que
+/// espera el TTL de DynamoDB para borrar el item por su cuenta
+pub const ATRIBUTO_TTL: &str = "expires_at";
+
+/// Atributo con el empleado dueño de la sesión; clave del índice disperso
+/// `sesiones-empleado-index` (solo lo tienen las sesiones con login)
+pub const ATRIBUTO_EMPLEADO: &str = "sesion_empleado_id";
+
+/// Sesión persistida por el session store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sesion {
+    pub session_id: String,
+    pub data: String,    // Record de tower-sessions serializado en JSON
+    pub expires_at: i64, // Unix timestamp (segundos)
+    pub created_at: String,
+
+    // Datos del dispositivo, copiados del record al guardar (None antes del login)
+    pub empleado_id: Option<String>,
+    pub ultima_actividad: Option<String>,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl Sesion {
+    pub fn esta_expirada(&self) -> bool {
+        self.expires_at < chrono::Utc::now().timestamp()
+    }
+
+    pub fn to_item(&self) -> HashMap<String, AttributeValue> {
+        let mut item = HashMap::new();
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("SESSION#{}", self.session_id)),
+        );
+        item.insert("SK".to_string(), AttributeValue::S("METADATA".to_string()));
+        item.insert("tipo".to_string(), AttributeValue::S("session".to_string()));
+        item.insert(
+            "session_id".to_string(),
+            AttributeValue::S(self.session_id.clone()),
+        );
+        item.insert("data".to_string(), AttributeValue::S(self.data.clone()));
+        item.insert(
+            ATRIBUTO_TTL.to_string(),
+            AttributeValue::N(self.expires_at.to_string()),
+        );
+        item.insert(
+            "created_at".to_string(),
+            AttributeValue::S(self.created_at.clone()),
+        );
+
+        let opcionales = [
+            (ATRIBUTO_EMPLEADO, &self.empleado_id),
+            ("ultima_actividad", &self.ultima_actividad),
+            ("ip", &self.ip),
+            ("user_agent", &self.user_agent),
+        ];
+        for (nombre, valor) in opcionales {
+            if let Some(valor) = valor {
+                item.insert(nombre.to_string(), AttributeValue::S(valor.clone()));
+            }
+        }
+
+        item
+    }
+
+    pub fn from_item(item: &HashMap<String, AttributeValue>) -> Option<Self> {
+        Some(Sesion {
+            session_id: item.get("session_id")?.as_s().ok()?.clone(),
+            data: item.get("data")?.as_s().ok()?.clone(),
+            expires_at: item.get(ATRIBUTO_TTL)?.as_n().ok()?.parse().ok()?,
+            created_at: item
+                .get("created_at")
+                .and_then(|v| v.as_s().ok())
+                .cloned()
+                .unwrap_or_default(),
+            empleado_id: opcional(item, ATRIBUTO_EMPLEADO),
+            ultima_actividad: opcional(item, "ultima_actividad"),
+            ip: opcional(item, "ip"),
+            user_agent: opcional(item, "user_agent"),
+        })
+    }
+}
+
+fn opcional(item: &HashMap<String, AttributeValue>, nombre: &str) -> Option<String> {
+    item.get(nombre).and_then(|v| v.as_s().ok()).cloned()
+}
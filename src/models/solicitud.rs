@@ -1,41 +1,394 @@
-use serde::{Deserialize, Serialize};
 use aws_sdk_dynamodb::types::AttributeValue;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::cadena::{self, DecisionPaso, PasoAprobacion};
+use crate::models::dias::{Dias, Jornada};
+use crate::models::periodo::ConsumoPeriodo;
+use crate::models::permiso::TipoPermiso;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolicitudVacaciones {
     pub id: String,
     pub empleado_id: String,
+    // Nombre y departamento copiados del empleado al crear la solicitud
     pub empleado_nombre: String,
+    #[serde(default)]
+    pub departamento: String,
+    // Tipo de ausencia (las creadas antes de los tipos son vacaciones) y referencia del
+    // documento que la respalda, p. ej. el folio del certificado de incapacidad
+    #[serde(default)]
+    pub tipo_permiso: TipoPermiso,
+    #[serde(default)]
+    pub documento: Option<String>,
     pub fecha_inicio: String,
     pub fecha_fin: String,
-    pub estado: String,
-    pub dias_solicitados: i32,
+    // Medio día en los extremos o unas horas; `dias_solicitados` ya lo descuenta
+    #[serde(flatten)]
+    pub jornada: Jornada,
+    pub estado: EstadoSolicitud,
+    pub dias_solicitados: Dias,
     pub created_at: String,
+    // Se incrementa en cada cambio de estado (control de concurrencia optimista)
+    #[serde(default)]
+    pub version: i64,
+    // Días descontados de cada periodo de servicio al aprobarla (el más antiguo primero)
+    #[serde(default)]
+    pub consumo: Vec<ConsumoPeriodo>,
+    // ID de la solicitud que esta reprograma (la original queda cancelada)
+    #[serde(default)]
+    pub reprograma: Option<String>,
+    // Quién aprobó o rechazó la solicitud, cuándo y con qué comentario
+    #[serde(default)]
+    pub resuelto_por: Option<String>,
+    #[serde(default)]
+    pub resuelto_at: Option<String>,
+    #[serde(default)]
+    pub comentario: Option<String>,
+    // Pasos de aprobación de la cadena de su departamento al crearla (vacío en las creadas
+    // antes de las cadenas: las aprueba cualquier administrador) y decisión de cada paso
+    #[serde(default)]
+    pub pasos: Vec<PasoAprobacion>,
+    #[serde(default)]
+    pub aprobaciones: Vec<DecisionPaso>,
+    // Transiciones de estado desde que se creó; solo se agregan entradas al final
+    #[serde(default)]
+    pub historial: Vec<CambioEstado>,
+}
+
+/// Solicitud en la bandeja "Por aprobar" de un empleado; `en_nombre_de` es el delegante
+/// cuando le toca decidirla por delegación
+#[derive(Debug, Clone, Serialize)]
+pub struct SolicitudPorAprobar {
+    #[serde(flatten)]
+    pub solicitud: SolicitudVacaciones,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub en_nombre_de: Option<String>,
+}
+
+/// Estado de una solicitud. Las transiciones permitidas están en `puede_pasar_a`:
+/// rechazada, cancelada, revocada y expirada son finales.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EstadoSolicitud {
+    Pendiente,
+    Aprobada,
+    Rechazada,
+    /// Retirada por el propio empleado
+    Cancelada,
+    /// Aprobación retirada por un administrador; los días vuelven al saldo
+    Revocada,
+    /// Pendiente cuya fecha de inicio pasó sin que nadie la resolviera
+    Expirada,
+}
+
+impl EstadoSolicitud {
+    pub const TODOS: [EstadoSolicitud; 6] = [
+        EstadoSolicitud::Pendiente,
+        EstadoSolicitud::Aprobada,
+        EstadoSolicitud::Rechazada,
+        EstadoSolicitud::Cancelada,
+        EstadoSolicitud::Revocada,
+        EstadoSolicitud::Expirada,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EstadoSolicitud::Pendiente => "pendiente",
+            EstadoSolicitud::Aprobada => "aprobada",
+            EstadoSolicitud::Rechazada => "rechazada",
+            EstadoSolicitud::Cancelada => "cancelada",
+            EstadoSolicitud::Revocada => "revocada",
+            EstadoSolicitud::Expirada => "expirada",
+        }
+    }
+
+    /// Si una solicitud en este estado puede pasar a `destino`
+    pub fn puede_pasar_a(self, destino: EstadoSolicitud) -> bool {
+        use EstadoSolicitud::*;
+        matches!(
+            (self, destino),
+            (Pendiente, Aprobada | Rechazada | Cancelada | Expirada)
+                | (Aprobada, Cancelada | Revocada)
+        )
+    }
+
+    /// Si la solicitud sigue ocupando sus fechas (cuenta para los traslapes)
+    pub fn activa(self) -> bool {
+        matches!(self, EstadoSolicitud::Pendiente | EstadoSolicitud::Aprobada)
+    }
 }
 
+impl fmt::Display for EstadoSolicitud {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EstadoSolicitud {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EstadoSolicitud::TODOS
+            .into_iter()
+            .find(|e| e.as_str() == s)
+            .ok_or_else(|| format!("Estado inválido: {}", s))
+    }
+}
+
+/// Actor de los cambios de estado automáticos (p. ej. al expirar)
+pub const ACTOR_SISTEMA: &str = "sistema";
+
+/// Transición registrada en el historial de una solicitud
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CambioEstado {
+    /// `None` en la entrada con la que se crea la solicitud
+    pub anterior: Option<EstadoSolicitud>,
+    pub estado: EstadoSolicitud,
+    /// ID del empleado que hizo el cambio, o `ACTOR_SISTEMA`
+    pub actor: String,
+    pub fecha: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motivo: Option<String>,
+}
+
+/// Cuerpo de POST /api/solicitudes: el resto de los datos del empleado se toman del registro guardado
 #[derive(Debug, Deserialize)]
 pub struct NuevaSolicitud {
     pub empleado_id: String,
-    pub empleado_nombre: String,
+    /// Por omisión, vacaciones
+    #[serde(default)]
+    pub tipo_permiso: TipoPermiso,
+    #[serde(default)]
+    pub documento: Option<String>,
+    pub fecha_inicio: String,
+    pub fecha_fin: String,
+    /// Por omisión, días completos
+    #[serde(flatten)]
+    pub jornada: Jornada,
+}
+
+/// Cuerpo de POST .../aprobar (opcional) y .../rechazar (con comentario obligatorio)
+#[derive(Debug, Default, Deserialize)]
+pub struct Resolucion {
+    #[serde(default)]
+    pub comentario: Option<String>,
+}
+
+/// Cuerpo de POST /api/solicitudes/:empleado_id/:solicitud_id/reprogramar
+#[derive(Debug, Deserialize)]
+pub struct Reprogramacion {
     pub fecha_inicio: String,
     pub fecha_fin: String,
+    #[serde(flatten)]
+    pub jornada: Jornada,
+}
+
+/// Cuerpo de POST /api/solicitudes/:empleado_id/:solicitud_id/revocar
+#[derive(Debug, Deserialize)]
+pub struct Revocacion {
+    pub motivo: String,
+}
+
+/// Criterios para listar solicitudes por páginas
+#[derive(Debug, Clone, Default)]
+pub struct FiltroSolicitudes {
+    pub empleado_id: Option<String>,
+    pub estado: Option<EstadoSolicitud>,
+}
+
+/// Ajuste del saldo de días tomados de un empleado en un periodo de servicio, aplicado
+/// junto con un cambio de estado. `anterior` es el valor leído (`None` si aún no existía).
+/// Una solicitud puede mover el saldo de varios periodos a la vez.
+#[derive(Debug, Clone)]
+pub struct CambioSaldo {
+    pub periodo: i32,
+    pub anterior: Option<Dias>,
+    pub nuevo: Dias,
+}
+
+impl FiltroSolicitudes {
+    pub fn acepta(&self, solicitud: &SolicitudVacaciones) -> bool {
+        self.empleado_id
+            .as_ref()
+            .is_none_or(|id| solicitud.empleado_id == *id)
+            && self.estado.is_none_or(|e| solicitud.estado == e)
+    }
 }
 
 impl SolicitudVacaciones {
+    /// Pasa la solicitud a `estado` y agrega la transición al historial; devuelve el estado
+    /// anterior. Al aprobar o rechazar, `actor` y `motivo` quedan además como `resuelto_por`
+    /// y `comentario`. No comprueba que la transición esté permitida (ver `puede_pasar_a`).
+    pub fn cambiar_estado(
+        &mut self,
+        estado: EstadoSolicitud,
+        actor: &str,
+        motivo: Option<String>,
+    ) -> EstadoSolicitud {
+        let anterior = std::mem::replace(&mut self.estado, estado);
+        let fecha = Utc::now().to_rfc3339();
+        if matches!(
+            estado,
+            EstadoSolicitud::Aprobada | EstadoSolicitud::Rechazada
+        ) {
+            self.resuelto_por = Some(actor.to_string());
+            self.resuelto_at = Some(fecha.clone());
+            self.comentario = motivo.clone();
+        }
+        self.historial.push(CambioEstado {
+            anterior: Some(anterior),
+            estado,
+            actor: actor.to_string(),
+            fecha,
+            motivo,
+        });
+        anterior
+    }
+
+    /// Índice y paso que falta decidir, o `None` si la solicitud ya no está pendiente
+    pub fn paso_actual(&self) -> Option<(usize, PasoAprobacion)> {
+        if self.estado != EstadoSolicitud::Pendiente {
+            return None;
+        }
+        let indice = self.aprobaciones.len();
+        if self.pasos.is_empty() {
+            return (indice == 0).then_some((0, PasoAprobacion::Administrador));
+        }
+        self.pasos.get(indice).map(|paso| (indice, paso.clone()))
+    }
+
+    /// Pasos de aprobación que requiere (al menos uno)
+    pub fn total_pasos(&self) -> usize {
+        self.pasos.len().max(1)
+    }
+
+    /// Si la aprobación del paso actual es la última que falta
+    pub fn es_ultimo_paso(&self) -> bool {
+        self.aprobaciones.len() + 1 >= self.total_pasos()
+    }
+
+    /// Delegante en cuyo nombre se tomó la decisión que resolvió la solicitud, si la hubo
+    pub fn resuelta_en_nombre_de(&self) -> Option<&String> {
+        self.resuelto_por.as_ref()?;
+        self.aprobaciones.last()?.en_nombre_de.as_ref()
+    }
+
     pub fn to_item(&self) -> HashMap<String, AttributeValue> {
         let mut item = HashMap::new();
-        item.insert("PK".to_string(), AttributeValue::S(format!("EMPLEADO#{}", self.empleado_id)));
-        item.insert("SK".to_string(), AttributeValue::S(format!("SOLICITUD#{}", self.id)));
+        item.insert(
+            "PK".to_string(),
+            AttributeValue::S(format!("EMPLEADO#{}", self.empleado_id)),
+        );
+        item.insert(
+            "SK".to_string(),
+            AttributeValue::S(format!("SOLICITUD#{}", self.id)),
+        );
         item.insert("id".to_string(), AttributeValue::S(self.id.clone()));
-        item.insert("empleado_id".to_string(), AttributeValue::S(self.empleado_id.clone()));
-        item.insert("empleado_nombre".to_string(), AttributeValue::S(self.empleado_nombre.clone()));
-        item.insert("fecha_inicio".to_string(), AttributeValue::S(self.fecha_inicio.clone()));
-        item.insert("fecha_fin".to_string(), AttributeValue::S(self.fecha_fin.clone()));
-        item.insert("estado".to_string(), AttributeValue::S(self.estado.clone()));
-        item.insert("dias_solicitados".to_string(), AttributeValue::N(self.dias_solicitados.to_string()));
-        item.insert("created_at".to_string(), AttributeValue::S(self.created_at.clone()));
-        item.insert("tipo".to_string(), AttributeValue::S("solicitud".to_string()));
+        item.insert(
+            "empleado_id".to_string(),
+            AttributeValue::S(self.empleado_id.clone()),
+        );
+        item.insert(
+            "empleado_nombre".to_string(),
+            AttributeValue::S(self.empleado_nombre.clone()),
+        );
+        item.insert(
+            "departamento".to_string(),
+            AttributeValue::S(self.departamento.clone()),
+        );
+        // "tipo" ya es el tipo de item del índice INDICE_TIPO
+        item.insert(
+            "tipo_permiso".to_string(),
+            AttributeValue::S(self.tipo_permiso.to_string()),
+        );
+        item.insert(
+            "fecha_inicio".to_string(),
+            AttributeValue::S(self.fecha_inicio.clone()),
+        );
+        item.insert(
+            "fecha_fin".to_string(),
+            AttributeValue::S(self.fecha_fin.clone()),
+        );
+        for (nombre, medio_dia) in [
+            ("medio_dia_inicio", self.jornada.medio_dia_inicio),
+            ("medio_dia_fin", self.jornada.medio_dia_fin),
+        ] {
+            if let Some(medio_dia) = medio_dia {
+                item.insert(
+                    nombre.to_string(),
+                    AttributeValue::S(medio_dia.as_str().to_string()),
+                );
+            }
+        }
+        if let Some(horas) = self.jornada.horas {
+            item.insert("horas".to_string(), AttributeValue::N(horas.to_string()));
+        }
+        item.insert(
+            "estado".to_string(),
+            AttributeValue::S(self.estado.to_string()),
+        );
+        item.insert(
+            "dias_solicitados".to_string(),
+            AttributeValue::N(self.dias_solicitados.to_string()),
+        );
+        item.insert(
+            "created_at".to_string(),
+            AttributeValue::S(self.created_at.clone()),
+        );
+        item.insert(
+            "version".to_string(),
+            AttributeValue::N(self.version.to_string()),
+        );
+        if !self.consumo.is_empty() {
+            item.insert(
+                "consumo".to_string(),
+                AttributeValue::L(
+                    self.consumo
+                        .iter()
+                        .map(|c| {
+                            AttributeValue::M(HashMap::from([
+                                (
+                                    "periodo".to_string(),
+                                    AttributeValue::N(c.periodo.to_string()),
+                                ),
+                                ("dias".to_string(), AttributeValue::N(c.dias.to_string())),
+                            ]))
+                        })
+                        .collect(),
+                ),
+            );
+        }
+        for (nombre, valor) in [
+            ("reprograma", &self.reprograma),
+            ("resuelto_por", &self.resuelto_por),
+            ("resuelto_at", &self.resuelto_at),
+            ("comentario", &self.comentario),
+            ("documento", &self.documento),
+        ] {
+            if let Some(valor) = valor {
+                item.insert(nombre.to_string(), AttributeValue::S(valor.clone()));
+            }
+        }
+        if !self.pasos.is_empty() {
+            item.insert("pasos".to_string(), cadena::pasos_to_attr(&self.pasos));
+        }
+        if !self.aprobaciones.is_empty() {
+            item.insert(
+                "aprobaciones".to_string(),
+                cadena::decisiones_to_attr(&self.aprobaciones),
+            );
+        }
+        if !self.historial.is_empty() {
+            item.insert("historial".to_string(), historial_to_attr(&self.historial));
+        }
+        item.insert(
+            "tipo".to_string(),
+            AttributeValue::S("solicitud".to_string()),
+        );
         item
     }
 
@@ -44,11 +397,125 @@ impl SolicitudVacaciones {
             id: item.get("id")?.as_s().ok()?.clone(),
             empleado_id: item.get("empleado_id")?.as_s().ok()?.clone(),
             empleado_nombre: item.get("empleado_nombre")?.as_s().ok()?.clone(),
+            departamento: item
+                .get("departamento")
+                .and_then(|v| v.as_s().ok())
+                .cloned()
+                .unwrap_or_default(),
+            tipo_permiso: match item.get("tipo_permiso") {
+                Some(v) => v.as_s().ok()?.parse().ok()?,
+                None => TipoPermiso::Vacaciones,
+            },
+            documento: item.get("documento").and_then(|v| v.as_s().ok()).cloned(),
             fecha_inicio: item.get("fecha_inicio")?.as_s().ok()?.clone(),
             fecha_fin: item.get("fecha_fin")?.as_s().ok()?.clone(),
-            estado: item.get("estado")?.as_s().ok()?.clone(),
+            jornada: Jornada {
+                medio_dia_inicio: item
+                    .get("medio_dia_inicio")
+                    .and_then(|v| v.as_s().ok())
+                    .and_then(|v| v.parse().ok()),
+                medio_dia_fin: item
+                    .get("medio_dia_fin")
+                    .and_then(|v| v.as_s().ok())
+                    .and_then(|v| v.parse().ok()),
+                horas: item
+                    .get("horas")
+                    .and_then(|v| v.as_n().ok())
+                    .and_then(|v| v.parse().ok()),
+            },
+            estado: item.get("estado")?.as_s().ok()?.parse().ok()?,
             dias_solicitados: item.get("dias_solicitados")?.as_n().ok()?.parse().ok()?,
             created_at: item.get("created_at")?.as_s().ok()?.clone(),
+            version: item
+                .get("version")
+                .and_then(|v| v.as_n().ok())
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0),
+            consumo: item
+                .get("consumo")
+                .and_then(|v| v.as_l().ok())
+                .map(|lista| {
+                    lista
+                        .iter()
+                        .filter_map(|v| {
+                            let m = v.as_m().ok()?;
+                            Some(ConsumoPeriodo {
+                                periodo: m.get("periodo")?.as_n().ok()?.parse().ok()?,
+                                dias: m.get("dias")?.as_n().ok()?.parse().ok()?,
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            reprograma: item.get("reprograma").and_then(|v| v.as_s().ok()).cloned(),
+            resuelto_por: item
+                .get("resuelto_por")
+                .and_then(|v| v.as_s().ok())
+                .cloned(),
+            resuelto_at: item.get("resuelto_at").and_then(|v| v.as_s().ok()).cloned(),
+            comentario: item.get("comentario").and_then(|v| v.as_s().ok()).cloned(),
+            pasos: item
+                .get("pasos")
+                .and_then(cadena::pasos_from_attr)
+                .unwrap_or_default(),
+            aprobaciones: item
+                .get("aprobaciones")
+                .and_then(|v| v.as_l().ok())
+                .map(|lista| {
+                    lista
+                        .iter()
+                        .filter_map(cadena::decision_from_attr)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            historial: item
+                .get("historial")
+                .and_then(|v| v.as_l().ok())
+                .map(|lista| lista.iter().filter_map(cambio_from_attr).collect())
+                .unwrap_or_default(),
         })
     }
-}
\ No newline at end of file
+}
+
+/// Historial como lista de mapas de DynamoDB
+fn historial_to_attr(historial: &[CambioEstado]) -> AttributeValue {
+    AttributeValue::L(
+        historial
+            .iter()
+            .map(|c| {
+                let mut m = HashMap::from([
+                    (
+                        "estado".to_string(),
+                        AttributeValue::S(c.estado.to_string()),
+                    ),
+                    ("actor".to_string(), AttributeValue::S(c.actor.clone())),
+                    ("fecha".to_string(), AttributeValue::S(c.fecha.clone())),
+                ]);
+                if let Some(anterior) = c.anterior {
+                    m.insert(
+                        "anterior".to_string(),
+                        AttributeValue::S(anterior.to_string()),
+                    );
+                }
+                if let Some(motivo) = &c.motivo {
+                    m.insert("motivo".to_string(), AttributeValue::S(motivo.clone()));
+                }
+                AttributeValue::M(m)
+            })
+            .collect(),
+    )
+}
+
+fn cambio_from_attr(valor: &AttributeValue) -> Option<CambioEstado> {
+    let m = valor.as_m().ok()?;
+    Some(CambioEstado {
+        anterior: match m.get("anterior") {
+            Some(v) => Some(v.as_s().ok()?.parse().ok()?),
+            None => None,
+        },
+        estado: m.get("estado")?.as_s().ok()?.parse().ok()?,
+        actor: m.get("actor")?.as_s().ok()?.clone(),
+        fecha: m.get("fecha")?.as_s().ok()?.clone(),
+        motivo: m.get("motivo").and_then(|v| v.as_s().ok()).cloned(),
+    })
+}
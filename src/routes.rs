@@ -1,9 +1,12 @@
-use crate::db::DynamoDBClient;
+use crate::db::Database;
 use crate::handlers;
-use axum::{Router, routing::{get, post}};
+use axum::{
+    Router,
+    routing::{delete, get, post, put},
+};
 use tower_http::services::ServeDir;
 
-pub fn create_router(db_client: DynamoDBClient) -> Router {
+pub fn create_router(db: Database) -> Router {
     // Rutas públicas (sin autenticación)
     let public_routes = Router::new()
         .route("/", get(handlers::home))
@@ -12,18 +15,35 @@ pub fn create_router(db_client: DynamoDBClient) -> Router {
         .route("/login", post(handlers::auth::login_submit))
         .route("/empleados", get(handlers::empleado::listar_empleados))
         .route("/empleados/{id}", get(handlers::empleado::obtener_empleado))
-        .route("/api/empleados", get(handlers::empleado::listar_empleados_json))
-        .route("/api/empleados/{id}", get(handlers::empleado::obtener_empleado_json));
+        .route(
+            "/api/empleados",
+            get(handlers::empleado::listar_empleados_json),
+        )
+        .route(
+            "/api/empleados/{id}",
+            get(handlers::empleado::obtener_empleado_json),
+        );
 
     // Rutas autenticadas (requieren AuthUser)
     let auth_routes = Router::new()
         .route("/solicitudes", get(handlers::solicitud::listar_solicitudes))
-        .route("/solicitudes/nueva", get(handlers::solicitud::nueva_solicitud_form))
-        .route("/api/solicitudes", post(handlers::solicitud::crear_solicitud))
-        .route("/logout", post(handlers::auth::logout));
-
-    // Rutas admin (requieren AdminUser)
-    let admin_routes = Router::new()
+        .route(
+            "/solicitudes/nueva",
+            get(handlers::solicitud::nueva_solicitud_form),
+        )
+        .route(
+            "/solicitudes/por-aprobar",
+            get(handlers::solicitud::por_aprobar),
+        )
+        .route(
+            "/api/solicitudes",
+            get(handlers::solicitud::listar_solicitudes_json)
+                .post(handlers::solicitud::crear_solicitud),
+        )
+        .route(
+            "/api/solicitudes/por-aprobar",
+            get(handlers::solicitud::listar_por_aprobar_json),
+        )
         .route(
             "/api/solicitudes/{empleado_id}/{solicitud_id}/aprobar",
             post(handlers::solicitud::aprobar_solicitud),
@@ -31,6 +51,116 @@ pub fn create_router(db_client: DynamoDBClient) -> Router {
         .route(
             "/api/solicitudes/{empleado_id}/{solicitud_id}/rechazar",
             post(handlers::solicitud::rechazar_solicitud),
+        )
+        .route(
+            "/api/solicitudes/{empleado_id}/{solicitud_id}/cancelar",
+            post(handlers::solicitud::cancelar_solicitud),
+        )
+        .route(
+            "/api/solicitudes/{empleado_id}/{solicitud_id}/reprogramar",
+            post(handlers::solicitud::reprogramar_solicitud),
+        )
+        .route(
+            "/api/delegaciones",
+            get(handlers::delegacion::listar_delegaciones_json)
+                .post(handlers::delegacion::crear_delegacion_json),
+        )
+        .route(
+            "/api/delegaciones/{id}",
+            delete(handlers::delegacion::eliminar_delegacion_json),
+        )
+        .route(
+            "/api/empleados/{id}/periodos",
+            get(handlers::empleado::listar_periodos_json),
+        )
+        .route(
+            "/api/empleados/{id}/permisos",
+            get(handlers::empleado::listar_saldos_permisos_json),
+        )
+        .route("/mis-sesiones", get(handlers::sesion::mis_sesiones))
+        .route(
+            "/mis-sesiones/revocar-otras",
+            post(handlers::sesion::revocar_otras_sesiones),
+        )
+        .route(
+            "/mis-sesiones/{session_id}/revocar",
+            post(handlers::sesion::revocar_sesion),
+        )
+        .route("/logout", post(handlers::auth::logout));
+
+    // Rutas admin (requieren AdminUser)
+    let admin_routes = Router::new()
+        .route(
+            "/vencimientos",
+            get(handlers::empleado::listar_vencimientos),
+        )
+        .route(
+            "/api/vencimientos",
+            get(handlers::empleado::listar_vencimientos_json),
+        )
+        .route(
+            "/calendarios",
+            get(handlers::calendario::listar_calendarios),
+        )
+        .route(
+            "/calendarios/{id}",
+            get(handlers::calendario::obtener_calendario),
+        )
+        .route(
+            "/api/calendarios",
+            get(handlers::calendario::listar_calendarios_json)
+                .post(handlers::calendario::crear_calendario_json),
+        )
+        .route(
+            "/api/calendarios/{id}",
+            get(handlers::calendario::obtener_calendario_json)
+                .put(handlers::calendario::actualizar_calendario_json)
+                .delete(handlers::calendario::eliminar_calendario_json),
+        )
+        .route(
+            "/api/politicas",
+            get(handlers::politica::listar_politicas_json)
+                .post(handlers::politica::crear_politica_json),
+        )
+        .route(
+            "/api/politicas/{id}",
+            put(handlers::politica::actualizar_politica_json)
+                .delete(handlers::politica::eliminar_politica_json),
+        )
+        .route(
+            "/api/horarios",
+            get(handlers::horario::listar_horarios_json)
+                .post(handlers::horario::crear_horario_json),
+        )
+        .route(
+            "/api/horarios/{id}",
+            put(handlers::horario::actualizar_horario_json)
+                .delete(handlers::horario::eliminar_horario_json),
+        )
+        .route(
+            "/api/empleados",
+            post(handlers::empleado::crear_empleado_json),
+        )
+        .route(
+            "/api/empleados/{id}",
+            put(handlers::empleado::actualizar_empleado_json),
+        )
+        .route(
+            "/api/empleados/{id}/sesiones/revocar",
+            post(handlers::sesion::revocar_sesiones_empleado),
+        )
+        .route(
+            "/api/cadenas",
+            get(handlers::cadena::listar_cadenas_json).post(handlers::cadena::crear_cadena_json),
+        )
+        .route(
+            "/api/cadenas/{id}",
+            put(handlers::cadena::actualizar_cadena_json)
+                .delete(handlers::cadena::eliminar_cadena_json),
+        )
+        .route(
+            "/api/solicitudes/{empleado_id}/{solicitud_id}/revocar",
+            post(handlers::solicitud::revocar_solicitud),
         );
 
     Router::new()
@@ -38,5 +168,5 @@ pub fn create_router(db_client: DynamoDBClient) -> Router {
         .merge(auth_routes)
         .merge(admin_routes)
         .nest_service("/static", ServeDir::new("static"))
-        .with_state(db_client)
+        .with_state(db)
 }
@@ -0,0 +1,361 @@
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::cadena::pasos_para;
+use crate::models::{CadenaAprobacion, DatosCadena, PasoAprobacion};
+
+pub struct CadenaService {
+    db: Database,
+}
+
+impl CadenaService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn listar(&self) -> AppResult<Vec<CadenaAprobacion>> {
+        self.db.listar_cadenas().await
+    }
+
+    /// Pasos que debe seguir una solicitud nueva de `departamento` (ver `pasos_para`)
+    pub async fn pasos_para(&self, departamento: &str) -> AppResult<Vec<PasoAprobacion>> {
+        Ok(pasos_para(&self.db.listar_cadenas().await?, departamento))
+    }
+
+    pub async fn crear(&self, datos: DatosCadena) -> AppResult<CadenaAprobacion> {
+        let cadena = self.validar(Uuid::new_v4().to_string(), datos).await?;
+        self.db.guardar_cadena(&cadena).await?;
+        Ok(cadena)
+    }
+
+    /// Reemplaza una cadena; las solicitudes ya creadas conservan los pasos que tenían
+    pub async fn actualizar(
+        &self,
+        cadena_id: &str,
+        datos: DatosCadena,
+    ) -> AppResult<CadenaAprobacion> {
+        self.db
+            .obtener_cadena(cadena_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Cadena {} no encontrada", cadena_id)))?;
+
+        let cadena = self.validar(cadena_id.to_string(), datos).await?;
+        self.db.guardar_cadena(&cadena).await?;
+        Ok(cadena)
+    }
+
+    pub async fn eliminar(&self, cadena_id: &str) -> AppResult<()> {
+        self.db.eliminar_cadena(cadena_id).await
+    }
+
+    /// Una cadena válida tiene nombre y al menos un paso, sus pasos de empleado apuntan a
+    /// empleados existentes y no hay otra cadena para el mismo departamento (ni otra general)
+    async fn validar(&self, id: String, datos: DatosCadena) -> AppResult<CadenaAprobacion> {
+        let nombre = datos.nombre.trim().to_string();
+        if nombre.is_empty() {
+            return Err(AppError::BadRequest(
+                "El nombre de la cadena es requerido".to_string(),
+            ));
+        }
+        if datos.pasos.is_empty() {
+            return Err(AppError::BadRequest(
+                "La cadena debe tener al menos un paso".to_string(),
+            ));
+        }
+        for paso in &datos.pasos {
+            if let PasoAprobacion::Empleado { empleado_id } = paso
+                && self.db.obtener_empleado(empleado_id).await?.is_none()
+            {
+                return Err(AppError::BadRequest(format!(
+                    "No existe el empleado {} del paso de aprobación",
+                    empleado_id
+                )));
+            }
+        }
+
+        let departamento = datos
+            .departamento
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+        let repetida = self.db.listar_cadenas().await?.into_iter().find(|c| {
+            c.id != id
+                && match (&c.departamento, &departamento) {
+                    (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                    (None, None) => true,
+                    _ => false,
+                }
+        });
+        if let Some(otra) = repetida {
+            return Err(AppError::Conflict(match departamento {
+                Some(d) => format!("El departamento {} ya tiene la cadena {}", d, otra.id),
+                None => format!("La cadena general ya es {}", otra.id),
+            }));
+        }
+
+        Ok(CadenaAprobacion {
+            id,
+            nombre,
+            departamento,
+            pasos: datos.pasos,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use crate::models::{ActualizarEmpleado, Dias, EstadoSolicitud, NuevoEmpleado};
+    use crate::services::solicitud::tests::{nueva, nueva_desde};
+    use crate::services::{EmpleadoService, SolicitudService};
+    use chrono::{Duration, Utc};
+
+    fn datos(departamento: Option<&str>, pasos: Vec<PasoAprobacion>) -> DatosCadena {
+        DatosCadena {
+            nombre: "Jefe y RH".to_string(),
+            departamento: departamento.map(str::to_string),
+            pasos,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cadenas_registradas() {
+        let db = MemoryStore::demo();
+        let service = CadenaService::new(db);
+        let rh = PasoAprobacion::Empleado {
+            empleado_id: "1".to_string(),
+        };
+
+        let ventas = service
+            .crear(datos(
+                Some("Ventas"),
+                vec![PasoAprobacion::JefeDirecto, rh.clone()],
+            ))
+            .await
+            .unwrap();
+        assert_eq!(service.pasos_para("ventas").await.unwrap(), ventas.pasos);
+        assert_eq!(
+            service.pasos_para("Tecnología").await.unwrap(),
+            vec![PasoAprobacion::Administrador]
+        );
+
+        assert!(matches!(
+            service
+                .crear(datos(Some(" ventas "), vec![rh.clone()]))
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            service.crear(datos(None, Vec::new())).await,
+            Err(AppError::BadRequest(_))
+        ));
+        let inexistente = PasoAprobacion::Empleado {
+            empleado_id: "no-existe".to_string(),
+        };
+        assert!(matches!(
+            service.crear(datos(None, vec![inexistente])).await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        // Reemplazar una cadena no choca consigo misma
+        service
+            .actualizar(&ventas.id, datos(Some("Ventas"), vec![rh.clone()]))
+            .await
+            .unwrap();
+        assert_eq!(service.pasos_para("Ventas").await.unwrap(), vec![rh]);
+
+        service.eliminar(&ventas.id).await.unwrap();
+        assert!(service.listar().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cadena_de_aprobacion() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+        let empleados = EmpleadoService::new(db.clone());
+
+        // Laura es jefa de Juan y Rosa aprueba en Recursos Humanos; ninguna es administradora
+        let alta = |id: &str, departamento: &str| NuevoEmpleado {
+            id: Some(id.to_string()),
+            nombre: id.to_string(),
+            departamento: departamento.to_string(),
+            email: format!("{}@ejemplo.com", id),
+            es_admin: false,
+            fecha_ingreso: "2020-01-01".to_string(),
+            ubicacion: None,
+            jefe_id: None,
+            horario_id: None,
+        };
+        let laura = empleados
+            .crear_empleado(alta("laura", "Tecnología"))
+            .await
+            .unwrap();
+        let rosa = empleados
+            .crear_empleado(alta("rosa", "Recursos Humanos"))
+            .await
+            .unwrap();
+        let cambio_jefe = |jefe: &str| ActualizarEmpleado {
+            nombre: None,
+            departamento: None,
+            email: None,
+            es_admin: None,
+            fecha_ingreso: None,
+            ubicacion: None,
+            jefe_id: Some(jefe.to_string()),
+            horario_id: None,
+        };
+        let juan = empleados
+            .actualizar_empleado("2", cambio_jefe("laura"))
+            .await
+            .unwrap();
+        // Juan ya depende de Laura: no puede ser su jefe
+        assert!(matches!(
+            empleados
+                .actualizar_empleado("laura", cambio_jefe("2"))
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+
+        CadenaService::new(db.clone())
+            .crear(DatosCadena {
+                nombre: "Jefe y RH".to_string(),
+                departamento: Some("Tecnología".to_string()),
+                pasos: vec![
+                    PasoAprobacion::JefeDirecto,
+                    PasoAprobacion::Empleado {
+                        empleado_id: "rosa".to_string(),
+                    },
+                ],
+            })
+            .await
+            .unwrap();
+        let ana = db.obtener_empleado("1").await.unwrap().unwrap();
+
+        let creada = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(2), "2")
+            .await
+            .unwrap();
+        assert_eq!(creada.pasos.len(), 2);
+        assert_eq!(
+            solicitudes
+                .listar_por_aprobar(&laura, &[])
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            solicitudes
+                .listar_por_aprobar(&rosa, &[])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Ni un administrador ni el propio empleado deciden el paso del jefe
+        for decisor in [&ana, &juan, &rosa] {
+            assert!(matches!(
+                solicitudes
+                    .decidir("2", &creada.id, decisor, &[], true, None)
+                    .await,
+                Err(AppError::Forbidden(_))
+            ));
+        }
+
+        let paso_1 = solicitudes
+            .decidir(
+                "2",
+                &creada.id,
+                &laura,
+                &[],
+                true,
+                Some("Por mí bien".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(paso_1.estado, EstadoSolicitud::Pendiente);
+        assert_eq!(paso_1.aprobaciones.len(), 1);
+        assert!(paso_1.resuelto_por.is_none());
+        assert!(
+            solicitudes
+                .listar_por_aprobar(&laura, &[])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            solicitudes
+                .listar_por_aprobar(&rosa, &[])
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let aprobada = solicitudes
+            .decidir("2", &creada.id, &rosa, &[], true, None)
+            .await
+            .unwrap();
+        assert_eq!(aprobada.estado, EstadoSolicitud::Aprobada);
+        assert_eq!(aprobada.resuelto_por.as_deref(), Some("rosa"));
+        assert_eq!(
+            aprobada
+                .aprobaciones
+                .iter()
+                .map(|d| (d.paso, d.aprobador.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(0, "laura"), (1, "rosa")]
+        );
+        assert!(matches!(
+            solicitudes
+                .decidir("2", &creada.id, &rosa, &[], true, None)
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+
+        // Un rechazo en cualquier paso rechaza la solicitud
+        let otra = solicitudes
+            .crear_solicitud(
+                &juan,
+                nueva_desde("2", Utc::now().date_naive() + Duration::days(30)),
+                Dias::enteros(1),
+                "2",
+            )
+            .await
+            .unwrap();
+        let rechazada = solicitudes
+            .decidir(
+                "2",
+                &otra.id,
+                &laura,
+                &[],
+                false,
+                Some("Cierre de proyecto".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rechazada.estado, EstadoSolicitud::Rechazada);
+        assert_eq!(rechazada.resuelto_por.as_deref(), Some("laura"));
+        assert!(!rechazada.aprobaciones[0].aprobada);
+
+        // Sin cadena para su departamento, la aprueba cualquier administrador
+        let propia = solicitudes
+            .crear_solicitud(&rosa, nueva("rosa"), Dias::enteros(1), "rosa")
+            .await
+            .unwrap();
+        assert!(
+            solicitudes
+                .listar_por_aprobar(&ana, &[])
+                .await
+                .unwrap()
+                .iter()
+                .any(|s| s.solicitud.id == propia.id)
+        );
+        let aprobada = solicitudes
+            .decidir("rosa", &propia.id, &ana, &[], true, None)
+            .await
+            .unwrap();
+        assert_eq!(aprobada.estado, EstadoSolicitud::Aprobada);
+    }
+}
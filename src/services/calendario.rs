@@ -0,0 +1,396 @@
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::{AppError, AppResult, ErrorCampo};
+use crate::models::calendario::{self, ConteoDias};
+use crate::models::dias::validar_rango;
+use crate::models::horario::horario_de;
+use crate::models::permiso::DiasQueCuentan;
+use crate::models::{CalendarioEmpresa, DatosCalendario, Dias, Empleado, Jornada, TipoPermiso};
+
+pub struct CalendarioService {
+    db: Database,
+}
+
+impl CalendarioService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn listar(&self) -> AppResult<Vec<CalendarioEmpresa>> {
+        self.db.listar_calendarios().await
+    }
+
+    pub async fn obtener(&self, calendario_id: &str) -> AppResult<CalendarioEmpresa> {
+        self.db
+            .obtener_calendario(calendario_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Calendario {} no encontrado", calendario_id))
+            })
+    }
+
+    pub async fn crear(&self, datos: DatosCalendario) -> AppResult<CalendarioEmpresa> {
+        let calendario = validar_calendario(Uuid::new_v4().to_string(), datos)?;
+        self.db.guardar_calendario(&calendario).await?;
+        Ok(calendario)
+    }
+
+    /// Reemplaza nombre, alcance y días de un calendario existente
+    pub async fn actualizar(
+        &self,
+        calendario_id: &str,
+        datos: DatosCalendario,
+    ) -> AppResult<CalendarioEmpresa> {
+        self.obtener(calendario_id).await?;
+
+        let calendario = validar_calendario(calendario_id.to_string(), datos)?;
+        self.db.guardar_calendario(&calendario).await?;
+        Ok(calendario)
+    }
+
+    pub async fn eliminar(&self, calendario_id: &str) -> AppResult<()> {
+        self.db.eliminar_calendario(calendario_id).await
+    }
+
+    /// Calendarios que aplican al empleado por departamento, ubicación o por ser generales
+    pub async fn calendarios_de(&self, empleado: &Empleado) -> AppResult<Vec<CalendarioEmpresa>> {
+        let mut calendarios = self.db.listar_calendarios().await?;
+        calendarios.retain(|c| c.aplica_a(empleado));
+        Ok(calendarios)
+    }
+
+    /// Días laborables entre dos fechas YYYY-MM-DD para el empleado: los que trabaja
+    /// según su horario, sin contar feriados oficiales ni los días inhábiles de sus
+    /// calendarios
+    pub async fn contar_dias(
+        &self,
+        empleado: &Empleado,
+        inicio: &str,
+        fin: &str,
+    ) -> AppResult<ConteoDias> {
+        let (fecha_inicio, fecha_fin) = validar_rango(inicio, fin).map_err(AppError::Validation)?;
+
+        let calendarios = self.calendarios_de(empleado).await?;
+        let horario = horario_de(&self.db.listar_horarios().await?, empleado);
+        Ok(calendario::contar_dias_laborables(
+            fecha_inicio,
+            fecha_fin,
+            &calendarios,
+            &horario,
+        ))
+    }
+
+    /// Días que cuentan para una solicitud de `tipo`: laborables (como `contar_dias`) o
+    /// naturales, según sus reglas
+    pub async fn contar_dias_permiso(
+        &self,
+        empleado: &Empleado,
+        tipo: TipoPermiso,
+        inicio: &str,
+        fin: &str,
+    ) -> AppResult<ConteoDias> {
+        match tipo.reglas().dias {
+            DiasQueCuentan::Laborables => self.contar_dias(empleado, inicio, fin).await,
+            DiasQueCuentan::Naturales => {
+                let (fecha_inicio, fecha_fin) =
+                    validar_rango(inicio, fin).map_err(AppError::Validation)?;
+                Ok(calendario::contar_dias_naturales(fecha_inicio, fecha_fin))
+            }
+        }
+    }
+
+    /// Días que cuesta una solicitud de `tipo`: los que cuentan (`contar_dias_permiso`)
+    /// menos los medios días de su `jornada`, o solo sus horas. El rango y la jornada se
+    /// validan antes de contar.
+    pub async fn contar_dias_solicitud(
+        &self,
+        empleado: &Empleado,
+        tipo: TipoPermiso,
+        inicio: &str,
+        fin: &str,
+        jornada: &Jornada,
+    ) -> AppResult<(Dias, ConteoDias)> {
+        let (fecha_inicio, fecha_fin) = validar_rango(inicio, fin).map_err(AppError::Validation)?;
+        let errores = jornada.validar(fecha_inicio, fecha_fin);
+        if !errores.is_empty() {
+            return Err(AppError::Validation(errores));
+        }
+
+        let conteo = self
+            .contar_dias_permiso(empleado, tipo, inicio, fin)
+            .await?;
+        let cierres: Vec<_> = conteo
+            .medios_dias
+            .iter()
+            .filter_map(|dia| Some((dia.fecha, dia.medio_dia?)))
+            .collect();
+        let dias = jornada
+            .dias(fecha_inicio, fecha_fin, &conteo.fechas, &cierres)
+            .ok_or_else(|| {
+                AppError::Validation(vec![ErrorCampo::new(
+                    "dias_solicitados",
+                    "La cantidad de días está fuera de rango",
+                )])
+            })?;
+        Ok((dias, conteo))
+    }
+}
+
+/// Normaliza los datos recibidos: recorta textos, descarta entradas vacías y
+/// rechaza calendarios sin nombre, con fechas repetidas o con medias jornadas en un
+/// feriado oficial (que ya es de descanso completo)
+fn validar_calendario(id: String, datos: DatosCalendario) -> AppResult<CalendarioEmpresa> {
+    let nombre = datos.nombre.trim().to_string();
+    if nombre.is_empty() {
+        return Err(AppError::BadRequest(
+            "El nombre del calendario es requerido".to_string(),
+        ));
+    }
+
+    let limpiar = |valores: Vec<String>| -> Vec<String> {
+        valores
+            .into_iter()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .collect()
+    };
+
+    let mut fechas = HashSet::new();
+    let mut dias = Vec::with_capacity(datos.dias.len());
+    for mut dia in datos.dias {
+        if !fechas.insert(dia.fecha) {
+            return Err(AppError::BadRequest(format!(
+                "La fecha {} está repetida",
+                dia.fecha
+            )));
+        }
+        dia.nombre = dia.nombre.trim().to_string();
+        if dia.nombre.is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "El día {} necesita un nombre",
+                dia.fecha
+            )));
+        }
+        if dia.medio_dia.is_some() && !calendario::feriados_entre(dia.fecha, dia.fecha).is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "El {} es feriado oficial: no puede cerrar solo media jornada",
+                dia.fecha
+            )));
+        }
+        dias.push(dia);
+    }
+    dias.sort_by_key(|d| d.fecha);
+
+    Ok(CalendarioEmpresa {
+        id,
+        nombre,
+        departamentos: limpiar(datos.departamentos),
+        ubicaciones: limpiar(datos.ubicaciones),
+        dias,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use crate::models::{DatosHorario, Feriado, MedioDia, PatronHorario};
+    use crate::services::HorarioService;
+    use chrono::NaiveDate;
+
+    fn fecha(texto: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(texto, "%Y-%m-%d").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_contar_dias_con_calendario_por_ubicacion() {
+        let db = MemoryStore::demo();
+        let service = CalendarioService::new(db.clone());
+
+        service
+            .crear(DatosCalendario {
+                nombre: " Feriados Monterrey ".to_string(),
+                departamentos: vec![],
+                ubicaciones: vec!["monterrey".to_string(), " ".to_string()],
+                dias: vec![
+                    Feriado {
+                        fecha: fecha("2025-12-12"),
+                        nombre: "Día de la Virgen de Guadalupe".to_string(),
+                        medio_dia: None,
+                    },
+                    Feriado {
+                        fecha: fecha("2025-09-16"),
+                        nombre: "Independencia".to_string(),
+                        medio_dia: None,
+                    },
+                    Feriado {
+                        fecha: fecha("2025-12-24"),
+                        nombre: "Nochebuena".to_string(),
+                        medio_dia: Some(MedioDia::Tarde),
+                    },
+                ],
+            })
+            .await
+            .unwrap();
+
+        let calendarios = service.listar().await.unwrap();
+        assert_eq!(calendarios[0].nombre, "Feriados Monterrey");
+        assert_eq!(calendarios[0].ubicaciones, vec!["monterrey".to_string()]);
+        assert_eq!(calendarios[0].dias[0].fecha, fecha("2025-09-16"));
+
+        // Juan (Monterrey) descansa el 12 de diciembre; Ana (CDMX) no
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+        let conteo = service
+            .contar_dias(&juan, "2025-12-08", "2025-12-12")
+            .await
+            .unwrap();
+        assert_eq!(conteo.dias_laborables, Dias::enteros(4));
+        assert_eq!(conteo.feriados_excluidos.len(), 1);
+
+        let ana = db.obtener_empleado("1").await.unwrap().unwrap();
+        let conteo = service
+            .contar_dias(&ana, "2025-12-08", "2025-12-12")
+            .await
+            .unwrap();
+        assert_eq!(conteo.dias_laborables, Dias::enteros(5));
+
+        // Un día que ya es feriado oficial no se descuenta dos veces
+        let conteo = service
+            .contar_dias(&juan, "2025-09-15", "2025-09-19")
+            .await
+            .unwrap();
+        assert_eq!(conteo.dias_laborables, Dias::enteros(4));
+        assert_eq!(conteo.feriados_excluidos.len(), 1);
+
+        // La tarde del 24 está cerrada: del lunes 22 al martes 23 cuestan 1.5 días, y
+        // tomar solo esa tarde no cuesta nada
+        let (dias, conteo) = service
+            .contar_dias_solicitud(
+                &juan,
+                TipoPermiso::Vacaciones,
+                "2025-12-22",
+                "2025-12-24",
+                &Jornada::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(dias, Dias::desde_milesimos(2500));
+        assert_eq!(conteo.dias_laborables, dias);
+        assert_eq!(conteo.medios_dias.len(), 1);
+        let tarde = Jornada {
+            medio_dia_inicio: Some(MedioDia::Tarde),
+            ..Jornada::default()
+        };
+        let (dias, _) = service
+            .contar_dias_solicitud(
+                &juan,
+                TipoPermiso::Vacaciones,
+                "2025-12-24",
+                "2025-12-24",
+                &tarde,
+            )
+            .await
+            .unwrap();
+        assert_eq!(dias, Dias::CERO);
+    }
+
+    #[tokio::test]
+    async fn test_contar_dias_con_horario() {
+        let db = MemoryStore::demo();
+        let service = CalendarioService::new(db.clone());
+        let horarios = HorarioService::new(db.clone());
+
+        let almacen = horarios
+            .crear(DatosHorario {
+                nombre: "Almacén".to_string(),
+                patron: PatronHorario::Semanal {
+                    dias: vec![1, 2, 3, 4, 5, 6],
+                },
+            })
+            .await
+            .unwrap();
+        let turno = horarios
+            .crear(DatosHorario {
+                nombre: "Turno 4x3".to_string(),
+                patron: PatronHorario::Rotativo {
+                    inicio: fecha("2025-12-04"),
+                    trabaja: 4,
+                    descansa: 3,
+                },
+            })
+            .await
+            .unwrap();
+
+        // Del lunes 8 al domingo 14 de diciembre de 2025
+        let mut ana = db.obtener_empleado("1").await.unwrap().unwrap();
+        let conteo = service
+            .contar_dias(&ana, "2025-12-08", "2025-12-14")
+            .await
+            .unwrap();
+        assert_eq!(conteo.dias_laborables, Dias::enteros(5));
+
+        ana.horario_id = Some(almacen.id);
+        let conteo = service
+            .contar_dias(&ana, "2025-12-08", "2025-12-14")
+            .await
+            .unwrap();
+        assert_eq!(conteo.dias_laborables, Dias::enteros(6));
+
+        // El turno trabaja de jueves a domingo
+        ana.horario_id = Some(turno.id);
+        let conteo = service
+            .contar_dias(&ana, "2025-12-08", "2025-12-14")
+            .await
+            .unwrap();
+        assert_eq!(conteo.dias_laborables, Dias::enteros(4));
+        assert_eq!(conteo.fechas.first(), Some(&fecha("2025-12-11")));
+
+        // Un horario que ya no existe vuelve a lunes a viernes
+        ana.horario_id = Some("no-existe".to_string());
+        let conteo = service
+            .contar_dias(&ana, "2025-12-08", "2025-12-14")
+            .await
+            .unwrap();
+        assert_eq!(conteo.dias_laborables, Dias::enteros(5));
+    }
+
+    #[test]
+    fn test_validar_fechas_repetidas() {
+        let dia = Feriado {
+            fecha: fecha("2025-12-12"),
+            nombre: "Guadalupe".to_string(),
+            medio_dia: None,
+        };
+        let datos = DatosCalendario {
+            nombre: "General".to_string(),
+            departamentos: vec![],
+            ubicaciones: vec![],
+            dias: vec![dia.clone(), dia],
+        };
+
+        let resultado = validar_calendario("x".to_string(), datos);
+        assert!(matches!(resultado, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_validar_medio_dia() {
+        let datos = |fecha_dia: &str| DatosCalendario {
+            nombre: "General".to_string(),
+            departamentos: vec![],
+            ubicaciones: vec![],
+            dias: vec![Feriado {
+                fecha: fecha(fecha_dia),
+                nombre: "Cierre".to_string(),
+                medio_dia: Some(MedioDia::Tarde),
+            }],
+        };
+
+        let calendario = validar_calendario("x".to_string(), datos("2025-12-31")).unwrap();
+        assert_eq!(calendario.dias[0].medio_dia, Some(MedioDia::Tarde));
+        // El 25 de diciembre ya es de descanso completo
+        let resultado = validar_calendario("x".to_string(), datos("2025-12-25"));
+        assert!(matches!(resultado, Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::{NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::{Delegacion, Empleado, NuevaDelegacion};
+
+pub struct DelegacionService {
+    db: Database,
+}
+
+impl DelegacionService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Delegaciones que `empleado_id` dio o recibió, de la más antigua a la más reciente
+    pub async fn listar(&self, empleado_id: &str) -> AppResult<Vec<Delegacion>> {
+        let mut delegaciones: Vec<Delegacion> = self
+            .db
+            .listar_delegaciones()
+            .await?
+            .into_iter()
+            .filter(|d| d.delegante_id == empleado_id || d.delegado_id == empleado_id)
+            .collect();
+        delegaciones.sort_by_key(|d| d.desde);
+        Ok(delegaciones)
+    }
+
+    /// IDs de los empleados cuya facultad de aprobación tiene `delegado_id` en `fecha`
+    pub async fn delegantes_vigentes(
+        &self,
+        delegado_id: &str,
+        fecha: NaiveDate,
+    ) -> AppResult<Vec<String>> {
+        Ok(self
+            .db
+            .listar_delegaciones_de_delegado(delegado_id)
+            .await?
+            .into_iter()
+            .filter(|d| d.vigente_al(fecha))
+            .map(|d| d.delegante_id)
+            .collect())
+    }
+
+    /// Registra una delegación de `actor` (o, si es administrador, del `delegante_id` que
+    /// indique). No puede terminar en el pasado ni cruzarse con otra del mismo delegante.
+    pub async fn crear(&self, actor: &Empleado, datos: NuevaDelegacion) -> AppResult<Delegacion> {
+        let delegante_id = match datos.delegante_id.map(|d| d.trim().to_string()) {
+            Some(id) if !id.is_empty() && id != actor.id => {
+                if !actor.es_admin {
+                    return Err(AppError::Forbidden(
+                        "Solo un administrador puede delegar por otro empleado".to_string(),
+                    ));
+                }
+                self.db
+                    .obtener_empleado(&id)
+                    .await?
+                    .ok_or_else(|| AppError::BadRequest(format!("No existe el empleado {}", id)))?;
+                id
+            }
+            _ => actor.id.clone(),
+        };
+
+        let delegado_id = datos.delegado_id.trim().to_string();
+        if delegado_id == delegante_id {
+            return Err(AppError::BadRequest(
+                "Un empleado no puede delegarse a sí mismo".to_string(),
+            ));
+        }
+        self.db
+            .obtener_empleado(&delegado_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::BadRequest(format!("No existe el delegado {}", delegado_id))
+            })?;
+
+        if datos.hasta < datos.desde {
+            return Err(AppError::BadRequest(
+                "La fecha final debe ser posterior o igual a la inicial".to_string(),
+            ));
+        }
+        if datos.hasta < Utc::now().date_naive() {
+            return Err(AppError::BadRequest(
+                "La delegación no puede terminar en el pasado".to_string(),
+            ));
+        }
+
+        let delegacion = Delegacion {
+            id: Uuid::new_v4().to_string(),
+            delegante_id,
+            delegado_id,
+            desde: datos.desde,
+            hasta: datos.hasta,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        let traslape = self
+            .db
+            .listar_delegaciones()
+            .await?
+            .into_iter()
+            .find(|d| d.delegante_id == delegacion.delegante_id && d.se_traslapa(&delegacion));
+        if let Some(otra) = traslape {
+            return Err(AppError::Conflict(format!(
+                "Ya hay una delegación a {} del {} al {}",
+                otra.delegado_id, otra.desde, otra.hasta
+            )));
+        }
+
+        self.db.guardar_delegacion(&delegacion).await?;
+        Ok(delegacion)
+    }
+
+    /// Elimina una delegación; solo su delegante o un administrador
+    pub async fn eliminar(&self, actor: &Empleado, delegacion_id: &str) -> AppResult<()> {
+        let delegacion = self
+            .db
+            .obtener_delegacion(delegacion_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Delegación {} no encontrada", delegacion_id))
+            })?;
+        if delegacion.delegante_id != actor.id && !actor.es_admin {
+            return Err(AppError::Forbidden(
+                "Solo quien delegó puede retirar la delegación".to_string(),
+            ));
+        }
+        self.db.eliminar_delegacion(delegacion_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use crate::models::{DatosCadena, Dias, EstadoSolicitud, NuevoEmpleado, PasoAprobacion};
+    use crate::services::solicitud::tests::nueva;
+    use crate::services::{CadenaService, EmpleadoService, SolicitudService};
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn test_delegacion_de_aprobacion() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+        let delegaciones = DelegacionService::new(db.clone());
+        let hoy = Utc::now().date_naive();
+
+        // Ana (1) es jefa de Juan (2) y se va de vacaciones; Laura decide por ella
+        CadenaService::new(db.clone())
+            .crear(DatosCadena {
+                nombre: "Jefe directo".to_string(),
+                departamento: None,
+                pasos: vec![PasoAprobacion::JefeDirecto],
+            })
+            .await
+            .unwrap();
+        let laura = EmpleadoService::new(db.clone())
+            .crear_empleado(NuevoEmpleado {
+                id: Some("laura".to_string()),
+                nombre: "Laura".to_string(),
+                departamento: "Tecnología".to_string(),
+                email: "laura@ejemplo.com".to_string(),
+                es_admin: false,
+                fecha_ingreso: "2020-01-01".to_string(),
+                ubicacion: None,
+                jefe_id: None,
+                horario_id: None,
+            })
+            .await
+            .unwrap();
+        let ana = db.obtener_empleado("1").await.unwrap().unwrap();
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+        let datos =
+            |delegante: Option<&str>, delegado: &str, desde: NaiveDate, hasta: NaiveDate| {
+                NuevaDelegacion {
+                    delegante_id: delegante.map(str::to_string),
+                    delegado_id: delegado.to_string(),
+                    desde,
+                    hasta,
+                }
+            };
+
+        let delegacion = delegaciones
+            .crear(&ana, datos(None, "laura", hoy, hoy + Duration::days(7)))
+            .await
+            .unwrap();
+        assert_eq!(delegacion.delegante_id, "1");
+        assert!(matches!(
+            delegaciones
+                .crear(
+                    &ana,
+                    datos(None, "2", hoy + Duration::days(7), hoy + Duration::days(9))
+                )
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            delegaciones.crear(&ana, datos(None, "1", hoy, hoy)).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            delegaciones
+                .crear(
+                    &ana,
+                    datos(
+                        None,
+                        "laura",
+                        hoy - Duration::days(9),
+                        hoy - Duration::days(1)
+                    )
+                )
+                .await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            delegaciones
+                .crear(&laura, datos(Some("1"), "2", hoy, hoy))
+                .await,
+            Err(AppError::Forbidden(_))
+        ));
+
+        let delegantes = delegaciones
+            .delegantes_vigentes("laura", hoy)
+            .await
+            .unwrap();
+        assert_eq!(delegantes, vec!["1".to_string()]);
+        assert!(
+            delegaciones
+                .delegantes_vigentes("laura", hoy + Duration::days(8))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let creada = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(2), "2")
+            .await
+            .unwrap();
+        let bandeja = solicitudes
+            .listar_por_aprobar(&laura, &delegantes)
+            .await
+            .unwrap();
+        assert_eq!(bandeja.len(), 1);
+        assert_eq!(bandeja[0].en_nombre_de.as_deref(), Some("1"));
+        assert!(
+            solicitudes
+                .listar_por_aprobar(&laura, &[])
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Sin la delegación no puede, y nadie aprueba por delegación su propia solicitud
+        assert!(matches!(
+            solicitudes
+                .decidir("2", &creada.id, &laura, &[], true, None)
+                .await,
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            solicitudes
+                .decidir("2", &creada.id, &juan, &delegantes, true, None)
+                .await,
+            Err(AppError::Forbidden(_))
+        ));
+
+        let aprobada = solicitudes
+            .decidir("2", &creada.id, &laura, &delegantes, true, None)
+            .await
+            .unwrap();
+        assert_eq!(aprobada.estado, EstadoSolicitud::Aprobada);
+        assert_eq!(aprobada.resuelto_por.as_deref(), Some("laura"));
+        assert_eq!(
+            aprobada.resuelta_en_nombre_de().map(String::as_str),
+            Some("1")
+        );
+
+        // Solo quien delegó (o un administrador) la retira
+        assert_eq!(delegaciones.listar("laura").await.unwrap().len(), 1);
+        assert!(matches!(
+            delegaciones.eliminar(&laura, &delegacion.id).await,
+            Err(AppError::Forbidden(_))
+        ));
+        delegaciones.eliminar(&ana, &delegacion.id).await.unwrap();
+        assert!(delegaciones.listar("1").await.unwrap().is_empty());
+    }
+}
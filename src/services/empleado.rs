@@ -1,123 +1,409 @@
-use crate::db::DynamoDBClient;
+use crate::db::Database;
 use crate::error::{AppError, AppResult};
-use crate::models::{Empleado, SolicitudVacaciones};
-use aws_sdk_dynamodb::types::AttributeValue;
+use crate::models::horario::HORARIO_LUNES_A_VIERNES;
+use crate::models::permiso::saldos_del_ano;
+use crate::models::{
+    ActualizarEmpleado, Dias, DiasPorVencer, Empleado, NuevoEmpleado, Pagina, Paginacion,
+    PeriodoVacaciones, PoliticaVacaciones, SaldoPermiso,
+};
 
-use chrono::Datelike;
+use chrono::{Duration, NaiveDate, Utc};
+use futures::{StreamExt, TryStreamExt, stream};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+/// Máximo de saldos que se reconstruyen (o empleados que se leen) en paralelo
+const CONCURRENCIA_SALDOS: usize = 8;
 
 pub struct EmpleadoService {
-    db: DynamoDBClient,
+    db: Database,
 }
 
 impl EmpleadoService {
-    pub fn new(db: DynamoDBClient) -> Self {
+    pub fn new(db: Database) -> Self {
         Self { db }
     }
 
     /// Obtiene un empleado con sus días calculados
     pub async fn obtener_empleado_con_dias(&self, empleado_id: &str) -> AppResult<Empleado> {
-        // 1. Obtener empleado de la DB
-        let mut empleado = self.obtener_empleado(empleado_id).await?;
-
-        // 2. Calcular días tomados en el año actual
-        let dias_tomados = self.calcular_dias_tomados(empleado_id).await?;
+        let empleado = self.obtener_empleado(empleado_id).await?;
+        let mut empleados = [empleado];
+        self.agregar_dias(&mut empleados).await?;
+        let [empleado] = empleados;
+        Ok(empleado)
+    }
 
-        // 3. Calcular información
-        let antiguedad = empleado.calcular_antiguedad();
-        let _dias_por_ley = empleado.calcular_dias_por_ley();
-        let dias_disponibles = empleado.calcular_dias_disponibles(dias_tomados);
+    /// Periodos de servicio de un empleado, del primero al actual (el más reciente primero),
+    /// con los días aprobados descontados de cada uno
+    pub async fn listar_periodos(&self, empleado_id: &str) -> AppResult<Vec<PeriodoVacaciones>> {
+        let empleado = self.obtener_empleado(empleado_id).await?;
+        let solicitudes = self.db.listar_solicitudes_por_empleado(empleado_id).await?;
+        let politicas = self.db.listar_politicas().await?;
 
-        // 4. Agregar información calculada
-        empleado.dias_tomados = Some(dias_tomados);
-        empleado.dias_disponibles = Some(dias_disponibles);
-        empleado.antiguedad_anos = Some(antiguedad);
+        Ok((1..=empleado.calcular_antiguedad())
+            .rev()
+            .map(|numero| {
+                let dias_tomados = empleado.dias_aprobados_en_periodo(&solicitudes, numero);
+                empleado.periodo(numero, dias_tomados, &politicas)
+            })
+            .collect())
+    }
 
-        Ok(empleado)
+    /// Saldo de cada tipo de permiso del empleado en el año calendario `ano`; el de
+    /// vacaciones son los días disponibles de sus periodos vigentes
+    pub async fn listar_saldos_permisos(
+        &self,
+        empleado_id: &str,
+        ano: i32,
+    ) -> AppResult<Vec<SaldoPermiso>> {
+        let empleado = self.obtener_empleado_con_dias(empleado_id).await?;
+        let solicitudes = self.db.listar_solicitudes_por_empleado(empleado_id).await?;
+        Ok(saldos_del_ano(
+            &solicitudes,
+            ano,
+            empleado.dias_disponibles.unwrap_or_default(),
+        ))
     }
 
     /// Obtiene empleado de la base de datos
     pub async fn obtener_empleado(&self, empleado_id: &str) -> AppResult<Empleado> {
-        let result = self
-            .db
-            .client
-            .get_item()
-            .table_name(&self.db.table_name)
-            .key("PK", AttributeValue::S(format!("EMPLEADO#{}", empleado_id)))
-            .key("SK", AttributeValue::S("METADATA".to_string()))
-            .send()
-            .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+        self.db
+            .obtener_empleado(empleado_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Empleado {} no encontrado", empleado_id)))
+    }
+
+    /// Nombre de cada empleado de `ids` (id → nombre), leídos en paralelo y una vez por id;
+    /// los que no existen se omiten
+    pub async fn nombres(&self, ids: HashSet<String>) -> AppResult<HashMap<String, String>> {
+        Ok(stream::iter(ids)
+            .map(|id| async move { self.db.obtener_empleado(&id).await })
+            .buffer_unordered(CONCURRENCIA_SALDOS)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|e| (e.id, e.nombre))
+            .collect())
+    }
 
-        let item = result
-            .item()
-            .ok_or_else(|| AppError::NotFound(format!("Empleado {} no encontrado", empleado_id)))?;
+    /// Da de alta un empleado; el email debe ser único (sin distinguir mayúsculas)
+    pub async fn crear_empleado(&self, nuevo: NuevoEmpleado) -> AppResult<Empleado> {
+        let empleado = Empleado {
+            id: nuevo.id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            nombre: nuevo.nombre.trim().to_string(),
+            departamento: nuevo.departamento.trim().to_string(),
+            email: Empleado::normalizar_email(&nuevo.email),
+            es_admin: nuevo.es_admin,
+            fecha_ingreso: nuevo.fecha_ingreso,
+            ubicacion: nuevo
+                .ubicacion
+                .map(|u| u.trim().to_string())
+                .filter(|u| !u.is_empty()),
+            jefe_id: nuevo
+                .jefe_id
+                .map(|j| j.trim().to_string())
+                .filter(|j| !j.is_empty()),
+            horario_id: nuevo
+                .horario_id
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty()),
+            password_hash: None,
+            dias_disponibles: None,
+            dias_tomados: None,
+            antiguedad_anos: None,
+            periodo: None,
+        };
+        validar_empleado(&empleado)?;
+        self.validar_jefe(&empleado).await?;
+        self.validar_horario(&empleado).await?;
+
+        if self.db.buscar_por_email(&empleado.email).await?.is_some() {
+            return Err(AppError::Conflict(format!(
+                "El email {} ya está registrado",
+                empleado.email
+            )));
+        }
 
-        Empleado::from_item(item)
-            .ok_or_else(|| AppError::InternalError("Error al parsear empleado".to_string()))
+        self.db.crear_empleado(&empleado).await?;
+        Ok(empleado)
     }
 
-    /// Calcula los días tomados en el año actual
-    async fn calcular_dias_tomados(&self, empleado_id: &str) -> AppResult<i32> {
-        let ano_actual = chrono::Utc::now().year();
+    /// Actualiza datos de un empleado; si cambia el email se vuelve a verificar su unicidad
+    pub async fn actualizar_empleado(
+        &self,
+        empleado_id: &str,
+        cambios: ActualizarEmpleado,
+    ) -> AppResult<Empleado> {
+        let mut empleado = self.obtener_empleado(empleado_id).await?;
+        let email_anterior = empleado.email.clone();
 
-        let result = self
+        if let Some(nombre) = cambios.nombre {
+            empleado.nombre = nombre.trim().to_string();
+        }
+        if let Some(departamento) = cambios.departamento {
+            empleado.departamento = departamento.trim().to_string();
+        }
+        if let Some(email) = cambios.email {
+            empleado.email = Empleado::normalizar_email(&email);
+        }
+        if let Some(es_admin) = cambios.es_admin {
+            empleado.es_admin = es_admin;
+        }
+        if let Some(fecha_ingreso) = cambios.fecha_ingreso {
+            empleado.fecha_ingreso = fecha_ingreso;
+        }
+        if let Some(ubicacion) = cambios.ubicacion {
+            // Una ubicación vacía la quita
+            let ubicacion = ubicacion.trim().to_string();
+            empleado.ubicacion = (!ubicacion.is_empty()).then_some(ubicacion);
+        }
+        if let Some(jefe_id) = cambios.jefe_id {
+            let jefe_id = jefe_id.trim().to_string();
+            empleado.jefe_id = (!jefe_id.is_empty()).then_some(jefe_id);
+        }
+        if let Some(horario_id) = cambios.horario_id {
+            let horario_id = horario_id.trim().to_string();
+            empleado.horario_id = (!horario_id.is_empty()).then_some(horario_id);
+        }
+        validar_empleado(&empleado)?;
+        self.validar_jefe(&empleado).await?;
+        self.validar_horario(&empleado).await?;
+
+        if empleado.email != Empleado::normalizar_email(&email_anterior)
+            && let Some(otro) = self.db.buscar_por_email(&empleado.email).await?
+            && otro.id != empleado.id
+        {
+            return Err(AppError::Conflict(format!(
+                "El email {} ya está registrado",
+                empleado.email
+            )));
+        }
+
+        self.db
+            .actualizar_empleado(&empleado, &email_anterior)
+            .await?;
+        Ok(empleado)
+    }
+
+    /// El jefe debe existir y no puede ser subordinado (directo o indirecto) del propio
+    /// empleado: la cadena de jefes hacia arriba no debe volver a él
+    async fn validar_jefe(&self, empleado: &Empleado) -> AppResult<()> {
+        let Some(jefe_id) = &empleado.jefe_id else {
+            return Ok(());
+        };
+        let mut jefe = self
             .db
-            .client
-            .query()
-            .table_name(&self.db.table_name)
-            .key_condition_expression("PK = :pk AND begins_with(SK, :sk)")
-            .expression_attribute_values(
-                ":pk",
-                AttributeValue::S(format!("EMPLEADO#{}", empleado_id)),
-            )
-            .expression_attribute_values(":sk", AttributeValue::S("SOLICITUD#".to_string()))
-            .send()
-            .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
-        let dias_tomados: i32 = result
-            .items()
-            .iter()
-            .filter_map(|item| SolicitudVacaciones::from_item(item))
-            .filter(|solicitud| {
-                // Solo contar solicitudes aprobadas del año actual
-                solicitud.estado == "aprobada"
-                    && solicitud.fecha_inicio.starts_with(&ano_actual.to_string())
-            })
-            .map(|solicitud| solicitud.dias_solicitados)
-            .sum();
+            .obtener_empleado(jefe_id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest(format!("No existe el jefe {}", jefe_id)))?;
+
+        let mut vistos = HashSet::new();
+        loop {
+            if jefe.id == empleado.id {
+                return Err(AppError::BadRequest(format!(
+                    "El empleado {} no puede depender de sí mismo",
+                    empleado.id
+                )));
+            }
+            let Some(siguiente) = jefe.jefe_id.filter(|id| vistos.insert(id.clone())) else {
+                return Ok(());
+            };
+            match self.db.obtener_empleado(&siguiente).await? {
+                Some(e) => jefe = e,
+                None => return Ok(()),
+            }
+        }
+    }
 
-        Ok(dias_tomados)
+    /// El horario asignado debe existir (el de lunes a viernes vive en el código)
+    async fn validar_horario(&self, empleado: &Empleado) -> AppResult<()> {
+        match &empleado.horario_id {
+            Some(horario_id)
+                if horario_id != HORARIO_LUNES_A_VIERNES
+                    && self.db.obtener_horario(horario_id).await?.is_none() =>
+            {
+                Err(AppError::BadRequest(format!(
+                    "No existe el horario {}",
+                    horario_id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Suma las solicitudes aprobadas del periodo y registra el resultado como saldo,
+    /// para que las siguientes lecturas no tengan que recorrer las solicitudes
+    async fn reconstruir_saldo(&self, empleado: &Empleado, periodo: i32) -> AppResult<Dias> {
+        let solicitudes = self
+            .db
+            .listar_solicitudes_por_empleado(&empleado.id)
+            .await?;
+        let dias_tomados = empleado.dias_aprobados_en_periodo(&solicitudes, periodo);
+
+        self.db
+            .inicializar_saldo(&empleado.id, periodo, dias_tomados)
+            .await?;
+        // Si otra operación registró el saldo antes, ese es el valor vigente
+        Ok(self
+            .db
+            .obtener_saldo(&empleado.id, periodo)
+            .await?
+            .unwrap_or(dias_tomados))
     }
 
     /// Lista todos los empleados con sus días calculados
     pub async fn listar_empleados_con_dias(&self) -> AppResult<Vec<Empleado>> {
-        // Obtener todos los empleados
-        let result = self
-            .db
-            .client
-            .scan()
-            .table_name(&self.db.table_name)
-            .filter_expression("tipo = :tipo")
-            .expression_attribute_values(":tipo", AttributeValue::S("empleado".to_string()))
-            .send()
-            .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
-        let mut empleados: Vec<Empleado> = result
-            .items()
+        let mut empleados = self.db.listar_empleados().await?;
+        self.agregar_dias(&mut empleados).await?;
+        Ok(empleados)
+    }
+
+    /// Lista una página de empleados con sus días calculados
+    pub async fn listar_empleados_con_dias_pagina(
+        &self,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<Empleado>> {
+        let mut pagina = self.db.listar_empleados_pagina(paginacion).await?;
+        self.agregar_dias(&mut pagina.elementos).await?;
+        Ok(pagina)
+    }
+
+    /// Periodos con días sin tomar que vencen en los próximos `dias`, el más próximo primero
+    pub async fn listar_por_vencer(&self, dias: i64) -> AppResult<Vec<DiasPorVencer>> {
+        let hoy = Utc::now().date_naive();
+        let limite = hoy + Duration::days(dias);
+
+        let empleados = self.db.listar_empleados().await?;
+        let saldos = self.leer_saldos(&empleados, hoy).await?;
+        let politicas = self.db.listar_politicas().await?;
+
+        let mut por_vencer: Vec<DiasPorVencer> = empleados
             .iter()
-            .filter_map(|item| Empleado::from_item(item))
+            .flat_map(|empleado| {
+                periodos_vigentes(empleado, &saldos, &politicas, hoy)
+                    .into_iter()
+                    .filter(|p| p.dias_disponibles.es_positivo() && p.vence <= limite)
+                    .map(|periodo| DiasPorVencer {
+                        empleado_id: empleado.id.clone(),
+                        empleado_nombre: empleado.nombre.clone(),
+                        departamento: empleado.departamento.clone(),
+                        periodo,
+                    })
+            })
             .collect();
+        por_vencer.sort_by(|a, b| {
+            (a.periodo.vence, &a.empleado_nombre).cmp(&(b.periodo.vence, &b.empleado_nombre))
+        });
+
+        Ok(por_vencer)
+    }
+
+    /// Completa antigüedad y días de varios empleados leyendo sus saldos en un solo lote.
+    /// `dias_tomados` y `periodo` son los del periodo en curso; `dias_disponibles` suma
+    /// además lo que queda de periodos anteriores que aún no vencen.
+    async fn agregar_dias(&self, empleados: &mut [Empleado]) -> AppResult<()> {
+        let hoy = Utc::now().date_naive();
+        let saldos = self.leer_saldos(empleados, hoy).await?;
+        let politicas = self.db.listar_politicas().await?;
+
+        for empleado in empleados {
+            let actual = empleado.periodo_de(hoy);
+            let dias_tomados = saldos
+                .get(&(empleado.id.clone(), actual))
+                .copied()
+                .unwrap_or_default();
+            let disponibles = periodos_vigentes(empleado, &saldos, &politicas, hoy)
+                .iter()
+                .map(|p| p.dias_disponibles)
+                .sum();
+            let periodo = empleado.periodo(actual, dias_tomados, &politicas);
 
-        // Calcular días para cada empleado
-        for empleado in &mut empleados {
-            let dias_tomados = self.calcular_dias_tomados(&empleado.id).await?;
             empleado.dias_tomados = Some(dias_tomados);
-            empleado.antiguedad_anos = Some(empleado.calcular_antiguedad());
-            empleado.dias_disponibles = Some(empleado.calcular_dias_disponibles(dias_tomados));
+            empleado.antiguedad_anos = Some(actual);
+            empleado.dias_disponibles = Some(disponibles);
+            empleado.periodo = Some(periodo);
         }
 
-        Ok(empleados)
+        Ok(())
+    }
+
+    /// Días tomados del periodo en curso y de los periodos vigentes en `hoy` de cada empleado,
+    /// leídos en un solo lote. Los que no tienen saldo registrado se reconstruyen con
+    /// concurrencia acotada.
+    async fn leer_saldos(
+        &self,
+        empleados: &[Empleado],
+        hoy: NaiveDate,
+    ) -> AppResult<HashMap<(String, i32), Dias>> {
+        let mut claves: Vec<(Empleado, i32)> = Vec::new();
+        for empleado in empleados {
+            let actual = empleado.periodo_de(hoy);
+            let mut numeros = empleado.periodos_vigentes_al(hoy);
+            if !numeros.contains(&actual) {
+                numeros.push(actual);
+            }
+            claves.extend(numeros.into_iter().map(|n| (empleado.clone(), n)));
+        }
+
+        let ids: Vec<(String, i32)> = claves.iter().map(|(e, n)| (e.id.clone(), *n)).collect();
+        let mut saldos = self.db.obtener_saldos(&ids).await?;
+
+        let faltantes: Vec<(Empleado, i32)> = claves
+            .into_iter()
+            .filter(|(e, n)| !saldos.contains_key(&(e.id.clone(), *n)))
+            .collect();
+        let reconstruidos: Vec<((String, i32), Dias)> = stream::iter(faltantes)
+            .map(|(empleado, periodo)| async move {
+                let dias_tomados = self.reconstruir_saldo(&empleado, periodo).await?;
+                Ok::<_, AppError>(((empleado.id, periodo), dias_tomados))
+            })
+            .buffer_unordered(CONCURRENCIA_SALDOS)
+            .try_collect()
+            .await?;
+        saldos.extend(reconstruidos);
+
+        Ok(saldos)
+    }
+}
+
+/// Periodos de un empleado cuyos días aún se pueden disfrutar en `hoy`, el más antiguo primero
+fn periodos_vigentes(
+    empleado: &Empleado,
+    saldos: &HashMap<(String, i32), Dias>,
+    politicas: &[PoliticaVacaciones],
+    hoy: NaiveDate,
+) -> Vec<PeriodoVacaciones> {
+    empleado
+        .periodos_vigentes_al(hoy)
+        .into_iter()
+        .map(|numero| {
+            let dias_tomados = saldos
+                .get(&(empleado.id.clone(), numero))
+                .copied()
+                .unwrap_or_default();
+            empleado.periodo(numero, dias_tomados, politicas)
+        })
+        .collect()
+}
+
+pub(crate) fn validar_empleado(empleado: &Empleado) -> AppResult<()> {
+    if empleado.nombre.is_empty() {
+        return Err(AppError::BadRequest("El nombre es obligatorio".to_string()));
+    }
+    if !empleado.email.contains('@') {
+        return Err(AppError::BadRequest(format!(
+            "Email inválido: {}",
+            empleado.email
+        )));
+    }
+    NaiveDate::parse_from_str(&empleado.fecha_ingreso, "%Y-%m-%d").map_err(|_| {
+        AppError::BadRequest("Formato de fecha_ingreso inválido. Use YYYY-MM-DD".to_string())
+    })?;
+    if empleado.jefe_id.as_deref() == Some(empleado.id.as_str()) {
+        return Err(AppError::BadRequest(
+            "Un empleado no puede ser su propio jefe".to_string(),
+        ));
     }
+    Ok(())
 }
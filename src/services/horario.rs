@@ -0,0 +1,209 @@
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::horario::{HORARIO_LUNES_A_VIERNES, horario_de, horario_por_defecto};
+use crate::models::{DatosHorario, Empleado, HorarioTrabajo, PatronHorario};
+
+/// Máximo de días de trabajo, y de descanso, en el ciclo de un horario rotativo
+const MAX_DIAS_CICLO: u32 = 365;
+
+pub struct HorarioService {
+    db: Database,
+}
+
+impl HorarioService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// El horario de lunes a viernes primero y después los registrados, por nombre
+    pub async fn listar(&self) -> AppResult<Vec<HorarioTrabajo>> {
+        let mut registrados = self.db.listar_horarios().await?;
+        registrados.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        let mut horarios = vec![horario_por_defecto()];
+        horarios.extend(registrados);
+        Ok(horarios)
+    }
+
+    /// Horario que se aplica al empleado (lunes a viernes si no tiene uno)
+    pub async fn horario_de(&self, empleado: &Empleado) -> AppResult<HorarioTrabajo> {
+        Ok(horario_de(&self.db.listar_horarios().await?, empleado))
+    }
+
+    pub async fn crear(&self, datos: DatosHorario) -> AppResult<HorarioTrabajo> {
+        let horario = validar_horario(Uuid::new_v4().to_string(), datos)?;
+        self.db.guardar_horario(&horario).await?;
+        Ok(horario)
+    }
+
+    /// Reemplaza un horario registrado; las solicitudes ya creadas conservan sus días
+    pub async fn actualizar(
+        &self,
+        horario_id: &str,
+        datos: DatosHorario,
+    ) -> AppResult<HorarioTrabajo> {
+        rechazar_horario_de_codigo(horario_id)?;
+        self.db
+            .obtener_horario(horario_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Horario {} no encontrado", horario_id)))?;
+
+        let horario = validar_horario(horario_id.to_string(), datos)?;
+        self.db.guardar_horario(&horario).await?;
+        Ok(horario)
+    }
+
+    /// Elimina un horario que ningún empleado tenga asignado
+    pub async fn eliminar(&self, horario_id: &str) -> AppResult<()> {
+        rechazar_horario_de_codigo(horario_id)?;
+        let asignados = self
+            .db
+            .listar_empleados()
+            .await?
+            .into_iter()
+            .filter(|e| e.horario_id.as_deref() == Some(horario_id))
+            .count();
+        if asignados > 0 {
+            return Err(AppError::Conflict(format!(
+                "El horario {} está asignado a {} empleado(s)",
+                horario_id, asignados
+            )));
+        }
+        self.db.eliminar_horario(horario_id).await
+    }
+}
+
+fn rechazar_horario_de_codigo(horario_id: &str) -> AppResult<()> {
+    if horario_id == HORARIO_LUNES_A_VIERNES {
+        return Err(AppError::BadRequest(format!(
+            "El horario {} está incluido y no se puede modificar",
+            horario_id
+        )));
+    }
+    Ok(())
+}
+
+/// Un horario semanal indica al menos un día entre 1 (lunes) y 7 (domingo); uno rotativo
+/// trabaja al menos un día por ciclo
+fn validar_horario(id: String, datos: DatosHorario) -> AppResult<HorarioTrabajo> {
+    let nombre = datos.nombre.trim().to_string();
+    if nombre.is_empty() {
+        return Err(AppError::BadRequest(
+            "El nombre del horario es requerido".to_string(),
+        ));
+    }
+
+    let patron = match datos.patron {
+        PatronHorario::Semanal { mut dias } => {
+            if dias.is_empty() || dias.iter().any(|d| !(1..=7).contains(d)) {
+                return Err(AppError::BadRequest(
+                    "Los días de un horario semanal van de 1 (lunes) a 7 (domingo)".to_string(),
+                ));
+            }
+            dias.sort_unstable();
+            dias.dedup();
+            PatronHorario::Semanal { dias }
+        }
+        PatronHorario::Rotativo { trabaja: 0, .. } => {
+            return Err(AppError::BadRequest(
+                "Un horario rotativo debe trabajar al menos un día por ciclo".to_string(),
+            ));
+        }
+        PatronHorario::Rotativo {
+            trabaja, descansa, ..
+        } if trabaja > MAX_DIAS_CICLO || descansa > MAX_DIAS_CICLO => {
+            return Err(AppError::BadRequest(format!(
+                "Un horario rotativo no puede trabajar ni descansar más de {} días por ciclo",
+                MAX_DIAS_CICLO
+            )));
+        }
+        rotativo => rotativo,
+    };
+
+    Ok(HorarioTrabajo { id, nombre, patron })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use crate::models::NuevoEmpleado;
+    use crate::services::EmpleadoService;
+    use std::sync::Arc;
+
+    fn semanal(dias: Vec<u32>) -> DatosHorario {
+        DatosHorario {
+            nombre: "Almacén".to_string(),
+            patron: PatronHorario::Semanal { dias },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_horarios_registrados() {
+        let db: Database = Arc::new(MemoryStore::new());
+        let service = HorarioService::new(db.clone());
+
+        let almacen = service
+            .crear(semanal(vec![6, 1, 2, 3, 4, 5, 1]))
+            .await
+            .unwrap();
+        assert_eq!(
+            almacen.patron,
+            PatronHorario::Semanal {
+                dias: vec![1, 2, 3, 4, 5, 6]
+            }
+        );
+        assert_eq!(service.listar().await.unwrap().len(), 2);
+
+        assert!(matches!(
+            service.crear(semanal(vec![0, 8])).await,
+            Err(AppError::BadRequest(_))
+        ));
+        let rotativo = |trabaja, descansa| DatosHorario {
+            nombre: "Guardias".to_string(),
+            patron: PatronHorario::Rotativo {
+                inicio: chrono::NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(),
+                trabaja,
+                descansa,
+            },
+        };
+        for (trabaja, descansa) in [(0, 3), (366, 1), (4, u32::MAX)] {
+            assert!(matches!(
+                service.crear(rotativo(trabaja, descansa)).await,
+                Err(AppError::BadRequest(_))
+            ));
+        }
+        service.crear(rotativo(365, 365)).await.unwrap();
+        assert!(matches!(
+            service.eliminar(HORARIO_LUNES_A_VIERNES).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service.actualizar("no-existe", semanal(vec![1])).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        let empleados = EmpleadoService::new(db);
+        let nuevo = |horario_id: &str| NuevoEmpleado {
+            id: None,
+            nombre: "Pedro Almacén".to_string(),
+            departamento: "Logística".to_string(),
+            email: "pedro@ejemplo.com".to_string(),
+            es_admin: false,
+            fecha_ingreso: "2024-01-15".to_string(),
+            ubicacion: None,
+            jefe_id: None,
+            horario_id: Some(horario_id.to_string()),
+        };
+        assert!(matches!(
+            empleados.crear_empleado(nuevo("no-existe")).await,
+            Err(AppError::BadRequest(_))
+        ));
+        empleados.crear_empleado(nuevo(&almacen.id)).await.unwrap();
+        assert!(matches!(
+            service.eliminar(&almacen.id).await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+}
@@ -1,6 +1,20 @@
 pub mod auth;
+pub mod cadena;
+pub mod calendario;
+pub mod delegacion;
 pub mod empleado;
+pub mod horario;
+pub mod politica;
+pub mod respaldo;
+pub mod sesion;
 pub mod solicitud;
 
+pub use cadena::CadenaService;
+pub use calendario::CalendarioService;
+pub use delegacion::DelegacionService;
 pub use empleado::EmpleadoService;
+pub use horario::HorarioService;
+pub use politica::PoliticaService;
+pub use respaldo::RespaldoService;
+pub use sesion::SesionService;
 pub use solicitud::SolicitudService;
@@ -0,0 +1,146 @@
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::politica::politicas_ley;
+use crate::models::{DatosPolitica, PoliticaVacaciones};
+
+pub struct PoliticaService {
+    db: Database,
+}
+
+impl PoliticaService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Políticas de la LFT y las registradas, de la más antigua a la más reciente
+    pub async fn listar(&self) -> AppResult<Vec<PoliticaVacaciones>> {
+        let mut politicas = politicas_ley();
+        politicas.extend(self.db.listar_politicas().await?);
+        politicas.sort_by_key(|p| p.vigente_desde);
+        Ok(politicas)
+    }
+
+    pub async fn crear(&self, datos: DatosPolitica) -> AppResult<PoliticaVacaciones> {
+        let politica = validar_politica(Uuid::new_v4().to_string(), datos)?;
+        self.db.guardar_politica(&politica).await?;
+        Ok(politica)
+    }
+
+    /// Reemplaza una política registrada; las de la LFT incluidas no se modifican
+    pub async fn actualizar(
+        &self,
+        politica_id: &str,
+        datos: DatosPolitica,
+    ) -> AppResult<PoliticaVacaciones> {
+        rechazar_politica_de_ley(politica_id)?;
+        self.db
+            .obtener_politica(politica_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Política {} no encontrada", politica_id)))?;
+
+        let politica = validar_politica(politica_id.to_string(), datos)?;
+        self.db.guardar_politica(&politica).await?;
+        Ok(politica)
+    }
+
+    pub async fn eliminar(&self, politica_id: &str) -> AppResult<()> {
+        rechazar_politica_de_ley(politica_id)?;
+        self.db.eliminar_politica(politica_id).await
+    }
+}
+
+fn rechazar_politica_de_ley(politica_id: &str) -> AppResult<()> {
+    if politicas_ley().iter().any(|p| p.id == politica_id) {
+        return Err(AppError::BadRequest(format!(
+            "La política {} es de la LFT y no se puede modificar",
+            politica_id
+        )));
+    }
+    Ok(())
+}
+
+/// Una tabla válida tiene al menos un año, días positivos que no disminuyen con la
+/// antigüedad y bloques de al menos un año para los incrementos posteriores
+fn validar_politica(id: String, datos: DatosPolitica) -> AppResult<PoliticaVacaciones> {
+    let nombre = datos.nombre.trim().to_string();
+    if nombre.is_empty() {
+        return Err(AppError::BadRequest(
+            "El nombre de la política es requerido".to_string(),
+        ));
+    }
+    if datos.tabla.is_empty() || datos.tabla.iter().any(|&dias| dias < 1) {
+        return Err(AppError::BadRequest(
+            "La tabla debe indicar días positivos desde el primer año".to_string(),
+        ));
+    }
+    if datos.tabla.windows(2).any(|par| par[1] < par[0]) {
+        return Err(AppError::BadRequest(
+            "Los días de la tabla no pueden disminuir con la antigüedad".to_string(),
+        ));
+    }
+    if datos.incremento < 0 || datos.cada_anos < 1 {
+        return Err(AppError::BadRequest(
+            "incremento debe ser >= 0 y cada_anos >= 1".to_string(),
+        ));
+    }
+
+    Ok(PoliticaVacaciones {
+        id,
+        nombre,
+        ambito: datos.ambito,
+        vigente_desde: datos.vigente_desde,
+        tabla: datos.tabla,
+        incremento: datos.incremento,
+        cada_anos: datos.cada_anos,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use crate::models::AmbitoPolitica;
+    use crate::models::politica::POLITICA_LFT_2023;
+    use chrono::NaiveDate;
+    use std::sync::Arc;
+
+    fn datos(tabla: Vec<i32>) -> DatosPolitica {
+        DatosPolitica {
+            nombre: "Plan mejorado".to_string(),
+            ambito: AmbitoPolitica::Empresa,
+            vigente_desde: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            tabla,
+            incremento: 2,
+            cada_anos: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_politicas_registradas() {
+        let db: Database = Arc::new(MemoryStore::new());
+        let service = PoliticaService::new(db);
+
+        let creada = service.crear(datos(vec![15, 17, 19])).await.unwrap();
+        let politicas = service.listar().await.unwrap();
+        assert_eq!(politicas.len(), 3);
+        assert_eq!(politicas.last().unwrap().id, creada.id);
+
+        assert!(matches!(
+            service.crear(datos(vec![15, 14])).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service.eliminar(POLITICA_LFT_2023).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(matches!(
+            service.actualizar("no-existe", datos(vec![15])).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        service.eliminar(&creada.id).await.unwrap();
+        assert_eq!(service.listar().await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,666 @@
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CadenaAprobacion, CalendarioEmpresa, Delegacion, Empleado, HorarioTrabajo, PoliticaVacaciones,
+    Sesion, SolicitudVacaciones,
+};
+use crate::services::empleado::validar_empleado;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+
+/// Identificador del formato en la cabecera del archivo
+pub const FORMATO_RESPALDO: &str = "vacaciones-respaldo";
+
+/// Versión del formato que escribe y acepta esta versión de la aplicación. Sube cada vez
+/// que `Registro` cambia (tipos o campos nuevos), para que un binario anterior rechace el
+/// archivo por su versión en lugar de fallar en el primer registro que no conoce.
+///
+/// - 1: empleados, solicitudes y sesiones
+/// - 2: calendarios, políticas, cadenas, delegaciones y horarios; tipo de permiso,
+///   jornada, aprobaciones, historial y consumo por periodo en las solicitudes
+pub const VERSION_RESPALDO: u32 = 2;
+
+/// Primera línea del archivo JSONL
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cabecera {
+    pub formato: String,
+    pub version: u32,
+    pub creado_at: String,
+}
+
+/// Datos de un empleado tal como se guardan (incluye `password_hash`, que
+/// `Empleado` omite al serializar, y no incluye los campos calculados)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmpleadoRespaldo {
+    pub id: String,
+    pub nombre: String,
+    pub departamento: String,
+    pub email: String,
+    pub es_admin: bool,
+    pub fecha_ingreso: String,
+    #[serde(default)]
+    pub ubicacion: Option<String>,
+    #[serde(default)]
+    pub jefe_id: Option<String>,
+    #[serde(default)]
+    pub horario_id: Option<String>,
+    pub password_hash: Option<String>,
+}
+
+impl From<Empleado> for EmpleadoRespaldo {
+    fn from(e: Empleado) -> Self {
+        Self {
+            id: e.id,
+            nombre: e.nombre,
+            departamento: e.departamento,
+            email: e.email,
+            es_admin: e.es_admin,
+            fecha_ingreso: e.fecha_ingreso,
+            ubicacion: e.ubicacion,
+            jefe_id: e.jefe_id,
+            horario_id: e.horario_id,
+            password_hash: e.password_hash,
+        }
+    }
+}
+
+impl From<EmpleadoRespaldo> for Empleado {
+    fn from(e: EmpleadoRespaldo) -> Self {
+        Self {
+            id: e.id,
+            nombre: e.nombre,
+            departamento: e.departamento,
+            email: e.email,
+            es_admin: e.es_admin,
+            fecha_ingreso: e.fecha_ingreso,
+            ubicacion: e.ubicacion,
+            jefe_id: e.jefe_id,
+            horario_id: e.horario_id,
+            password_hash: e.password_hash,
+            dias_disponibles: None,
+            dias_tomados: None,
+            antiguedad_anos: None,
+            periodo: None,
+        }
+    }
+}
+
+/// Cada línea después de la cabecera: un item de la tabla, etiquetado por `tipo`
+/// (los mismos valores de `tipo` que usan los items de DynamoDB)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "tipo", rename_all = "lowercase")]
+pub enum Registro {
+    Empleado(EmpleadoRespaldo),
+    Solicitud(Box<SolicitudVacaciones>),
+    Calendario(CalendarioEmpresa),
+    Politica(PoliticaVacaciones),
+    Cadena(CadenaAprobacion),
+    Delegacion(Delegacion),
+    Horario(HorarioTrabajo),
+    #[serde(rename = "session")]
+    Sesion(Sesion),
+}
+
+/// Cuántos registros de cada tipo se exportaron o se restaurarían
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ResumenRespaldo {
+    pub empleados: usize,
+    pub solicitudes: usize,
+    pub calendarios: usize,
+    pub politicas: usize,
+    pub cadenas: usize,
+    pub delegaciones: usize,
+    pub horarios: usize,
+    pub sesiones: usize,
+}
+
+/// Registros de un respaldo agrupados por tipo
+#[derive(Debug, Default)]
+struct Contenido {
+    empleados: Vec<Empleado>,
+    solicitudes: Vec<SolicitudVacaciones>,
+    calendarios: Vec<CalendarioEmpresa>,
+    politicas: Vec<PoliticaVacaciones>,
+    cadenas: Vec<CadenaAprobacion>,
+    delegaciones: Vec<Delegacion>,
+    horarios: Vec<HorarioTrabajo>,
+    sesiones: Vec<Sesion>,
+}
+
+impl Contenido {
+    fn resumen(&self) -> ResumenRespaldo {
+        ResumenRespaldo {
+            empleados: self.empleados.len(),
+            solicitudes: self.solicitudes.len(),
+            calendarios: self.calendarios.len(),
+            politicas: self.politicas.len(),
+            cadenas: self.cadenas.len(),
+            delegaciones: self.delegaciones.len(),
+            horarios: self.horarios.len(),
+            sesiones: self.sesiones.len(),
+        }
+    }
+}
+
+/// Resultado de validar (y, si no hubo problemas, aplicar) un respaldo
+#[derive(Debug, Default)]
+pub struct InformeRestauracion {
+    pub resumen: ResumenRespaldo,
+    /// Problemas del propio archivo (formato, datos inválidos, duplicados)
+    pub errores: Vec<String>,
+    /// Registros que ya existen en el destino
+    pub conflictos: Vec<String>,
+    pub aplicado: bool,
+}
+
+impl InformeRestauracion {
+    pub fn es_valido(&self) -> bool {
+        self.errores.is_empty() && self.conflictos.is_empty()
+    }
+}
+
+fn io_error(e: impl std::fmt::Display) -> AppError {
+    AppError::InternalError(format!("Error de E/S en el respaldo: {}", e))
+}
+
+#[derive(Clone)]
+pub struct RespaldoService {
+    db: Database,
+}
+
+impl RespaldoService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Escribe la cabecera y todos los empleados, solicitudes, calendarios, políticas, cadenas,
+    /// delegaciones de aprobación y horarios (y las sesiones vigentes si `incluir_sesiones`), un
+    /// registro JSON por línea
+    pub async fn exportar(
+        &self,
+        mut salida: impl Write,
+        incluir_sesiones: bool,
+    ) -> AppResult<ResumenRespaldo> {
+        let mut resumen = ResumenRespaldo::default();
+
+        let cabecera = Cabecera {
+            formato: FORMATO_RESPALDO.to_string(),
+            version: VERSION_RESPALDO,
+            creado_at: chrono::Utc::now().to_rfc3339(),
+        };
+        escribir_linea(&mut salida, &cabecera)?;
+
+        let mut empleados = self.db.listar_empleados().await?;
+        empleados.sort_by(|a, b| a.id.cmp(&b.id));
+        for empleado in empleados {
+            escribir_linea(&mut salida, &Registro::Empleado(empleado.into()))?;
+            resumen.empleados += 1;
+        }
+
+        let mut solicitudes = self.db.listar_solicitudes().await?;
+        solicitudes.sort_by(|a, b| (&a.created_at, &a.id).cmp(&(&b.created_at, &b.id)));
+        for solicitud in solicitudes {
+            escribir_linea(&mut salida, &Registro::Solicitud(Box::new(solicitud)))?;
+            resumen.solicitudes += 1;
+        }
+
+        for calendario in self.db.listar_calendarios().await? {
+            escribir_linea(&mut salida, &Registro::Calendario(calendario))?;
+            resumen.calendarios += 1;
+        }
+
+        for politica in self.db.listar_politicas().await? {
+            escribir_linea(&mut salida, &Registro::Politica(politica))?;
+            resumen.politicas += 1;
+        }
+
+        for cadena in self.db.listar_cadenas().await? {
+            escribir_linea(&mut salida, &Registro::Cadena(cadena))?;
+            resumen.cadenas += 1;
+        }
+
+        for delegacion in self.db.listar_delegaciones().await? {
+            escribir_linea(&mut salida, &Registro::Delegacion(delegacion))?;
+            resumen.delegaciones += 1;
+        }
+
+        for horario in self.db.listar_horarios().await? {
+            escribir_linea(&mut salida, &Registro::Horario(horario))?;
+            resumen.horarios += 1;
+        }
+
+        if incluir_sesiones {
+            let mut sesiones = self.db.listar_sesiones().await?;
+            sesiones.retain(|s| !s.esta_expirada());
+            sesiones.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+            for sesion in sesiones {
+                escribir_linea(&mut salida, &Registro::Sesion(sesion))?;
+                resumen.sesiones += 1;
+            }
+        }
+
+        salida.flush().map_err(io_error)?;
+        Ok(resumen)
+    }
+
+    /// Valida el archivo completo y lo compara con el destino. Solo escribe si no hay
+    /// errores ni conflictos y no es `dry_run`; si algo falla no se escribe nada.
+    /// Los saldos de días tomados no se respaldan: se reconstruyen desde las solicitudes.
+    pub async fn restaurar(
+        &self,
+        entrada: impl BufRead,
+        dry_run: bool,
+    ) -> AppResult<InformeRestauracion> {
+        let mut informe = InformeRestauracion::default();
+        let registros = leer_registros(entrada, &mut informe)?;
+
+        let mut contenido = Contenido::default();
+        for registro in registros {
+            match registro {
+                Registro::Empleado(e) => contenido.empleados.push(Empleado::from(e)),
+                Registro::Solicitud(s) => contenido.solicitudes.push(*s),
+                Registro::Calendario(c) => contenido.calendarios.push(c),
+                Registro::Politica(p) => contenido.politicas.push(p),
+                Registro::Cadena(c) => contenido.cadenas.push(c),
+                Registro::Delegacion(d) => contenido.delegaciones.push(d),
+                Registro::Horario(h) => contenido.horarios.push(h),
+                Registro::Sesion(s) => contenido.sesiones.push(s),
+            }
+        }
+        informe.resumen = contenido.resumen();
+
+        validar_registros(&contenido, &mut informe.errores);
+        self.buscar_conflictos(&contenido, &mut informe.conflictos)
+            .await?;
+
+        if dry_run || !informe.es_valido() {
+            return Ok(informe);
+        }
+
+        let Contenido {
+            empleados,
+            solicitudes,
+            calendarios,
+            politicas,
+            cadenas,
+            delegaciones,
+            horarios,
+            sesiones,
+        } = contenido;
+        for empleado in &empleados {
+            self.db.crear_empleado(empleado).await?;
+        }
+        for solicitud in &solicitudes {
+            self.db.guardar_solicitud(solicitud).await?;
+        }
+        for calendario in &calendarios {
+            self.db.guardar_calendario(calendario).await?;
+        }
+        for politica in &politicas {
+            self.db.guardar_politica(politica).await?;
+        }
+        for cadena in &cadenas {
+            self.db.guardar_cadena(cadena).await?;
+        }
+        for delegacion in &delegaciones {
+            self.db.guardar_delegacion(delegacion).await?;
+        }
+        for horario in &horarios {
+            self.db.guardar_horario(horario).await?;
+        }
+        for sesion in &sesiones {
+            self.db.guardar_sesion(sesion).await?;
+        }
+        informe.aplicado = true;
+
+        Ok(informe)
+    }
+
+    async fn buscar_conflictos(
+        &self,
+        contenido: &Contenido,
+        conflictos: &mut Vec<String>,
+    ) -> AppResult<()> {
+        let Contenido {
+            empleados,
+            solicitudes,
+            calendarios,
+            politicas,
+            cadenas,
+            delegaciones,
+            horarios,
+            sesiones,
+        } = contenido;
+        for empleado in empleados {
+            if self.db.obtener_empleado(&empleado.id).await?.is_some() {
+                conflictos.push(format!("El empleado {} ya existe", empleado.id));
+            }
+            if let Some(existente) = self.db.buscar_por_email(&empleado.email).await?
+                && existente.id != empleado.id
+            {
+                conflictos.push(format!(
+                    "El email {} ya pertenece al empleado {}",
+                    empleado.email, existente.id
+                ));
+            }
+        }
+        for solicitud in solicitudes {
+            if self
+                .db
+                .obtener_solicitud(&solicitud.empleado_id, &solicitud.id)
+                .await?
+                .is_some()
+            {
+                conflictos.push(format!("La solicitud {} ya existe", solicitud.id));
+            }
+        }
+        for calendario in calendarios {
+            if self.db.obtener_calendario(&calendario.id).await?.is_some() {
+                conflictos.push(format!("El calendario {} ya existe", calendario.id));
+            }
+        }
+        for politica in politicas {
+            if self.db.obtener_politica(&politica.id).await?.is_some() {
+                conflictos.push(format!("La política {} ya existe", politica.id));
+            }
+        }
+        for cadena in cadenas {
+            if self.db.obtener_cadena(&cadena.id).await?.is_some() {
+                conflictos.push(format!("La cadena {} ya existe", cadena.id));
+            }
+        }
+        for delegacion in delegaciones {
+            if self.db.obtener_delegacion(&delegacion.id).await?.is_some() {
+                conflictos.push(format!("La delegación {} ya existe", delegacion.id));
+            }
+        }
+        for horario in horarios {
+            if self.db.obtener_horario(&horario.id).await?.is_some() {
+                conflictos.push(format!("El horario {} ya existe", horario.id));
+            }
+        }
+        for sesion in sesiones {
+            if self.db.obtener_sesion(&sesion.session_id).await?.is_some() {
+                conflictos.push(format!("La sesión {} ya existe", sesion.session_id));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn escribir_linea<T: Serialize>(salida: &mut impl Write, valor: &T) -> AppResult<()> {
+    serde_json::to_writer(&mut *salida, valor).map_err(io_error)?;
+    salida.write_all(b"\n").map_err(io_error)
+}
+
+/// Lee la cabecera y los registros; los problemas de formato se acumulan en
+/// `informe.errores` con el número de línea en lugar de cortar la lectura
+fn leer_registros(
+    entrada: impl BufRead,
+    informe: &mut InformeRestauracion,
+) -> AppResult<Vec<Registro>> {
+    let mut registros = Vec::new();
+    let mut cabecera_leida = false;
+
+    for (i, linea) in entrada.lines().enumerate() {
+        let linea = linea.map_err(io_error)?;
+        let numero = i + 1;
+        if linea.trim().is_empty() {
+            continue;
+        }
+
+        if !cabecera_leida {
+            cabecera_leida = true;
+            match serde_json::from_str::<Cabecera>(&linea) {
+                Ok(c) if c.formato != FORMATO_RESPALDO => {
+                    informe.errores.push(format!(
+                        "Línea {}: formato desconocido '{}'",
+                        numero, c.formato
+                    ));
+                    return Ok(Vec::new());
+                }
+                Ok(c) if c.version > VERSION_RESPALDO => {
+                    informe.errores.push(format!(
+                        "Línea {}: versión de respaldo {} no soportada (máxima {})",
+                        numero, c.version, VERSION_RESPALDO
+                    ));
+                    return Ok(Vec::new());
+                }
+                Ok(_) => continue,
+                Err(e) => {
+                    informe
+                        .errores
+                        .push(format!("Línea {}: cabecera inválida: {}", numero, e));
+                    return Ok(Vec::new());
+                }
+            }
+        }
+
+        match serde_json::from_str::<Registro>(&linea) {
+            Ok(registro) => registros.push(registro),
+            Err(e) => informe.errores.push(format!("Línea {}: {}", numero, e)),
+        }
+    }
+
+    if !cabecera_leida {
+        informe.errores.push("El archivo está vacío".to_string());
+    }
+
+    Ok(registros)
+}
+
+/// Revisa la consistencia del archivo sin consultar el destino
+fn validar_registros(contenido: &Contenido, errores: &mut Vec<String>) {
+    let Contenido {
+        empleados,
+        solicitudes,
+        calendarios,
+        politicas,
+        cadenas,
+        delegaciones,
+        horarios,
+        sesiones,
+    } = contenido;
+    let mut ids = HashSet::new();
+    let mut emails = HashSet::new();
+    for empleado in empleados {
+        if let Err(e) = validar_empleado(empleado) {
+            errores.push(format!("Empleado {}: {}", empleado.id, e));
+        }
+        if !ids.insert(empleado.id.as_str()) {
+            errores.push(format!("Empleado {} repetido", empleado.id));
+        }
+        if !emails.insert(Empleado::normalizar_email(&empleado.email)) {
+            errores.push(format!("Email {} repetido", empleado.email));
+        }
+    }
+
+    let mut ids_solicitudes = HashSet::new();
+    for solicitud in solicitudes {
+        if !ids_solicitudes.insert(solicitud.id.as_str()) {
+            errores.push(format!("Solicitud {} repetida", solicitud.id));
+        }
+        if !ids.contains(solicitud.empleado_id.as_str()) {
+            errores.push(format!(
+                "Solicitud {}: el empleado {} no está en el respaldo",
+                solicitud.id, solicitud.empleado_id
+            ));
+        }
+        let inicio = NaiveDate::parse_from_str(&solicitud.fecha_inicio, "%Y-%m-%d");
+        let fin = NaiveDate::parse_from_str(&solicitud.fecha_fin, "%Y-%m-%d");
+        match (inicio, fin) {
+            (Ok(inicio), Ok(fin)) if fin < inicio => errores.push(format!(
+                "Solicitud {}: fecha_fin anterior a fecha_inicio",
+                solicitud.id
+            )),
+            (Ok(_), Ok(_)) => {}
+            _ => errores.push(format!("Solicitud {}: fechas inválidas", solicitud.id)),
+        }
+    }
+
+    let mut ids_calendarios = HashSet::new();
+    for calendario in calendarios {
+        if !ids_calendarios.insert(calendario.id.as_str()) {
+            errores.push(format!("Calendario {} repetido", calendario.id));
+        }
+    }
+
+    let mut ids_politicas = HashSet::new();
+    for politica in politicas {
+        if !ids_politicas.insert(politica.id.as_str()) {
+            errores.push(format!("Política {} repetida", politica.id));
+        }
+        if politica.es_de_codigo() {
+            errores.push(format!(
+                "Política {}: las de la LFT no se respaldan",
+                politica.id
+            ));
+        }
+    }
+
+    let mut ids_cadenas = HashSet::new();
+    for cadena in cadenas {
+        if !ids_cadenas.insert(cadena.id.as_str()) {
+            errores.push(format!("Cadena {} repetida", cadena.id));
+        }
+    }
+
+    let mut ids_delegaciones = HashSet::new();
+    for delegacion in delegaciones {
+        if !ids_delegaciones.insert(delegacion.id.as_str()) {
+            errores.push(format!("Delegación {} repetida", delegacion.id));
+        }
+        if delegacion.hasta < delegacion.desde {
+            errores.push(format!(
+                "Delegación {}: hasta anterior a desde",
+                delegacion.id
+            ));
+        }
+    }
+
+    let mut ids_horarios = HashSet::new();
+    for horario in horarios {
+        if !ids_horarios.insert(horario.id.as_str()) {
+            errores.push(format!("Horario {} repetido", horario.id));
+        }
+        if horario.es_de_codigo() {
+            errores.push(format!(
+                "Horario {}: el de lunes a viernes no se respalda",
+                horario.id
+            ));
+        }
+    }
+
+    let mut ids_sesiones = HashSet::new();
+    for sesion in sesiones {
+        if !ids_sesiones.insert(sesion.session_id.as_str()) {
+            errores.push(format!("Sesión {} repetida", sesion.session_id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_exportar_y_restaurar() {
+        let origen = MemoryStore::new();
+        origen.seed_demo("Demo1234").unwrap();
+        let origen = RespaldoService::new(Arc::new(origen));
+
+        let mut archivo = Vec::new();
+        let exportado = origen.exportar(&mut archivo, false).await.unwrap();
+        assert!(exportado.empleados > 0);
+
+        // Simulacro sobre una base vacía: valida pero no escribe
+        let db: Database = Arc::new(MemoryStore::new());
+        let destino = RespaldoService::new(db.clone());
+        let informe = destino.restaurar(archivo.as_slice(), true).await.unwrap();
+        assert!(informe.es_valido(), "{:?}", informe);
+        assert!(!informe.aplicado);
+        assert!(db.listar_empleados().await.unwrap().is_empty());
+
+        let informe = destino.restaurar(archivo.as_slice(), false).await.unwrap();
+        assert!(informe.aplicado);
+        assert_eq!(informe.resumen, exportado);
+        let empleado = db.obtener_empleado("1").await.unwrap().unwrap();
+        assert!(empleado.password_hash.is_some());
+
+        // Restaurar otra vez reporta conflictos y no escribe
+        let informe = destino.restaurar(archivo.as_slice(), false).await.unwrap();
+        assert!(!informe.aplicado);
+        assert_eq!(
+            informe.conflictos.len(),
+            exportado.empleados + exportado.solicitudes
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restaurar_archivo_invalido() {
+        let db: Database = Arc::new(MemoryStore::new());
+        let servicio = RespaldoService::new(db.clone());
+
+        let archivo = format!(
+            "{}\n{}\n{}\n",
+            r#"{"formato":"vacaciones-respaldo","version":1,"creado_at":"2025-01-01T00:00:00Z"}"#,
+            r#"{"tipo":"solicitud","id":"s1","empleado_id":"99","empleado_nombre":"X","fecha_inicio":"2025-02-10","fecha_fin":"2025-02-01","estado":"aprobada","dias_solicitados":3,"created_at":"2025-01-01T00:00:00Z"}"#,
+            "no es json",
+        );
+        let informe = servicio.restaurar(archivo.as_bytes(), false).await.unwrap();
+        assert!(!informe.aplicado);
+        assert_eq!(informe.errores.len(), 3, "{:?}", informe.errores);
+        assert!(informe.errores[0].starts_with("Línea 3"));
+
+        let informe = servicio
+            .restaurar(
+                r#"{"formato":"vacaciones-respaldo","version":9,"creado_at":""}"#.as_bytes(),
+                false,
+            )
+            .await
+            .unwrap();
+        assert!(informe.errores[0].contains("no soportada"));
+    }
+
+    #[tokio::test]
+    async fn test_rechazar_version_posterior() {
+        let origen = RespaldoService::new(MemoryStore::demo());
+        let mut archivo = Vec::new();
+        origen.exportar(&mut archivo, false).await.unwrap();
+        let archivo = String::from_utf8(archivo).unwrap();
+        let (cabecera, registros) = archivo.split_once('\n').unwrap();
+        let cabecera: Cabecera = serde_json::from_str(cabecera).unwrap();
+        assert_eq!(cabecera.version, VERSION_RESPALDO);
+
+        // Un respaldo de una versión posterior, con un tipo de registro que esta no conoce
+        let posterior = Cabecera {
+            version: VERSION_RESPALDO + 1,
+            ..cabecera
+        };
+        let archivo = format!(
+            "{}\n{}{}\n",
+            serde_json::to_string(&posterior).unwrap(),
+            registros,
+            r#"{"tipo":"desconocido","id":"x"}"#,
+        );
+        let db: Database = Arc::new(MemoryStore::new());
+        let informe = RespaldoService::new(db.clone())
+            .restaurar(archivo.as_bytes(), false)
+            .await
+            .unwrap();
+        assert!(!informe.aplicado);
+        assert_eq!(
+            informe.errores,
+            [format!(
+                "Línea 1: versión de respaldo {} no soportada (máxima {})",
+                VERSION_RESPALDO + 1,
+                VERSION_RESPALDO
+            )]
+        );
+        assert!(db.listar_empleados().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::db::Database;
+use crate::error::{AppError, AppResult};
+use crate::models::Sesion;
+
+#[derive(Clone)]
+pub struct SesionService {
+    db: Database,
+}
+
+impl SesionService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Sesiones vigentes de un empleado, la de actividad más reciente primero
+    pub async fn listar_activas(&self, empleado_id: &str) -> AppResult<Vec<Sesion>> {
+        let mut sesiones = self.db.listar_sesiones_empleado(empleado_id).await?;
+        sesiones.retain(|s| !s.esta_expirada());
+        sesiones.sort_by(|a, b| b.ultima_actividad.cmp(&a.ultima_actividad));
+        Ok(sesiones)
+    }
+
+    /// Revoca una sesión del empleado; no permite tocar sesiones de otros
+    pub async fn revocar(&self, empleado_id: &str, session_id: &str) -> AppResult<()> {
+        let sesion = self
+            .db
+            .obtener_sesion(session_id)
+            .await?
+            .filter(|s| s.empleado_id.as_deref() == Some(empleado_id))
+            .ok_or_else(|| AppError::NotFound("Sesión no encontrada".to_string()))?;
+
+        self.db.eliminar_sesion(&sesion.session_id).await
+    }
+
+    /// Revoca todas las sesiones del empleado salvo `excepto` (la actual, si se indica).
+    /// Devuelve cuántas se revocaron.
+    pub async fn revocar_todas(
+        &self,
+        empleado_id: &str,
+        excepto: Option<&str>,
+    ) -> AppResult<usize> {
+        let sesiones = self.db.listar_sesiones_empleado(empleado_id).await?;
+
+        let mut revocadas = 0;
+        for sesion in sesiones {
+            if Some(sesion.session_id.as_str()) == excepto {
+                continue;
+            }
+            self.db.eliminar_sesion(&sesion.session_id).await?;
+            revocadas += 1;
+        }
+
+        Ok(revocadas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_revocar_sesiones() {
+        let db: Database = Arc::new(MemoryStore::new());
+        let ahora = chrono::Utc::now().timestamp();
+        for (id, empleado_id, expires_at) in [
+            ("a", Some("1"), ahora + 3600),
+            ("b", Some("1"), ahora + 3600),
+            ("c", Some("1"), ahora - 1),
+            ("d", Some("2"), ahora + 3600),
+            ("e", None, ahora + 3600),
+        ] {
+            db.guardar_sesion(&Sesion {
+                session_id: id.to_string(),
+                data: "{}".to_string(),
+                expires_at,
+                created_at: chrono::Utc::now().to_rfc3339(),
+                empleado_id: empleado_id.map(String::from),
+                ultima_actividad: None,
+                ip: None,
+                user_agent: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let service = SesionService::new(db.clone());
+        assert_eq!(service.listar_activas("1").await.unwrap().len(), 2);
+
+        // No se puede revocar la sesión de otro empleado
+        assert!(matches!(
+            service.revocar("1", "d").await,
+            Err(AppError::NotFound(_))
+        ));
+
+        assert_eq!(service.revocar_todas("1", Some("a")).await.unwrap(), 2);
+        let restantes = service.listar_activas("1").await.unwrap();
+        assert_eq!(restantes.len(), 1);
+        assert_eq!(restantes[0].session_id, "a");
+
+        service.revocar("1", "a").await.unwrap();
+        assert!(db.obtener_sesion("a").await.unwrap().is_none());
+        assert!(db.obtener_sesion("d").await.unwrap().is_some());
+    }
+}
@@ -1,39 +1,35 @@
-use crate::db::DynamoDBClient;
-use crate::error::{AppError, AppResult};
-use crate::models::{SolicitudVacaciones};
-use aws_sdk_dynamodb::types::AttributeValue;
+use crate::db::Database;
+use crate::error::{AppError, AppResult, ErrorCampo};
+use crate::models::cadena::pasos_para;
+use crate::models::dias::validar_rango;
+use crate::models::pagina::LIMITE_MAXIMO;
+use crate::models::permiso::{self, Derecho};
+use crate::models::{
+    ACTOR_SISTEMA, CambioEstado, CambioSaldo, ConsumoPeriodo, DecisionPaso, Dias, Empleado,
+    EstadoSolicitud, FiltroSolicitudes, NuevaSolicitud, Pagina, Paginacion, PasoAprobacion,
+    PeriodoVacaciones, SolicitudPorAprobar, SolicitudVacaciones,
+};
 
+use chrono::{Datelike, NaiveDate, Utc};
+use futures::{StreamExt, TryStreamExt, stream};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
+/// Máximo de solicitantes que se leen en paralelo al armar la bandeja de aprobación
+const CONCURRENCIA_SOLICITANTES: usize = 8;
 
 pub struct SolicitudService {
-    db: DynamoDBClient,
+    db: Database,
 }
 
 impl SolicitudService {
-    pub fn new(db: DynamoDBClient) -> Self {
+    pub fn new(db: Database) -> Self {
         Self { db }
     }
 
     /// Lista todas las solicitudes de vacaciones
     pub async fn listar_solicitudes(&self) -> AppResult<Vec<SolicitudVacaciones>> {
-        let result = self
-            .db
-            .client
-            .scan()
-            .table_name(&self.db.table_name)
-            .filter_expression("begins_with(SK, :sk)")
-            .expression_attribute_values(":sk", AttributeValue::S("SOLICITUD#".to_string()))
-            .send()
-            .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
-
-        let solicitudes: Vec<SolicitudVacaciones> = result
-            .items()
-            .iter()
-            .filter_map(|item| SolicitudVacaciones::from_item(item))
-            .collect();
-
-        Ok(solicitudes)
+        self.db.listar_solicitudes().await
     }
 
     /// Lista solicitudes de un empleado específico
@@ -41,80 +37,1496 @@ impl SolicitudService {
         &self,
         empleado_id: &str,
     ) -> AppResult<Vec<SolicitudVacaciones>> {
-        let result = self
-            .db
-            .client
-            .query()
-            .table_name(&self.db.table_name)
-            .key_condition_expression("PK = :pk AND begins_with(SK, :sk)")
-            .expression_attribute_values(
-                ":pk",
-                AttributeValue::S(format!("EMPLEADO#{}", empleado_id)),
-            )
-            .expression_attribute_values(":sk", AttributeValue::S("SOLICITUD#".to_string()))
-            .send()
+        self.db.listar_solicitudes_por_empleado(empleado_id).await
+    }
+
+    /// Lista una página de las solicitudes que cumplen el filtro
+    pub async fn listar_solicitudes_pagina(
+        &self,
+        filtro: &FiltroSolicitudes,
+        paginacion: &Paginacion,
+    ) -> AppResult<Pagina<SolicitudVacaciones>> {
+        self.db.listar_solicitudes_pagina(filtro, paginacion).await
+    }
+
+    /// Crea una solicitud pendiente de `empleado` con los días ya calculados según su tipo
+    /// (laborables o naturales), si cumple las reglas de `validar`. El nombre y el
+    /// departamento se copian del empleado, los pasos de aprobación de la cadena vigente de
+    /// su departamento y `actor` (quien la crea) queda en la primera entrada del historial.
+    /// Los tipos que no requieren aprobación quedan aprobados al registrarse.
+    pub async fn crear_solicitud(
+        &self,
+        empleado: &Empleado,
+        solicitud: NuevaSolicitud,
+        dias_solicitados: Dias,
+        actor: &str,
+    ) -> AppResult<SolicitudVacaciones> {
+        let cadenas = self.db.listar_cadenas().await?;
+        let created_at = Utc::now().to_rfc3339();
+        let nueva_solicitud = SolicitudVacaciones {
+            id: Uuid::new_v4().to_string(),
+            empleado_id: empleado.id.clone(),
+            empleado_nombre: empleado.nombre.clone(),
+            departamento: empleado.departamento.clone(),
+            tipo_permiso: solicitud.tipo_permiso,
+            documento: solicitud
+                .documento
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            fecha_inicio: solicitud.fecha_inicio,
+            fecha_fin: solicitud.fecha_fin,
+            jornada: solicitud.jornada,
+            estado: EstadoSolicitud::Pendiente,
+            dias_solicitados,
+            created_at: created_at.clone(),
+            version: 0,
+            consumo: Vec::new(),
+            reprograma: None,
+            resuelto_por: None,
+            resuelto_at: None,
+            comentario: None,
+            pasos: pasos_para(&cadenas, &empleado.departamento),
+            aprobaciones: Vec::new(),
+            historial: vec![CambioEstado {
+                anterior: None,
+                estado: EstadoSolicitud::Pendiente,
+                actor: actor.to_string(),
+                fecha: created_at,
+                motivo: None,
+            }],
+        };
+
+        self.validar(&nueva_solicitud, empleado, None).await?;
+        self.registrar(nueva_solicitud).await
+    }
+
+    /// Guarda una solicitud nueva ya validada; si su tipo no requiere aprobación la aprueba
+    /// en seguida a nombre de `ACTOR_SISTEMA`
+    async fn registrar(&self, solicitud: SolicitudVacaciones) -> AppResult<SolicitudVacaciones> {
+        self.db.guardar_solicitud(&solicitud).await?;
+        self.aprobar_sin_cadena(solicitud).await
+    }
+
+    /// Aprueba a nombre de `ACTOR_SISTEMA` una solicitud recién guardada cuyo tipo no
+    /// requiere aprobación; las demás se devuelven tal cual
+    async fn aprobar_sin_cadena(
+        &self,
+        solicitud: SolicitudVacaciones,
+    ) -> AppResult<SolicitudVacaciones> {
+        if solicitud.tipo_permiso.reglas().requiere_aprobacion {
+            return Ok(solicitud);
+        }
+        let motivo = format!("{} no requiere aprobación", solicitud.tipo_permiso.nombre());
+        self.aplicar_estado(
+            solicitud,
+            EstadoSolicitud::Aprobada,
+            ACTOR_SISTEMA,
+            Some(motivo),
+        )
+        .await
+    }
+
+    /// Cambia el estado de una solicitud de forma atómica y registra la transición en su
+    /// historial con `actor` y `motivo`. Solo se permiten las transiciones de
+    /// `EstadoSolicitud::puede_pasar_a`; cualquier otra es `AppError::Conflict`.
+    ///
+    /// Al aprobar se vuelven a aplicar las reglas de `validar` y los días se descuentan de los
+    /// periodos de servicio vigentes en la fecha de inicio, el más antiguo primero; al cancelar
+    /// o revocar una aprobada se devuelven. Los saldos de esos periodos se actualizan en la
+    /// misma operación que la solicitud; si otra operación cambió la solicitud o algún saldo
+    /// mientras tanto, devuelve `AppError::Conflict`.
+    pub async fn actualizar_estado(
+        &self,
+        empleado_id: &str,
+        solicitud_id: &str,
+        nuevo_estado: EstadoSolicitud,
+        actor: &str,
+        motivo: Option<String>,
+    ) -> AppResult<SolicitudVacaciones> {
+        let solicitud = self.obtener_solicitud(empleado_id, solicitud_id).await?;
+        self.aplicar_estado(solicitud, nuevo_estado, actor, motivo)
+            .await
+    }
+
+    /// Registra la decisión de `decisor` sobre el paso actual de la cadena de aprobación de
+    /// una solicitud pendiente. Solo puede decidir quien corresponde al paso (ver
+    /// `Aprobador`); si no, `AppError::Forbidden`. Un rechazo en cualquier paso la rechaza;
+    /// la aprobación del último paso la aprueba como `actualizar_estado`, y la de uno
+    /// intermedio la deja pendiente del siguiente, comprobando antes que aún se podría aprobar.
+    pub async fn decidir(
+        &self,
+        empleado_id: &str,
+        solicitud_id: &str,
+        decisor: &Empleado,
+        delegantes: &[String],
+        aprobada: bool,
+        comentario: Option<String>,
+    ) -> AppResult<SolicitudVacaciones> {
+        let mut solicitud = self.obtener_solicitud(empleado_id, solicitud_id).await?;
+        let destino = if aprobada {
+            EstadoSolicitud::Aprobada
+        } else {
+            EstadoSolicitud::Rechazada
+        };
+        let Some((indice, paso)) = solicitud.paso_actual() else {
+            return Err(transicion_invalida(solicitud.estado, destino));
+        };
+
+        let solicitante = self.obtener_empleado(&solicitud.empleado_id).await?;
+        let Some(en_nombre_de) =
+            Aprobador::del_paso(&paso, &solicitante).facultad(decisor, delegantes, &solicitante)
+        else {
+            return Err(AppError::Forbidden(format!(
+                "La solicitud espera la decisión del paso {} de {} ({})",
+                indice + 1,
+                solicitud.total_pasos(),
+                paso
+            )));
+        };
+
+        let ultimo = solicitud.es_ultimo_paso();
+        solicitud.aprobaciones.push(DecisionPaso {
+            paso: indice,
+            aprobador: decisor.id.clone(),
+            aprobada,
+            fecha: Utc::now().to_rfc3339(),
+            comentario: comentario.clone(),
+            en_nombre_de,
+        });
+
+        if aprobada && !ultimo {
+            self.validar(&solicitud, &solicitante, None).await?;
+            solicitud.version += 1;
+            self.db
+                .cambiar_estado_solicitud(&solicitud, EstadoSolicitud::Pendiente, &[])
+                .await?;
+            return Ok(solicitud);
+        }
+        self.aplicar_estado(solicitud, destino, &decisor.id, comentario)
             .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+    }
+
+    /// Solicitudes pendientes cuyo paso actual le toca decidir a `decisor`, por sí mismo o
+    /// por delegación de alguno de `delegantes`, de la más antigua a la más reciente
+    pub async fn listar_por_aprobar(
+        &self,
+        decisor: &Empleado,
+        delegantes: &[String],
+    ) -> AppResult<Vec<SolicitudPorAprobar>> {
+        let filtro = FiltroSolicitudes {
+            empleado_id: None,
+            estado: Some(EstadoSolicitud::Pendiente),
+        };
+        let mut paginacion = Paginacion {
+            limit: Some(LIMITE_MAXIMO),
+            cursor: None,
+        };
+
+        // Primero las pendientes con un paso por decidir, luego cada solicitante una sola vez
+        let mut candidatas = Vec::new();
+        loop {
+            let pagina = self
+                .db
+                .listar_solicitudes_pagina(&filtro, &paginacion)
+                .await?;
+            candidatas.extend(
+                pagina
+                    .elementos
+                    .into_iter()
+                    .filter_map(|s| s.paso_actual().map(|(_, paso)| (s, paso))),
+            );
+
+            match pagina.siguiente_cursor {
+                Some(cursor) => paginacion.cursor = Some(cursor),
+                None => break,
+            }
+        }
 
-        let solicitudes: Vec<SolicitudVacaciones> = result
-            .items()
+        let ids: HashSet<String> = candidatas
             .iter()
-            .filter_map(|item| SolicitudVacaciones::from_item(item))
+            .map(|(s, _)| s.empleado_id.clone())
+            .collect();
+        let solicitantes: HashMap<String, Empleado> = stream::iter(ids)
+            .map(|id| async move { self.db.obtener_empleado(&id).await })
+            .buffer_unordered(CONCURRENCIA_SOLICITANTES)
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flatten()
+            .map(|e| (e.id.clone(), e))
+            .collect();
+
+        let mut por_aprobar: Vec<SolicitudPorAprobar> = candidatas
+            .into_iter()
+            .filter_map(|(solicitud, paso)| {
+                let solicitante = solicitantes.get(&solicitud.empleado_id)?;
+                let en_nombre_de = Aprobador::del_paso(&paso, solicitante).facultad(
+                    decisor,
+                    delegantes,
+                    solicitante,
+                )?;
+                Some(SolicitudPorAprobar {
+                    solicitud,
+                    en_nombre_de,
+                })
+            })
             .collect();
 
-        Ok(solicitudes)
+        por_aprobar.sort_by(|a, b| a.solicitud.created_at.cmp(&b.solicitud.created_at));
+        Ok(por_aprobar)
     }
 
-    /// Actualiza el estado de una solicitud                                                                                                 
-    pub async fn actualizar_estado(
+    /// Cancela una solicitud pendiente, o una aprobada que aún no empieza (sus días vuelven
+    /// al saldo). `actor` es quien la cancela: el propio empleado o un administrador.
+    pub async fn cancelar(
         &self,
         empleado_id: &str,
         solicitud_id: &str,
-        nuevo_estado: &str,
+        actor: &str,
+        motivo: Option<String>,
     ) -> AppResult<SolicitudVacaciones> {
-        // Validar que el estado sea válido
-        if !["pendiente", "aprobada", "rechazada"].contains(&nuevo_estado) {
-            return Err(AppError::BadRequest(format!(
-                "Estado inválido: {}",
-                nuevo_estado
-            )));
+        let solicitud = self.obtener_solicitud(empleado_id, solicitud_id).await?;
+        validar_cancelable(&solicitud, Utc::now().date_naive())?;
+        self.aplicar_estado(solicitud, EstadoSolicitud::Cancelada, actor, motivo)
+            .await
+    }
+
+    /// Cambia las fechas de una solicitud: crea una nueva versión pendiente del mismo tipo con
+    /// `reprograma` apuntando a la original y cancela la original (con las mismas reglas que `cancelar`).
+    /// La nueva se valida como si la original ya estuviera cancelada, así que sus fechas y
+    /// sus días no cuentan en contra; si no es válida no se cambia nada. La cancelación y la
+    /// nueva se guardan en una sola operación atómica.
+    pub async fn reprogramar(
+        &self,
+        empleado: &Empleado,
+        solicitud_id: &str,
+        fechas: NuevaSolicitud,
+        dias_solicitados: Dias,
+        actor: &str,
+    ) -> AppResult<SolicitudVacaciones> {
+        let mut original = self.obtener_solicitud(&empleado.id, solicitud_id).await?;
+        validar_cancelable(&original, Utc::now().date_naive())?;
+
+        let cadenas = self.db.listar_cadenas().await?;
+        let created_at = Utc::now().to_rfc3339();
+        let nueva_solicitud = SolicitudVacaciones {
+            id: Uuid::new_v4().to_string(),
+            empleado_id: empleado.id.clone(),
+            empleado_nombre: empleado.nombre.clone(),
+            departamento: empleado.departamento.clone(),
+            tipo_permiso: original.tipo_permiso,
+            documento: original.documento.clone(),
+            fecha_inicio: fechas.fecha_inicio,
+            fecha_fin: fechas.fecha_fin,
+            jornada: fechas.jornada,
+            estado: EstadoSolicitud::Pendiente,
+            dias_solicitados,
+            created_at: created_at.clone(),
+            version: 0,
+            consumo: Vec::new(),
+            reprograma: Some(original.id.clone()),
+            resuelto_por: None,
+            resuelto_at: None,
+            comentario: None,
+            pasos: pasos_para(&cadenas, &empleado.departamento),
+            aprobaciones: Vec::new(),
+            historial: vec![CambioEstado {
+                anterior: None,
+                estado: EstadoSolicitud::Pendiente,
+                actor: actor.to_string(),
+                fecha: created_at,
+                motivo: Some(format!("Reprograma la solicitud {}", original.id)),
+            }],
+        };
+        self.validar(&nueva_solicitud, empleado, Some(&original))
+            .await?;
+
+        let motivo = format!("Reprogramada como {}", nueva_solicitud.id);
+        let (estado_anterior, saldos) = self
+            .preparar_estado(
+                &mut original,
+                EstadoSolicitud::Cancelada,
+                actor,
+                Some(motivo),
+            )
+            .await?;
+        self.db
+            .reprogramar_solicitud(&original, estado_anterior, &saldos, &nueva_solicitud)
+            .await?;
+        self.aprobar_sin_cadena(nueva_solicitud).await
+    }
+
+    /// Marca como expiradas las solicitudes pendientes cuya fecha de inicio es anterior a
+    /// `hoy`. Las que otra operación resolvió mientras tanto se dejan como están. Devuelve
+    /// cuántas se expiraron.
+    pub async fn expirar_pendientes(&self, hoy: NaiveDate) -> AppResult<usize> {
+        let filtro = FiltroSolicitudes {
+            empleado_id: None,
+            estado: Some(EstadoSolicitud::Pendiente),
+        };
+        let mut paginacion = Paginacion {
+            limit: Some(LIMITE_MAXIMO),
+            cursor: None,
+        };
+
+        let mut expiradas = 0;
+        loop {
+            let pagina = self
+                .db
+                .listar_solicitudes_pagina(&filtro, &paginacion)
+                .await?;
+            for solicitud in pagina.elementos {
+                let vencida = NaiveDate::parse_from_str(&solicitud.fecha_inicio, "%Y-%m-%d")
+                    .is_ok_and(|inicio| inicio < hoy);
+                if !vencida {
+                    continue;
+                }
+                match self
+                    .aplicar_estado(solicitud, EstadoSolicitud::Expirada, ACTOR_SISTEMA, None)
+                    .await
+                {
+                    Ok(_) => expiradas += 1,
+                    Err(AppError::Conflict(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+
+            match pagina.siguiente_cursor {
+                Some(cursor) => paginacion.cursor = Some(cursor),
+                None => return Ok(expiradas),
+            }
+        }
+    }
+
+    async fn aplicar_estado(
+        &self,
+        mut solicitud: SolicitudVacaciones,
+        nuevo_estado: EstadoSolicitud,
+        actor: &str,
+        motivo: Option<String>,
+    ) -> AppResult<SolicitudVacaciones> {
+        let (estado_anterior, saldos) = self
+            .preparar_estado(&mut solicitud, nuevo_estado, actor, motivo)
+            .await?;
+        self.db
+            .cambiar_estado_solicitud(&solicitud, estado_anterior, &saldos)
+            .await?;
+
+        Ok(solicitud)
+    }
+
+    /// Aplica a `solicitud` la transición a `nuevo_estado` sin guardarla: devuelve el estado
+    /// anterior y los cambios de saldo que deben guardarse junto con ella
+    async fn preparar_estado(
+        &self,
+        solicitud: &mut SolicitudVacaciones,
+        nuevo_estado: EstadoSolicitud,
+        actor: &str,
+        motivo: Option<String>,
+    ) -> AppResult<(EstadoSolicitud, Vec<CambioSaldo>)> {
+        if !solicitud.estado.puede_pasar_a(nuevo_estado) {
+            return Err(transicion_invalida(solicitud.estado, nuevo_estado));
         }
 
-        // Primero obtener la solicitud para verificar que existe
-        let result = self
+        // Solo entrar o salir de "aprobada" mueve los saldos de días tomados
+        let saldos = if nuevo_estado == EstadoSolicitud::Aprobada {
+            self.descontar_dias(solicitud).await?
+        } else if solicitud.estado == EstadoSolicitud::Aprobada {
+            self.devolver_dias(solicitud).await?
+        } else {
+            Vec::new()
+        };
+
+        let estado_anterior = solicitud.cambiar_estado(nuevo_estado, actor, motivo);
+        solicitud.version += 1;
+        Ok((estado_anterior, saldos))
+    }
+
+    /// Valida la solicitud (ver `validar`), registra en `solicitud.consumo` cómo se reparten
+    /// sus días entre los periodos y devuelve el cambio de saldo de cada periodo
+    async fn descontar_dias(
+        &self,
+        solicitud: &mut SolicitudVacaciones,
+    ) -> AppResult<Vec<CambioSaldo>> {
+        let empleado = self.obtener_empleado(&solicitud.empleado_id).await?;
+        let reparto = self.validar(solicitud, &empleado, None).await?;
+
+        let saldos = cambios_de_saldo(&reparto.numeros, &reparto.anteriores, &reparto.consumo, 1);
+        solicitud.consumo = reparto.consumo;
+        Ok(saldos)
+    }
+
+    /// Reglas de una solicitud, al crearla y otra vez al aprobarla: fechas válidas, que no
+    /// empiece en el pasado (salvo los tipos retroactivos), al menos un día, el documento si
+    /// su tipo lo exige, sin traslaparse con otra solicitud pendiente o aprobada del empleado
+    /// y con días suficientes según el derecho de su tipo: las vacaciones en los periodos
+    /// vigentes en su fecha de inicio, el resto en su año o por solicitud (ver
+    /// `TipoPermiso::reglas`). Devuelve cómo se repartirían los días de vacaciones (el
+    /// periodo más antiguo primero) o `AppError::Validation` con todos los campos que no las
+    /// cumplen.
+    ///
+    /// `reemplaza` es una solicitud que se va a cancelar en su lugar (ver `reprogramar`): no
+    /// cuenta para los traslapes y, si está aprobada, sus días se consideran ya devueltos.
+    async fn validar(
+        &self,
+        solicitud: &SolicitudVacaciones,
+        empleado: &Empleado,
+        reemplaza: Option<&SolicitudVacaciones>,
+    ) -> AppResult<Reparto> {
+        let hoy = Utc::now().date_naive();
+        let mut otras = self
             .db
-            .client
-            .get_item()
-            .table_name(&self.db.table_name)
-            .key("PK", AttributeValue::S(format!("EMPLEADO#{}", empleado_id)))
-            .key(
-                "SK",
-                AttributeValue::S(format!("SOLICITUD#{}", solicitud_id)),
+            .listar_solicitudes_por_empleado(&empleado.id)
+            .await?;
+        otras.retain(|o| reemplaza.is_none_or(|r| r.id != o.id));
+        let mut errores = validar_campos(solicitud, &otras, hoy);
+        let devueltos = match reemplaza {
+            Some(r) if r.estado == EstadoSolicitud::Aprobada && r.tipo_permiso.es_vacaciones() => {
+                consumo_aprobado(empleado, r)?
+            }
+            _ => Vec::new(),
+        };
+
+        let mut reparto = Reparto::default();
+        let tipo = solicitud.tipo_permiso;
+        let rango = validar_rango(&solicitud.fecha_inicio, &solicitud.fecha_fin);
+        if let Ok((inicio, _)) = rango
+            && solicitud.dias_solicitados.es_positivo()
+            && !tipo.es_vacaciones()
+        {
+            match tipo.reglas().derecho {
+                Derecho::PorAno(derecho) => {
+                    let usados = permiso::dias_en_ano(
+                        &otras,
+                        tipo,
+                        EstadoSolicitud::Aprobada,
+                        inicio.year(),
+                    );
+                    if usados + solicitud.dias_solicitados > Dias::enteros(derecho) {
+                        errores.push(ErrorCampo::new(
+                            "dias_solicitados",
+                            format!(
+                                "Días insuficientes de {}: quedan {} en {} y la solicitud requiere {}",
+                                tipo.nombre(),
+                                (Dias::enteros(derecho) - usados).max(Dias::CERO),
+                                inicio.year(),
+                                solicitud.dias_solicitados
+                            ),
+                        ));
+                    }
+                }
+                Derecho::PorSolicitud(derecho)
+                    if solicitud.dias_solicitados > Dias::enteros(derecho) =>
+                {
+                    errores.push(ErrorCampo::new(
+                        "dias_solicitados",
+                        format!(
+                            "{} es de hasta {} días por solicitud y la solicitud requiere {}",
+                            tipo.nombre(),
+                            derecho,
+                            solicitud.dias_solicitados
+                        ),
+                    ));
+                }
+                _ => {}
+            }
+        } else if let Ok((inicio, _)) = rango
+            && solicitud.dias_solicitados.es_positivo()
+        {
+            reparto.numeros = empleado.periodos_vigentes_al(inicio);
+            reparto.anteriores = self.leer_saldos(empleado, &reparto.numeros).await?;
+            let politicas = self.db.listar_politicas().await?;
+            let periodos: Vec<PeriodoVacaciones> = reparto
+                .numeros
+                .iter()
+                .zip(&reparto.anteriores)
+                .map(|(&numero, (_, tomados))| {
+                    let devuelto: Dias = devueltos
+                        .iter()
+                        .filter(|c| c.periodo == numero)
+                        .map(|c| c.dias)
+                        .sum();
+                    empleado.periodo(numero, *tomados - devuelto, &politicas)
+                })
+                .collect();
+
+            match PeriodoVacaciones::repartir(&periodos, solicitud.dias_solicitados) {
+                Ok(consumo) => reparto.consumo = consumo,
+                Err(disponibles) => errores.push(ErrorCampo::new(
+                    "dias_solicitados",
+                    format!(
+                        "Días insuficientes: el empleado tiene {} disponibles al {} y la solicitud requiere {}",
+                        disponibles, inicio, solicitud.dias_solicitados
+                    ),
+                )),
+            }
+        }
+
+        if errores.is_empty() {
+            Ok(reparto)
+        } else {
+            Err(AppError::Validation(errores))
+        }
+    }
+
+    /// Devuelve a sus periodos los días que descontó una solicitud aprobada y limpia su
+    /// `consumo`. Las aprobadas sin consumo registrado devuelven todo al periodo de su inicio.
+    async fn devolver_dias(
+        &self,
+        solicitud: &mut SolicitudVacaciones,
+    ) -> AppResult<Vec<CambioSaldo>> {
+        if !solicitud.tipo_permiso.es_vacaciones() {
+            return Ok(Vec::new());
+        }
+        let empleado = self.obtener_empleado(&solicitud.empleado_id).await?;
+        let consumo = consumo_aprobado(&empleado, solicitud)?;
+        solicitud.consumo.clear();
+
+        let numeros: Vec<i32> = consumo.iter().map(|c| c.periodo).collect();
+        let anteriores = self.leer_saldos(&empleado, &numeros).await?;
+        Ok(cambios_de_saldo(&numeros, &anteriores, &consumo, -1))
+    }
+
+    /// Días tomados de cada periodo, en el mismo orden que `numeros`: el saldo leído
+    /// (`None` si aún no existe) y el valor vigente, que sin saldo se calcula desde las
+    /// solicitudes aprobadas
+    async fn leer_saldos(
+        &self,
+        empleado: &Empleado,
+        numeros: &[i32],
+    ) -> AppResult<Vec<(Option<Dias>, Dias)>> {
+        let claves: Vec<(String, i32)> =
+            numeros.iter().map(|&n| (empleado.id.clone(), n)).collect();
+        let saldos = self.db.obtener_saldos(&claves).await?;
+
+        let solicitudes = if claves.iter().all(|clave| saldos.contains_key(clave)) {
+            Vec::new()
+        } else {
+            self.db
+                .listar_solicitudes_por_empleado(&empleado.id)
+                .await?
+        };
+
+        Ok(claves
+            .iter()
+            .map(|clave| match saldos.get(clave) {
+                Some(&dias) => (Some(dias), dias),
+                None => (
+                    None,
+                    empleado.dias_aprobados_en_periodo(&solicitudes, clave.1),
+                ),
+            })
+            .collect())
+    }
+
+    /// Solicitud de un empleado; `NotFound` si no existe
+    pub async fn obtener_solicitud(
+        &self,
+        empleado_id: &str,
+        solicitud_id: &str,
+    ) -> AppResult<SolicitudVacaciones> {
+        self.db
+            .obtener_solicitud(empleado_id, solicitud_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Solicitud no encontrada".to_string()))
+    }
+
+    async fn obtener_empleado(&self, empleado_id: &str) -> AppResult<Empleado> {
+        self.db
+            .obtener_empleado(empleado_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Empleado {} no encontrado", empleado_id)))
+    }
+}
+
+/// Quién puede decidir un paso de aprobación para un solicitante concreto. Si el paso
+/// recae en el propio solicitante (o en su jefe y no tiene uno), lo deciden los
+/// administradores.
+enum Aprobador {
+    Empleado(String),
+    Administradores,
+}
+
+impl Aprobador {
+    fn del_paso(paso: &PasoAprobacion, solicitante: &Empleado) -> Self {
+        let empleado_id = match paso {
+            PasoAprobacion::JefeDirecto => solicitante.jefe_id.as_ref(),
+            PasoAprobacion::Empleado { empleado_id } => Some(empleado_id),
+            PasoAprobacion::Administrador => None,
+        };
+        match empleado_id {
+            Some(id) if *id != solicitante.id => Aprobador::Empleado(id.clone()),
+            _ => Aprobador::Administradores,
+        }
+    }
+
+    /// Si `decisor` puede decidir el paso: `Some(None)` por sí mismo, `Some(Some(id))` por
+    /// delegación de `id` (uno de sus `delegantes` vigentes), `None` si no puede. Los pasos
+    /// de administrador no se delegan, y nadie decide por delegación su propia solicitud.
+    fn facultad(
+        &self,
+        decisor: &Empleado,
+        delegantes: &[String],
+        solicitante: &Empleado,
+    ) -> Option<Option<String>> {
+        match self {
+            Aprobador::Empleado(id) if decisor.id == *id => Some(None),
+            Aprobador::Empleado(id) if delegantes.contains(id) && decisor.id != solicitante.id => {
+                Some(Some(id.clone()))
+            }
+            Aprobador::Empleado(_) => None,
+            Aprobador::Administradores => decisor.es_admin.then_some(None),
+        }
+    }
+}
+
+/// Error de una transición no permitida por `EstadoSolicitud::puede_pasar_a`
+fn transicion_invalida(actual: EstadoSolicitud, destino: EstadoSolicitud) -> AppError {
+    if actual == destino {
+        AppError::Conflict(format!("La solicitud ya está {}", destino))
+    } else {
+        AppError::Conflict(format!(
+            "Una solicitud {} no puede pasar a {}",
+            actual, destino
+        ))
+    }
+}
+
+/// Reparto de los días de una solicitud: periodos vigentes, sus saldos (ver `leer_saldos`)
+/// y los días que se toman de cada uno
+#[derive(Default)]
+struct Reparto {
+    numeros: Vec<i32>,
+    anteriores: Vec<(Option<Dias>, Dias)>,
+    consumo: Vec<ConsumoPeriodo>,
+}
+
+/// Días que descontó una solicitud aprobada de cada periodo. Las aprobadas sin consumo
+/// registrado se imputan completas al periodo de su fecha de inicio.
+fn consumo_aprobado(
+    empleado: &Empleado,
+    solicitud: &SolicitudVacaciones,
+) -> AppResult<Vec<ConsumoPeriodo>> {
+    if !solicitud.consumo.is_empty() {
+        return Ok(solicitud.consumo.clone());
+    }
+    let periodo = empleado.periodo_de_solicitud(solicitud).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "Fecha de inicio inválida: {}",
+            solicitud.fecha_inicio
+        ))
+    })?;
+    Ok(vec![ConsumoPeriodo {
+        periodo,
+        dias: solicitud.dias_solicitados,
+    }])
+}
+
+/// Solo se pueden cancelar (o reprogramar) las pendientes y las aprobadas que aún no empiezan
+fn validar_cancelable(solicitud: &SolicitudVacaciones, hoy: NaiveDate) -> AppResult<()> {
+    if !solicitud.estado.puede_pasar_a(EstadoSolicitud::Cancelada) {
+        return Err(AppError::Conflict(format!(
+            "Una solicitud {} no se puede cancelar",
+            solicitud.estado
+        )));
+    }
+    let iniciada = NaiveDate::parse_from_str(&solicitud.fecha_inicio, "%Y-%m-%d")
+        .is_ok_and(|inicio| inicio <= hoy);
+    if solicitud.estado == EstadoSolicitud::Aprobada && iniciada {
+        return Err(AppError::Validation(vec![ErrorCampo::new(
+            "fecha_inicio",
+            format!(
+                "La solicitud empezó el {}: ya no se puede cancelar",
+                solicitud.fecha_inicio
+            ),
+        )]));
+    }
+    Ok(())
+}
+
+/// Reglas que no dependen del saldo: fechas, inicio no pasado (salvo tipos retroactivos),
+/// días que cuentan, documento y traslapes con otras solicitudes pendientes o aprobadas del
+/// empleado
+fn validar_campos(
+    solicitud: &SolicitudVacaciones,
+    otras: &[SolicitudVacaciones],
+    hoy: NaiveDate,
+) -> Vec<ErrorCampo> {
+    let (inicio, fin) = match validar_rango(&solicitud.fecha_inicio, &solicitud.fecha_fin) {
+        Ok(rango) => rango,
+        Err(errores) => return errores,
+    };
+
+    let reglas = solicitud.tipo_permiso.reglas();
+    let mut errores = Vec::new();
+    if inicio < hoy && !reglas.retroactivo {
+        errores.push(ErrorCampo::new(
+            "fecha_inicio",
+            format!("La fecha de inicio {} ya pasó", inicio),
+        ));
+    }
+    if !solicitud.dias_solicitados.es_positivo() {
+        errores.push(ErrorCampo::new(
+            "dias_solicitados",
+            "El rango no incluye días laborables",
+        ));
+    }
+    errores.extend(solicitud.jornada.validar(inicio, fin));
+    if reglas.requiere_documento && solicitud.documento.is_none() {
+        errores.push(ErrorCampo::new(
+            "documento",
+            format!(
+                "{} requiere la referencia de su documento (folio, acta, etc.)",
+                solicitud.tipo_permiso.nombre()
+            ),
+        ));
+    }
+
+    let vigentes = otras
+        .iter()
+        .filter(|o| o.id != solicitud.id && o.estado.activa());
+    for otra in vigentes {
+        // Las ya guardadas no se limitan a MAX_DIAS_RANGO: solo se leen sus fechas
+        let otra_inicio = NaiveDate::parse_from_str(&otra.fecha_inicio, "%Y-%m-%d");
+        let otra_fin = NaiveDate::parse_from_str(&otra.fecha_fin, "%Y-%m-%d");
+        if let (Ok(otra_inicio), Ok(otra_fin)) = (otra_inicio, otra_fin)
+            && otra_inicio <= fin
+            && inicio <= otra_fin
+        {
+            errores.push(ErrorCampo::new(
+                "fecha_inicio",
+                format!(
+                    "Se traslapa con la solicitud {} del {} al {} ({})",
+                    otra.id, otra.fecha_inicio, otra.fecha_fin, otra.estado
+                ),
+            ));
+        }
+    }
+
+    errores
+}
+
+/// Cambios de saldo al sumar (`signo` 1) o restar (`signo` -1) el consumo de cada periodo
+fn cambios_de_saldo(
+    numeros: &[i32],
+    anteriores: &[(Option<Dias>, Dias)],
+    consumo: &[ConsumoPeriodo],
+    signo: i32,
+) -> Vec<CambioSaldo> {
+    consumo
+        .iter()
+        .filter_map(|c| {
+            let i = numeros.iter().position(|&n| n == c.periodo)?;
+            let (anterior, tomados) = anteriores[i];
+            Some(CambioSaldo {
+                periodo: c.periodo,
+                anterior,
+                nuevo: (tomados + c.dias * signo).max(Dias::CERO),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::db::MemoryStore;
+    use crate::models::{Jornada, MedioDia, TipoPermiso};
+    use crate::services::EmpleadoService;
+    use chrono::{Duration, Months};
+
+    pub(crate) fn nueva_desde(empleado_id: &str, inicio: NaiveDate) -> NuevaSolicitud {
+        NuevaSolicitud {
+            empleado_id: empleado_id.to_string(),
+            fecha_inicio: inicio.to_string(),
+            fecha_fin: (inicio + Duration::days(4)).to_string(),
+            tipo_permiso: TipoPermiso::Vacaciones,
+            documento: None,
+            jornada: Jornada::default(),
+        }
+    }
+
+    // Empieza hoy, así sus días se imputan al periodo de servicio en curso
+    pub(crate) fn nueva(empleado_id: &str) -> NuevaSolicitud {
+        nueva_desde(empleado_id, Utc::now().date_naive())
+    }
+
+    fn solicitud(id: &str, inicio: &str, fin: &str, estado: &str) -> SolicitudVacaciones {
+        SolicitudVacaciones {
+            id: id.to_string(),
+            empleado_id: "1".to_string(),
+            empleado_nombre: "Test".to_string(),
+            departamento: "Sistemas".to_string(),
+            tipo_permiso: TipoPermiso::Vacaciones,
+            documento: None,
+            fecha_inicio: inicio.to_string(),
+            fecha_fin: fin.to_string(),
+            jornada: Jornada::default(),
+            estado: estado.parse().unwrap(),
+            dias_solicitados: Dias::enteros(3),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            version: 0,
+            consumo: Vec::new(),
+            reprograma: None,
+            resuelto_por: None,
+            resuelto_at: None,
+            comentario: None,
+            pasos: Vec::new(),
+            aprobaciones: Vec::new(),
+            historial: Vec::new(),
+        }
+    }
+
+    fn campos(errores: &[ErrorCampo]) -> Vec<&str> {
+        errores.iter().map(|e| e.campo.as_str()).collect()
+    }
+
+    #[test]
+    fn test_validar_campos() {
+        let hoy = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let otras = [
+            solicitud("aprobada", "2025-03-10", "2025-03-14", "aprobada"),
+            solicitud("rechazada", "2025-03-17", "2025-03-21", "rechazada"),
+        ];
+
+        let valida = solicitud("nueva", "2025-03-17", "2025-03-19", "pendiente");
+        assert!(validar_campos(&valida, &otras, hoy).is_empty());
+
+        // Traslape con una aprobada (los extremos cuentan); la misma solicitud no se compara
+        let traslapada = solicitud("nueva", "2025-03-14", "2025-03-18", "pendiente");
+        assert_eq!(
+            campos(&validar_campos(&traslapada, &otras, hoy)),
+            ["fecha_inicio"]
+        );
+        assert!(validar_campos(&otras[0], &otras, hoy).is_empty());
+
+        let pasada = SolicitudVacaciones {
+            dias_solicitados: Dias::CERO,
+            ..solicitud("nueva", "2025-02-28", "2025-02-28", "pendiente")
+        };
+        assert_eq!(
+            campos(&validar_campos(&pasada, &otras, hoy)),
+            ["fecha_inicio", "dias_solicitados"]
+        );
+
+        let invertida = solicitud("nueva", "2025-03-19", "2025-03-17", "pendiente");
+        assert_eq!(
+            campos(&validar_campos(&invertida, &otras, hoy)),
+            ["fecha_fin"]
+        );
+        let sin_formato = solicitud("nueva", "17/03/2025", "", "pendiente");
+        assert_eq!(
+            campos(&validar_campos(&sin_formato, &otras, hoy)),
+            ["fecha_inicio", "fecha_fin"]
+        );
+
+        let enorme = solicitud("nueva", "2025-04-01", "9999-12-31", "pendiente");
+        assert_eq!(campos(&validar_campos(&enorme, &otras, hoy)), ["fecha_fin"]);
+    }
+
+    #[tokio::test]
+    async fn test_aprobacion_con_conflictos() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+
+        // Más días de los que corresponden por ley: se rechaza al crearla
+        match solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(100), "2")
+            .await
+        {
+            Err(AppError::Validation(errores)) => {
+                assert_eq!(errores.len(), 1);
+                assert_eq!(errores[0].campo, "dias_solicitados");
+            }
+            otro => panic!("se esperaba un error de validación: {:?}", otro),
+        }
+
+        // Dos pendientes que caben por separado pero no juntas: la segunda falla al aprobarse
+        // (un mes después del próximo aniversario se pueden usar dos periodos completos)
+        let siguiente = juan.periodo_de(Utc::now().date_naive()) + 1;
+        let inicio = juan.fecha_aniversario(siguiente) + Months::new(1);
+        let disponibles = Dias::enteros(
+            juan.dias_derecho(siguiente - 1, &[]) + juan.dias_derecho(siguiente, &[]),
+        );
+        let primera = solicitudes
+            .crear_solicitud(
+                &juan,
+                nueva_desde("2", inicio),
+                disponibles - Dias::enteros(1),
+                "2",
+            )
+            .await
+            .unwrap();
+        let segunda = solicitudes
+            .crear_solicitud(
+                &juan,
+                nueva_desde("2", inicio + Duration::days(7)),
+                Dias::enteros(2),
+                "2",
+            )
+            .await
+            .unwrap();
+        solicitudes
+            .actualizar_estado("2", &primera.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            solicitudes
+                .actualizar_estado("2", &segunda.id, EstadoSolicitud::Aprobada, "1", None)
+                .await,
+            Err(AppError::Validation(_))
+        ));
+        solicitudes
+            .actualizar_estado(
+                "2",
+                &primera.id,
+                EstadoSolicitud::Revocada,
+                "1",
+                Some("Cambio de planes".to_string()),
             )
-            .send()
             .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            .unwrap();
 
-        let item = result
-            .item()
-            .ok_or_else(|| AppError::NotFound("Solicitud no encontrada".to_string()))?;
+        // No se puede crear otra que se traslape con una pendiente
+        let creada = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(3), "2")
+            .await
+            .unwrap();
+        match solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(3), "2")
+            .await
+        {
+            Err(AppError::Validation(errores)) => {
+                assert_eq!(errores[0].campo, "fecha_inicio");
+            }
+            otro => panic!("se esperaba un traslape: {:?}", otro),
+        }
 
-        let mut solicitud = SolicitudVacaciones::from_item(item)
-            .ok_or_else(|| AppError::InternalError("Error al parsear solicitud".to_string()))?;
+        let aprobada = solicitudes
+            .actualizar_estado("2", &creada.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+        assert_eq!(aprobada.version, 1);
 
-        // Actualizar el estado
-        solicitud.estado = nuevo_estado.to_string();
+        // Aprobar de nuevo o rechazar con la versión leída antes de aprobar
+        assert!(matches!(
+            solicitudes
+                .actualizar_estado("2", &creada.id, EstadoSolicitud::Aprobada, "1", None)
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+        let obsoleta = SolicitudVacaciones {
+            estado: EstadoSolicitud::Rechazada,
+            version: 1,
+            ..creada
+        };
+        assert!(matches!(
+            db.cambiar_estado_solicitud(&obsoleta, EstadoSolicitud::Pendiente, &[])
+                .await,
+            Err(AppError::Conflict(_))
+        ));
 
-        // Guardar en DynamoDB
-        self.db
-            .client
-            .put_item()
-            .table_name(&self.db.table_name)
-            .set_item(Some(solicitud.to_item()))
-            .send()
+        // Rechazar una aprobada devuelve sus días al saldo
+        solicitudes
+            .actualizar_estado(
+                "2",
+                &obsoleta.id,
+                EstadoSolicitud::Revocada,
+                "1",
+                Some("Cambio de planes".to_string()),
+            )
             .await
-            .map_err(|e| AppError::DatabaseError(e.to_string()))?;
+            .unwrap();
+        let periodo = aprobada.consumo[0].periodo;
+        assert_eq!(
+            db.obtener_saldo("2", periodo).await.unwrap(),
+            Some(Dias::CERO)
+        );
+    }
 
-        Ok(solicitud)
+    #[tokio::test]
+    async fn test_consumo_fifo_entre_periodos() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+
+        // Un mes después del próximo aniversario aún se pueden usar los días del periodo
+        // en curso (vencen 18 meses después de su inicio) además de los del siguiente
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+        let actual = juan.periodo_de(Utc::now().date_naive());
+        let siguiente = actual + 1;
+        let derecho_actual = Dias::enteros(juan.dias_derecho(actual, &[]));
+        let derecho_siguiente = Dias::enteros(juan.dias_derecho(siguiente, &[]));
+        let inicio = juan.fecha_aniversario(siguiente) + Months::new(1);
+
+        let creada = solicitudes
+            .crear_solicitud(
+                &juan,
+                nueva_desde("2", inicio),
+                derecho_actual + Dias::enteros(3),
+                "2",
+            )
+            .await
+            .unwrap();
+        let aprobada = solicitudes
+            .actualizar_estado("2", &creada.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            aprobada.consumo,
+            vec![
+                ConsumoPeriodo {
+                    periodo: actual,
+                    dias: derecho_actual
+                },
+                ConsumoPeriodo {
+                    periodo: siguiente,
+                    dias: Dias::enteros(3)
+                },
+            ]
+        );
+        assert_eq!(
+            db.obtener_saldo("2", actual).await.unwrap(),
+            Some(derecho_actual)
+        );
+        assert_eq!(
+            db.obtener_saldo("2", siguiente).await.unwrap(),
+            Some(Dias::enteros(3))
+        );
+
+        // Al vencer el periodo en curso solo queda lo que falta del siguiente
+        let vencido = juan.vencimiento_periodo(actual) + Duration::days(1);
+        match solicitudes
+            .crear_solicitud(
+                &juan,
+                nueva_desde("2", vencido),
+                derecho_siguiente - Dias::enteros(2),
+                "2",
+            )
+            .await
+        {
+            Err(AppError::Validation(errores)) => assert_eq!(
+                errores,
+                vec![ErrorCampo::new(
+                    "dias_solicitados",
+                    format!(
+                        "Días insuficientes: el empleado tiene {} disponibles al {} y la solicitud requiere {}",
+                        derecho_siguiente - Dias::enteros(3),
+                        vencido,
+                        derecho_siguiente - Dias::enteros(2)
+                    )
+                )]
+            ),
+            otro => panic!("se esperaba un error de validación: {:?}", otro),
+        }
+
+        // Rechazar la solicitud devuelve los días a cada periodo
+        let rechazada = solicitudes
+            .actualizar_estado(
+                "2",
+                &creada.id,
+                EstadoSolicitud::Revocada,
+                "1",
+                Some("Cambio de planes".to_string()),
+            )
+            .await
+            .unwrap();
+        assert!(rechazada.consumo.is_empty());
+        assert_eq!(
+            db.obtener_saldo("2", actual).await.unwrap(),
+            Some(Dias::CERO)
+        );
+        assert_eq!(
+            db.obtener_saldo("2", siguiente).await.unwrap(),
+            Some(Dias::CERO)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transiciones_e_historial() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+
+        // Una rechazada ya no se puede aprobar
+        let creada = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(3), "2")
+            .await
+            .unwrap();
+        let rechazada = solicitudes
+            .actualizar_estado(
+                "2",
+                &creada.id,
+                EstadoSolicitud::Rechazada,
+                "1",
+                Some("Cierre contable".to_string()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(rechazada.resuelto_por.as_deref(), Some("1"));
+        assert_eq!(rechazada.comentario.as_deref(), Some("Cierre contable"));
+        assert_eq!(
+            rechazada.resuelto_at.as_ref(),
+            Some(&rechazada.historial[1].fecha)
+        );
+        assert!(matches!(
+            solicitudes
+                .actualizar_estado("2", &creada.id, EstadoSolicitud::Aprobada, "1", None)
+                .await,
+            Err(AppError::Conflict(_))
+        ));
+
+        // El historial registra quién hizo cada cambio y por qué
+        let otra = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(3), "2")
+            .await
+            .unwrap();
+        solicitudes
+            .actualizar_estado("2", &otra.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+        let revocada = solicitudes
+            .actualizar_estado(
+                "2",
+                &otra.id,
+                EstadoSolicitud::Revocada,
+                "1",
+                Some("Cierre de trimestre".to_string()),
+            )
+            .await
+            .unwrap();
+        let pasos: Vec<_> = revocada
+            .historial
+            .iter()
+            .map(|c| (c.anterior, c.estado, c.actor.as_str(), c.motivo.as_deref()))
+            .collect();
+        assert_eq!(
+            pasos,
+            [
+                (None, EstadoSolicitud::Pendiente, "2", None),
+                (
+                    Some(EstadoSolicitud::Pendiente),
+                    EstadoSolicitud::Aprobada,
+                    "1",
+                    None
+                ),
+                (
+                    Some(EstadoSolicitud::Aprobada),
+                    EstadoSolicitud::Revocada,
+                    "1",
+                    Some("Cierre de trimestre")
+                ),
+            ]
+        );
+        assert!(revocada.consumo.is_empty());
+
+        // Las pendientes cuya fecha de inicio ya pasó expiran
+        let hoy = Utc::now().date_naive();
+        let pendiente = solicitudes
+            .crear_solicitud(
+                &juan,
+                nueva_desde("2", hoy + Duration::days(7)),
+                Dias::enteros(3),
+                "2",
+            )
+            .await
+            .unwrap();
+        assert_eq!(solicitudes.expirar_pendientes(hoy).await.unwrap(), 0);
+        assert_eq!(
+            solicitudes
+                .expirar_pendientes(hoy + Duration::days(8))
+                .await
+                .unwrap(),
+            1
+        );
+        let expirada = db
+            .obtener_solicitud("2", &pendiente.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expirada.estado, EstadoSolicitud::Expirada);
+        assert_eq!(expirada.historial.last().unwrap().actor, ACTOR_SISTEMA);
+    }
+
+    #[tokio::test]
+    async fn test_cancelar_y_reprogramar() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+
+        // Una aprobada que ya empezó no se puede cancelar
+        let en_curso = solicitudes
+            .crear_solicitud(&juan, nueva("2"), Dias::enteros(1), "2")
+            .await
+            .unwrap();
+        solicitudes
+            .actualizar_estado("2", &en_curso.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            solicitudes.cancelar("2", &en_curso.id, "2", None).await,
+            Err(AppError::Validation(_))
+        ));
+        solicitudes
+            .actualizar_estado("2", &en_curso.id, EstadoSolicitud::Revocada, "1", None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            solicitudes.cancelar("2", &en_curso.id, "2", None).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        // Todos los días disponibles, aprobados: reprogramar a fechas que se traslapan solo es
+        // posible porque la original deja de contar
+        let siguiente = juan.periodo_de(Utc::now().date_naive()) + 1;
+        let inicio = juan.fecha_aniversario(siguiente) + Months::new(1);
+        let disponibles = Dias::enteros(
+            juan.dias_derecho(siguiente - 1, &[]) + juan.dias_derecho(siguiente, &[]),
+        );
+        let original = solicitudes
+            .crear_solicitud(&juan, nueva_desde("2", inicio), disponibles, "2")
+            .await
+            .unwrap();
+        solicitudes
+            .actualizar_estado("2", &original.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+
+        let reprogramada = solicitudes
+            .reprogramar(
+                &juan,
+                &original.id,
+                nueva_desde("2", inicio + Duration::days(2)),
+                disponibles,
+                "2",
+            )
+            .await
+            .unwrap();
+        assert_eq!(reprogramada.estado, EstadoSolicitud::Pendiente);
+        assert_eq!(
+            reprogramada.reprograma.as_deref(),
+            Some(original.id.as_str())
+        );
+
+        let cancelada = db
+            .obtener_solicitud("2", &original.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cancelada.estado, EstadoSolicitud::Cancelada);
+        assert_eq!(
+            cancelada.historial.last().unwrap().motivo,
+            Some(format!("Reprogramada como {}", reprogramada.id))
+        );
+        assert_eq!(
+            db.obtener_saldo("2", siguiente).await.unwrap(),
+            Some(Dias::CERO)
+        );
+
+        // Si la nueva versión no es válida la original no cambia
+        assert!(matches!(
+            solicitudes
+                .reprogramar(
+                    &juan,
+                    &reprogramada.id,
+                    nueva_desde("2", inicio),
+                    disponibles + Dias::enteros(1),
+                    "2"
+                )
+                .await,
+            Err(AppError::Validation(_))
+        ));
+        let sigue = db
+            .obtener_solicitud("2", &reprogramada.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sigue.estado, EstadoSolicitud::Pendiente);
+
+        let cancelada = solicitudes
+            .cancelar("2", &reprogramada.id, "2", None)
+            .await
+            .unwrap();
+        assert_eq!(cancelada.estado, EstadoSolicitud::Cancelada);
+    }
+
+    #[tokio::test]
+    async fn test_tipos_de_permiso() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+        let empleados_service = EmpleadoService::new(db.clone());
+        let antes = empleados_service
+            .obtener_empleado_con_dias("2")
+            .await
+            .unwrap();
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+        let permiso = |tipo, inicio, documento: Option<&str>| NuevaSolicitud {
+            tipo_permiso: tipo,
+            documento: documento.map(str::to_string),
+            ..nueva_desde("2", inicio)
+        };
+
+        // La incapacidad exige el folio, admite fechas pasadas y queda aprobada sin tocar
+        // el saldo de vacaciones
+        let hace_tres_dias = Utc::now().date_naive() - Duration::days(3);
+        assert!(matches!(
+            solicitudes
+                .crear_solicitud(&juan, permiso(TipoPermiso::IncapacidadImss, hace_tres_dias, None), Dias::enteros(5), "2")
+                .await,
+            Err(AppError::Validation(errores)) if errores.iter().any(|e| e.campo == "documento")
+        ));
+        let incapacidad = solicitudes
+            .crear_solicitud(
+                &juan,
+                permiso(
+                    TipoPermiso::IncapacidadImss,
+                    hace_tres_dias,
+                    Some(" IMSS-123 "),
+                ),
+                Dias::enteros(5),
+                "2",
+            )
+            .await
+            .unwrap();
+        assert_eq!(incapacidad.estado, EstadoSolicitud::Aprobada);
+        assert_eq!(incapacidad.documento.as_deref(), Some("IMSS-123"));
+        assert_eq!(incapacidad.historial.last().unwrap().actor, ACTOR_SISTEMA);
+        assert!(incapacidad.consumo.is_empty());
+        let despues = empleados_service
+            .obtener_empleado_con_dias("2")
+            .await
+            .unwrap();
+        assert_eq!(despues.dias_disponibles, antes.dias_disponibles);
+
+        // Paternidad: hasta cinco días por solicitud
+        let ano = Utc::now().year() + 1;
+        let febrero = NaiveDate::from_ymd_opt(ano, 2, 2).unwrap();
+        assert!(matches!(
+            solicitudes
+                .crear_solicitud(
+                    &juan,
+                    permiso(TipoPermiso::Paternidad, febrero, Some("Acta 1")),
+                    Dias::enteros(6),
+                    "2"
+                )
+                .await,
+            Err(AppError::Validation(_))
+        ));
+        let paternidad = solicitudes
+            .crear_solicitud(
+                &juan,
+                permiso(TipoPermiso::Paternidad, febrero, Some("Acta 1")),
+                Dias::enteros(5),
+                "2",
+            )
+            .await
+            .unwrap();
+        assert_eq!(paternidad.estado, EstadoSolicitud::Pendiente);
+
+        // Días personales: dos por año calendario, contando solo los aprobados
+        let marzo = NaiveDate::from_ymd_opt(ano, 3, 2).unwrap();
+        let personal = solicitudes
+            .crear_solicitud(
+                &juan,
+                permiso(TipoPermiso::DiaPersonal, marzo, None),
+                Dias::enteros(2),
+                "2",
+            )
+            .await
+            .unwrap();
+        solicitudes
+            .actualizar_estado("2", &personal.id, EstadoSolicitud::Aprobada, "1", None)
+            .await
+            .unwrap();
+        let abril = NaiveDate::from_ymd_opt(ano, 4, 6).unwrap();
+        assert!(matches!(
+            solicitudes
+                .crear_solicitud(
+                    &juan,
+                    permiso(TipoPermiso::DiaPersonal, abril, None),
+                    Dias::enteros(1),
+                    "2"
+                )
+                .await,
+            Err(AppError::Validation(_))
+        ));
+
+        let saldos = empleados_service
+            .listar_saldos_permisos("2", ano)
+            .await
+            .unwrap();
+        let saldo = |tipo| saldos.iter().find(|s| s.tipo == tipo).unwrap();
+        assert_eq!(saldo(TipoPermiso::DiaPersonal).usados, Dias::enteros(2));
+        assert_eq!(
+            saldo(TipoPermiso::DiaPersonal).disponibles,
+            Some(Dias::CERO)
+        );
+        assert_eq!(saldo(TipoPermiso::Paternidad).pendientes, Dias::enteros(5));
+        assert_eq!(
+            saldo(TipoPermiso::Vacaciones).disponibles,
+            antes.dias_disponibles
+        );
+    }
+
+    #[tokio::test]
+    async fn test_medios_dias_y_horas() {
+        let db = MemoryStore::demo();
+        let solicitudes = SolicitudService::new(db.clone());
+        let empleados_service = EmpleadoService::new(db.clone());
+        let antes = empleados_service
+            .obtener_empleado_con_dias("2")
+            .await
+            .unwrap();
+        let juan = db.obtener_empleado("2").await.unwrap().unwrap();
+
+        let un_dia = |inicio: NaiveDate, jornada| NuevaSolicitud {
+            fecha_fin: inicio.to_string(),
+            jornada,
+            ..nueva_desde("2", inicio)
+        };
+        let hoy = Utc::now().date_naive();
+        let manana = Jornada {
+            medio_dia_inicio: Some(MedioDia::Manana),
+            ..Jornada::default()
+        };
+        let tres_horas = Jornada {
+            horas: Some(3),
+            ..Jornada::default()
+        };
+        for (nueva, dias) in [
+            (un_dia(hoy, manana), Dias::MEDIO),
+            (un_dia(hoy + Duration::days(7), tres_horas), Dias::horas(3)),
+        ] {
+            let creada = solicitudes
+                .crear_solicitud(&juan, nueva, dias, "2")
+                .await
+                .unwrap();
+            let aprobada = solicitudes
+                .actualizar_estado("2", &creada.id, EstadoSolicitud::Aprobada, "1", None)
+                .await
+                .unwrap();
+            assert_eq!(aprobada.consumo.iter().map(|c| c.dias).sum::<Dias>(), dias);
+        }
+
+        let empleado = empleados_service
+            .obtener_empleado_con_dias("2")
+            .await
+            .unwrap();
+        assert_eq!(
+            empleado.dias_disponibles,
+            antes
+                .dias_disponibles
+                .map(|d| d - Dias::desde_milesimos(875))
+        );
+
+        // Las horas son de un solo día
+        let varios_dias = NuevaSolicitud {
+            jornada: tres_horas,
+            ..nueva_desde("2", hoy + Duration::days(14))
+        };
+        assert!(matches!(
+            solicitudes.crear_solicitud(&juan, varios_dias, Dias::horas(3), "2").await,
+            Err(AppError::Validation(errores)) if errores.iter().any(|e| e.campo == "horas")
+        ));
     }
 }
@@ -1,123 +1,136 @@
-use aws_sdk_dynamodb::types::AttributeValue;
-use std::collections::HashMap;
+use tower_sessions::Session;
 use tower_sessions_core::{
+    ExpiredDeletion, SessionStore,
     session::{Id, Record},
-    session_store, ExpiredDeletion, SessionStore,
+    session_store,
 };
 
-use crate::db::DynamoDBClient;
+use crate::db::Database;
+use crate::models::Sesion;
+
+/// Claves del record de la sesión
+pub const CLAVE_EMPLEADO_ID: &str = "empleado_id";
+pub const CLAVE_IP: &str = "ip";
+pub const CLAVE_USER_AGENT: &str = "user_agent";
+pub const CLAVE_INICIO: &str = "inicio"; // RFC 3339
+pub const CLAVE_ACTIVIDAD: &str = "ultima_actividad"; // Unix timestamp (segundos)
+
+/// Mínimo de segundos entre dos registros de actividad de la misma sesión,
+/// para no reescribirla en cada request
+pub const INTERVALO_ACTIVIDAD: i64 = 300;
+
+/// Vincula la sesión al empleado tras el login y guarda los datos del dispositivo.
+/// Cambia el ID de la sesión para que no se pueda reutilizar uno anterior al login.
+pub async fn iniciar(
+    session: &Session,
+    empleado_id: &str,
+    ip: Option<String>,
+    user_agent: Option<String>,
+) -> Result<(), tower_sessions::session::Error> {
+    let ahora = chrono::Utc::now();
+    session.cycle_id().await?;
+    session.insert(CLAVE_EMPLEADO_ID, empleado_id).await?;
+    session.insert(CLAVE_INICIO, ahora.to_rfc3339()).await?;
+    session.insert(CLAVE_ACTIVIDAD, ahora.timestamp()).await?;
+    session.insert(CLAVE_IP, ip).await?;
+    session.insert(CLAVE_USER_AGENT, user_agent).await?;
+    Ok(())
+}
+
+/// Registra actividad si pasó más de `INTERVALO_ACTIVIDAD` desde la última vez;
+/// al modificarse, la sesión se guarda y también se renueva su expiración
+pub async fn registrar_actividad(session: &Session) -> Result<(), tower_sessions::session::Error> {
+    let ahora = chrono::Utc::now().timestamp();
+    let ultima: Option<i64> = session.get(CLAVE_ACTIVIDAD).await?;
+    if ultima.is_none_or(|t| ahora - t >= INTERVALO_ACTIVIDAD) {
+        session.insert(CLAVE_ACTIVIDAD, ahora).await?;
+    }
+    Ok(())
+}
 
+/// Session store de tower-sessions sobre el backend de almacenamiento configurado
 #[derive(Clone, Debug)]
-pub struct DynamoDBSessionStore {
-    db: DynamoDBClient,
+pub struct DatabaseSessionStore {
+    db: Database,
 }
 
-impl DynamoDBSessionStore {
-    pub fn new(db: DynamoDBClient) -> Self {
+impl DatabaseSessionStore {
+    pub fn new(db: Database) -> Self {
         Self { db }
     }
 
-    fn session_to_item(&self, id: &Id, record: &Record) -> HashMap<String, AttributeValue> {
-        let session_id = id.to_string();
-        let mut item = HashMap::new();
-
-        item.insert(
-            "PK".to_string(),
-            AttributeValue::S(format!("SESSION#{}", session_id)),
-        );
-        item.insert("SK".to_string(), AttributeValue::S("METADATA".to_string()));
-        item.insert("tipo".to_string(), AttributeValue::S("session".to_string()));
-        item.insert("session_id".to_string(), AttributeValue::S(session_id));
-
+    fn record_to_sesion(&self, record: &Record) -> session_store::Result<Sesion> {
         // Serializar el record completo
-        if let Ok(data) = serde_json::to_string(record) {
-            item.insert("data".to_string(), AttributeValue::S(data));
-        }
-
-        // Agregar expiry time
-        item.insert(
-            "expires_at".to_string(),
-            AttributeValue::N(record.expiry_date.unix_timestamp().to_string()),
-        );
-
-        item.insert(
-            "created_at".to_string(),
-            AttributeValue::S(chrono::Utc::now().to_rfc3339()),
-        );
-
-        item
+        let data = serde_json::to_string(record)
+            .map_err(|e| session_store::Error::Encode(e.to_string()))?;
+
+        // Copiar los datos del dispositivo a columnas propias para poder listarlas
+        let texto = |clave: &str| {
+            record
+                .data
+                .get(clave)
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        };
+        let ahora = chrono::Utc::now();
+        let ultima_actividad = record
+            .data
+            .get(CLAVE_ACTIVIDAD)
+            .and_then(|v| v.as_i64())
+            .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+            .unwrap_or(ahora);
+
+        Ok(Sesion {
+            session_id: record.id.to_string(),
+            data,
+            expires_at: record.expiry_date.unix_timestamp(),
+            created_at: texto(CLAVE_INICIO).unwrap_or_else(|| ahora.to_rfc3339()),
+            empleado_id: texto(CLAVE_EMPLEADO_ID),
+            ultima_actividad: Some(ultima_actividad.to_rfc3339()),
+            ip: texto(CLAVE_IP),
+            user_agent: texto(CLAVE_USER_AGENT),
+        })
     }
 
-    fn item_to_record(&self, item: HashMap<String, AttributeValue>) -> Option<Record> {
-        let data = item.get("data")?.as_s().ok()?;
-        serde_json::from_str(data).ok()
+    fn sesion_to_record(&self, sesion: &Sesion) -> Option<Record> {
+        serde_json::from_str(&sesion.data).ok()
     }
 }
 
 #[async_trait::async_trait]
-impl SessionStore for DynamoDBSessionStore {
+impl SessionStore for DatabaseSessionStore {
     async fn save(&self, record: &Record) -> session_store::Result<()> {
-        let item = self.session_to_item(&record.id, record);
+        let sesion = self.record_to_sesion(record)?;
 
         self.db
-            .client
-            .put_item()
-            .table_name(&self.db.table_name)
-            .set_item(Some(item))
-            .send()
+            .guardar_sesion(&sesion)
             .await
-            .map_err(|e| {
-                session_store::Error::Backend(format!("Failed to save session: {}", e))
-            })?;
+            .map_err(|e| session_store::Error::Backend(format!("Failed to save session: {}", e)))?;
 
         Ok(())
     }
 
     async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
-        let pk = format!("SESSION#{}", session_id);
-
-        let result = self
+        let sesion = self
             .db
-            .client
-            .get_item()
-            .table_name(&self.db.table_name)
-            .key("PK", AttributeValue::S(pk))
-            .key("SK", AttributeValue::S("METADATA".to_string()))
-            .send()
+            .obtener_sesion(&session_id.to_string())
             .await
-            .map_err(|e| {
-                session_store::Error::Backend(format!("Failed to load session: {}", e))
-            })?;
+            .map_err(|e| session_store::Error::Backend(format!("Failed to load session: {}", e)))?;
 
-        if let Some(item) = result.item {
-            // Verificar si la sesión ha expirado
-            if let Some(expires_at) = item.get("expires_at") {
-                if let Some(timestamp) = expires_at.as_n().ok().and_then(|n| n.parse::<i64>().ok()) {
-                    let now = chrono::Utc::now().timestamp();
-                    if timestamp < now {
-                        // Sesión expirada, eliminarla
-                        let _ = self.delete(session_id).await;
-                        return Ok(None);
-                    }
-                }
+        match sesion {
+            Some(sesion) if sesion.esta_expirada() => {
+                // Sesión expirada, eliminarla
+                let _ = self.delete(session_id).await;
+                Ok(None)
             }
-
-            Ok(self.item_to_record(item))
-        } else {
-            Ok(None)
+            Some(sesion) => Ok(self.sesion_to_record(&sesion)),
+            None => Ok(None),
         }
     }
 
     async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
-        let pk = format!("SESSION#{}", session_id);
-
         self.db
-            .client
-            .delete_item()
-            .table_name(&self.db.table_name)
-            .key("PK", AttributeValue::S(pk))
-            .key("SK", AttributeValue::S("METADATA".to_string()))
-            .send()
+            .eliminar_sesion(&session_id.to_string())
             .await
             .map_err(|e| {
                 session_store::Error::Backend(format!("Failed to delete session: {}", e))
@@ -128,10 +141,21 @@ impl SessionStore for DynamoDBSessionStore {
 }
 
 #[async_trait::async_trait]
-impl ExpiredDeletion for DynamoDBSessionStore {
+impl ExpiredDeletion for DatabaseSessionStore {
     async fn delete_expired(&self) -> session_store::Result<()> {
-        // DynamoDB TTL manejará esto automáticamente si está configurado
-        // Este método es principalmente para compatibilidad con el trait
+        // El TTL de DynamoDB también borra estas sesiones, pero puede tardar hasta días;
+        // en SQL y en memoria esta es la única limpieza
+        let eliminadas = self
+            .db
+            .eliminar_sesiones_expiradas(chrono::Utc::now().timestamp())
+            .await
+            .map_err(|e| {
+                session_store::Error::Backend(format!("Failed to delete expired sessions: {}", e))
+            })?;
+
+        if eliminadas > 0 {
+            tracing::info!("Sesiones expiradas eliminadas: {}", eliminadas);
+        }
         Ok(())
     }
 }